@@ -10,9 +10,9 @@ use std::{
 
 use common::{
     card::{
-        Attribute, Card, CardLimit, CardPassword, CardType, CombatStat, Header, LinkMarkers,
-        MonsterEffect, MonsterStats, MonsterType, Race, SpanKind, SpellType, TextBlock, TextPart,
-        TrapType,
+        Attribute, Card, CardLimit, CardPassword, CardType, CombatStat, EffectTags, Header,
+        LinkMarkers, MonsterEffect, MonsterStats, MonsterType, Race, ReleaseDate, SpanKind,
+        SpellType, TextBlock, TextPart, TrapType,
     },
     card_data::{CardData, Id},
     deck::DeckEntry,
@@ -248,23 +248,28 @@ fn main() {
         .field::<&str>("name")
         .field::<CardPassword>("password")
         .field::<&[TextPart<&str>]>("description")
+        .field::<&str>("search_name")
         .field::<&str>("search_text")
         .field::<CardType>("card_type")
-        .field::<CardLimit>("limit");
+        .field::<CardLimit>("limit")
+        .field::<Option<ReleaseDate>>("release_date")
+        .field::<EffectTags>("effect_tags");
 
     manager
         .check::<TextPart<String>>("TextPart<String>")
         .variant::<(SpanKind, String)>("Span")
         .variant::<TextBlock>("Block")
         .variant::<TextBlock>("EndBlock")
-        .variant::<Header>("Header");
+        .variant::<Header>("Header")
+        .variant::<()>("Separator");
 
     manager
         .check::<TextPart<&str>>("TextPart<&str>")
         .variant::<(SpanKind, &str)>("Span")
         .variant::<TextBlock>("Block")
         .variant::<TextBlock>("EndBlock")
-        .variant::<Header>("Header");
+        .variant::<Header>("Header")
+        .variant::<()>("Separator");
 
     manager
         .check::<CardType>("CardType")
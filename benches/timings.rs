@@ -1,12 +1,102 @@
 use codspeed_criterion_compat::{black_box, criterion_group, criterion_main, Criterion};
 use common::{
-    card_data::Id,
+    card::{
+        test_util::{make_card, make_extra_deck_card},
+        EffectTag, EffectTags,
+    },
+    card_data::{CardData, CardDataStorage, Id},
     deck::{Deck, PartType},
+    deck_part::DeckPart,
+    search::{self, Query},
+    ydk,
 };
 use criterion::Throughput;
 
 const STEPS: u64 = 10_000;
 
+/// Roughly the size of the current card pool.
+const CARD_COUNT: u32 = 13_000;
+
+const NAME_WORDS: &[&str] = &[
+    "Dark",
+    "Magician",
+    "Blue-Eyes",
+    "White",
+    "Dragon",
+    "Elemental",
+    "HERO",
+    "Cyber",
+    "Sky",
+    "Striker",
+    "Ash",
+    "Blossom",
+    "Joyous",
+    "Spring",
+    "Pot",
+    "of",
+    "Greed",
+    "Knight",
+    "Beast",
+    "Warrior",
+    "Shaddoll",
+    "Fusion",
+    "Synchro",
+    "Tuner",
+    "Résonance",
+    "Lord",
+    "Storm",
+];
+
+const TEXT_PHRASES: &[&str] = &[
+    "You can Special Summon this card from your hand.",
+    "If this card is Normal or Special Summoned:",
+    "add 1 monster from your Deck to your hand.",
+    "Destroy 1 card on the field.",
+    "You can only use this effect once per turn.",
+    "Negate the activation, and if you do, destroy that card.",
+    "Draw 2 cards.",
+    "Banish 1 card from your opponent's GY.",
+    "Target 1 monster in your GY; Special Summon it.",
+];
+
+/// Synthetic card pool with random names, texts and effect tags.
+///
+/// Every fourth card is an Extra deck monster.
+fn make_cards() -> CardData {
+    fastrand::seed(0);
+
+    let cards = (0..CARD_COUNT)
+        .map(|password| {
+            let mut card = if password % 4 == 0 {
+                make_extra_deck_card(password)
+            } else {
+                make_card(password)
+            };
+
+            let name_words = (0..fastrand::usize(1..5))
+                .map(|_| NAME_WORDS[fastrand::usize(..NAME_WORDS.len())])
+                .collect::<Vec<_>>();
+            card.name = name_words.join(" ");
+            card.search_name = search::fold(&card.name);
+
+            let phrases = (0..fastrand::usize(1..6))
+                .map(|_| TEXT_PHRASES[fastrand::usize(..TEXT_PHRASES.len())])
+                .collect::<Vec<_>>();
+            card.search_text = search::fold(&phrases.join(" "));
+
+            for tag in EffectTag::iter() {
+                if fastrand::u8(0..4) == 0 {
+                    card.effect_tags.add(tag);
+                }
+            }
+
+            card
+        })
+        .collect();
+
+    CardData::from(CardDataStorage::new(cards, vec![]))
+}
+
 pub fn deck(c: &mut Criterion) {
     let mut group = c.benchmark_group("deck");
     group.throughput(Throughput::Elements(STEPS));
@@ -44,5 +134,117 @@ pub fn deck(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, deck);
+pub fn search(c: &mut Criterion) {
+    let cards = make_cards();
+
+    let mut group = c.benchmark_group("search");
+    group.throughput(Throughput::Elements(CARD_COUNT.into()));
+
+    let mut tags = EffectTags::default();
+    tags.add(EffectTag::Search);
+    let queries = [
+        (
+            "name",
+            Query {
+                name: search::fold("Dragon"),
+                ..Query::default()
+            },
+        ),
+        (
+            "name substring",
+            Query {
+                name: search::fold("ragon"),
+                ..Query::default()
+            },
+        ),
+        (
+            "text",
+            Query {
+                text: search::fold("special summon this card"),
+                ..Query::default()
+            },
+        ),
+        (
+            "combined",
+            Query {
+                name: search::fold("dark"),
+                text: search::fold("once per turn"),
+                tags,
+                ..Query::default()
+            },
+        ),
+        (
+            "extra deck",
+            Query {
+                part: Some(DeckPart::Extra),
+                ..Query::default()
+            },
+        ),
+    ];
+
+    for (name, query) in queries {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(
+                    cards
+                        .entries()
+                        .filter(|(_, card)| query.matches(card))
+                        .count(),
+                );
+            });
+        });
+    }
+}
+
+/// Full sized deck of 60 Main, 15 Extra and 15 Side deck cards, spread over the card pool.
+fn make_full_deck(cards: &CardData) -> Deck {
+    let mut deck = Deck::default();
+
+    let mut main = cards
+        .entries()
+        .filter(|(_, card)| !card.card_type.is_extra_deck_monster())
+        .step_by(97);
+    let mut extra = cards
+        .entries()
+        .filter(|(_, card)| card.card_type.is_extra_deck_monster())
+        .step_by(89);
+
+    for (id, _) in main.by_ref().take(20) {
+        deck.increment(id, PartType::Playing, 3);
+    }
+    for (id, _) in extra.by_ref().take(15) {
+        deck.increment(id, PartType::Playing, 1);
+    }
+    for (id, _) in main.take(5) {
+        deck.increment(id, PartType::Side, 3);
+    }
+
+    deck
+}
+
+pub fn ydk(c: &mut Criterion) {
+    let cards = make_cards();
+    let deck = make_full_deck(&cards);
+
+    let mut text = Vec::new();
+    ydk::save(&deck, &cards, &mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+
+    let mut group = c.benchmark_group("ydk");
+    group.throughput(Throughput::Elements(90));
+
+    group.bench_function("load", |b| {
+        b.iter(|| black_box(ydk::load(black_box(&text), &cards).unwrap()));
+    });
+
+    group.bench_function("save", |b| {
+        b.iter(|| {
+            let mut result = Vec::with_capacity(text.len());
+            ydk::save(black_box(&deck), &cards, &mut result).unwrap();
+            black_box(result);
+        });
+    });
+}
+
+criterion_group!(benches, deck, search, ydk);
 criterion_main!(benches);
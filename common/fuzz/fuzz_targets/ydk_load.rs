@@ -0,0 +1,35 @@
+//! Arbitrary text given to the YDK import must never panic, only return an error.
+//!
+//! Run with `cargo fuzz run ydk_load` from the `common` directory.
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use common::{
+    card::test_util::{make_card, make_extra_deck_card},
+    card_data::{CardData, CardDataStorage},
+    ydk,
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Small card pool, so short passwords found by the fuzzer resolve to Main and Extra deck cards.
+fn cards() -> CardData {
+    static CARDS: OnceLock<CardData> = OnceLock::new();
+
+    *CARDS.get_or_init(|| {
+        let mut cards = (0..20).map(make_card).collect::<Vec<_>>();
+        cards.extend((20..30).map(make_extra_deck_card));
+        CardData::from(CardDataStorage::new(cards, vec![]))
+    })
+}
+
+fuzz_target!(|data: &str| {
+    let cards = cards();
+    if let Ok(deck) = ydk::load(data, &cards) {
+        // Loaded decks can always be exported again
+        let mut result = Vec::new();
+        ydk::save(&deck, &cards, &mut result).unwrap();
+        ydk::load(std::str::from_utf8(&result).unwrap(), &cards).unwrap();
+    }
+});
@@ -1,6 +1,10 @@
-use std::fmt::{Display, Write};
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 /// Full card data after extraction.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -9,9 +13,12 @@ pub struct FullCard {
     pub main_password: CardPassword,
     pub all_passwords: Vec<CardPassword>,
     pub description: Vec<TextPart<String>>,
+    pub search_name: String,
     pub search_text: String,
     pub card_type: CardType,
     pub limit: CardLimit,
+    pub release_date: Option<ReleaseDate>,
+    pub effect_tags: EffectTags,
 }
 
 /// Card data used in the app.
@@ -20,9 +27,20 @@ pub struct Card {
     pub name: &'static str,
     pub password: CardPassword,
     pub description: &'static [TextPart<&'static str>],
+    pub search_name: &'static str,
     pub search_text: &'static str,
     pub card_type: CardType,
     pub limit: CardLimit,
+    pub release_date: Option<ReleaseDate>,
+    pub effect_tags: EffectTags,
+}
+
+impl Card {
+    /// One line summary of the card type and stats, see [`CardType::summary`].
+    #[must_use]
+    pub fn summary(&self) -> String {
+        self.card_type.summary()
+    }
 }
 
 /// Type used for [Passwords](https://yugipedia.com/wiki/Password).
@@ -30,12 +48,61 @@ pub struct Card {
 /// Uses [`u32`] as it is the smallest integer type which can fit all eight-digit numbers.
 pub type CardPassword = u32;
 
+/// Error when parsing a card property from a name which does not belong to any value.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown name: {0:?}")]
+pub struct UnknownName(pub String);
+
+/// Names match ignoring case, with hyphens treated like spaces (e.g. `Creator-God`).
+fn names_match(lhs: &str, rhs: &str) -> bool {
+    let normalize = |c: char| {
+        if c == '-' {
+            ' '
+        } else {
+            c.to_ascii_lowercase()
+        }
+    };
+    lhs.chars().map(normalize).eq(rhs.chars().map(normalize))
+}
+
+/// Display the `name` of card properties, and parse them from any of the values in `iter`.
+macro_rules! impl_name_conversions {
+    ($($ty:ty),*) => {$(
+        impl Display for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = UnknownName;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::iter()
+                    .find(|value| names_match(value.name(), s))
+                    .ok_or_else(|| UnknownName(s.to_owned()))
+            }
+        }
+    )*};
+}
+
+impl_name_conversions!(
+    Race,
+    Attribute,
+    MonsterType,
+    MonsterEffect,
+    SpellType,
+    TrapType
+);
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub enum TextPart<T> {
     Span(SpanKind, T),
     Block(TextBlock),
     EndBlock(TextBlock),
     Header(Header),
+    /// Blank line grouping the blocks, e.g. between summoning condition and effects
+    Separator,
 }
 
 impl<T> TextPart<T> {
@@ -45,6 +112,7 @@ impl<T> TextPart<T> {
             Self::Block(block) => TextPart::Block(block),
             Self::EndBlock(block) => TextPart::EndBlock(block),
             Self::Header(header) => TextPart::Header(header),
+            Self::Separator => TextPart::Separator,
         }
     }
 }
@@ -52,12 +120,20 @@ impl<T> TextPart<T> {
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum SpanKind {
     Normal,
+    /// Quoted card name
+    CardName,
+    /// Game term like "Special Summon"
+    Keyword,
+    /// Emphasized text, like the condition of a list entry
+    Bold,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum TextBlock {
     Paragraph,
     List,
+    /// List of numbered conditions (`①`, `②`, ...)
+    OrderedList,
     ListEntry,
 }
 
@@ -84,19 +160,119 @@ pub enum CardType {
 impl CardType {
     #[must_use]
     pub fn is_extra_deck_monster(&self) -> bool {
+        self.is_link()
+            || matches!(
+                self.monster_type(),
+                Some(MonsterType::Fusion | MonsterType::Synchro | MonsterType::Xyz)
+            )
+    }
+
+    /// Summoning type of monsters with a level or rank, `None` for Link monsters and non-monsters.
+    #[must_use]
+    pub fn monster_type(&self) -> Option<MonsterType> {
+        match self {
+            CardType::Monster {
+                stats: MonsterStats::Normal { monster_type, .. },
+                ..
+            } => *monster_type,
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_link(&self) -> bool {
+        matches!(
+            self,
+            CardType::Monster {
+                stats: MonsterStats::Link { .. },
+                ..
+            }
+        )
+    }
+
+    #[must_use]
+    pub fn is_pendulum(&self) -> bool {
         matches!(
             self,
             CardType::Monster {
                 stats: MonsterStats::Normal {
-                    monster_type: Some(
-                        MonsterType::Fusion | MonsterType::Synchro | MonsterType::Xyz
-                    ),
+                    pendulum_scale: Some(_),
                     ..
-                } | MonsterStats::Link { .. },
+                },
                 ..
             }
         )
     }
+
+    #[must_use]
+    pub fn is_tuner(&self) -> bool {
+        matches!(self, CardType::Monster { is_tuner: true, .. })
+    }
+
+    /// One line summary, e.g. `DARK Fiend / Level 6 / 2400/1200 / Effect` or `Quick-Play Spell`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let (race, attribute, stats, effect, is_tuner) = match self {
+            CardType::Monster {
+                race,
+                attribute,
+                stats,
+                effect,
+                is_tuner,
+            } => (race, attribute, stats, effect, is_tuner),
+            CardType::Spell(SpellType::Normal) => return "Spell".to_owned(),
+            CardType::Spell(spell_type) => return format!("{spell_type} Spell"),
+            CardType::Trap(TrapType::Normal) => return "Trap".to_owned(),
+            CardType::Trap(trap_type) => return format!("{trap_type} Trap"),
+        };
+
+        let mut result = format!("{} {race} / ", attribute.name().to_uppercase());
+        let mut types = Vec::new();
+        match stats {
+            MonsterStats::Normal {
+                atk,
+                def,
+                level,
+                monster_type,
+                pendulum_scale,
+            } => {
+                let level_name = if *monster_type == Some(MonsterType::Xyz) {
+                    "Rank"
+                } else {
+                    "Level"
+                };
+                write!(result, "{level_name} {level} / {atk}/{def}").unwrap(/* Write for String should never fail */);
+                types.extend(monster_type.map(MonsterType::name));
+                if pendulum_scale.is_some() {
+                    types.push("Pendulum");
+                }
+            }
+            MonsterStats::Link {
+                atk, link_value, ..
+            } => {
+                write!(result, "Link {link_value} / {atk}").unwrap(/* Write for String should never fail */);
+                types.push("Link");
+            }
+        }
+        if *is_tuner {
+            types.push("Tuner");
+        }
+        types.push(effect.name());
+
+        write!(result, " / {}", types.join(" ")).unwrap(/* Write for String should never fail */);
+        result
+    }
+}
+
+/// Number of monsters to tribute for the Tribute Summon of a monster of `level`.
+#[must_use]
+pub fn tributes(level: u8) -> u8 {
+    // Level 0 monsters need no tributes, just like the low level ones
+    match level {
+        0..=4 => 0,
+        5..=6 => 1,
+        7.. => 2,
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
@@ -127,6 +303,75 @@ pub enum Race {
     WingedBeast,
     Wyrm,
     Zombie,
+    /// Race not known when the card data was built, not part of [`Race::iter`]
+    Unknown,
+}
+
+impl Race {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Self::Aqua,
+            Self::Beast,
+            Self::BeastWarrior,
+            Self::CreatorGod,
+            Self::Cyberse,
+            Self::Dinosaur,
+            Self::DivineBeast,
+            Self::Dragon,
+            Self::Fairy,
+            Self::Fiend,
+            Self::Fish,
+            Self::Illusion,
+            Self::Insect,
+            Self::Machine,
+            Self::Plant,
+            Self::Psychic,
+            Self::Pyro,
+            Self::Reptile,
+            Self::Rock,
+            Self::SeaSerpent,
+            Self::Spellcaster,
+            Self::Thunder,
+            Self::Warrior,
+            Self::WingedBeast,
+            Self::Wyrm,
+            Self::Zombie,
+        ]
+        .into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Aqua => "Aqua",
+            Self::Beast => "Beast",
+            Self::BeastWarrior => "Beast-Warrior",
+            Self::CreatorGod => "Creator God",
+            Self::Cyberse => "Cyberse",
+            Self::Dinosaur => "Dinosaur",
+            Self::DivineBeast => "Divine-Beast",
+            Self::Dragon => "Dragon",
+            Self::Fairy => "Fairy",
+            Self::Fiend => "Fiend",
+            Self::Fish => "Fish",
+            Self::Illusion => "Illusion",
+            Self::Insect => "Insect",
+            Self::Machine => "Machine",
+            Self::Plant => "Plant",
+            Self::Psychic => "Psychic",
+            Self::Pyro => "Pyro",
+            Self::Reptile => "Reptile",
+            Self::Rock => "Rock",
+            Self::SeaSerpent => "Sea Serpent",
+            Self::Spellcaster => "Spellcaster",
+            Self::Thunder => "Thunder",
+            Self::Warrior => "Warrior",
+            Self::WingedBeast => "Winged Beast",
+            Self::Wyrm => "Wyrm",
+            Self::Zombie => "Zombie",
+            Self::Unknown => "Unknown",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
@@ -138,6 +383,37 @@ pub enum Attribute {
     Water,
     Wind,
     Divine,
+    /// Attribute not known when the card data was built, not part of [`Attribute::iter`]
+    Unknown,
+}
+
+impl Attribute {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Self::Dark,
+            Self::Earth,
+            Self::Fire,
+            Self::Light,
+            Self::Water,
+            Self::Wind,
+            Self::Divine,
+        ]
+        .into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dark => "Dark",
+            Self::Earth => "Earth",
+            Self::Fire => "Fire",
+            Self::Light => "Light",
+            Self::Water => "Water",
+            Self::Wind => "Wind",
+            Self::Divine => "Divine",
+            Self::Unknown => "Unknown",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -176,15 +452,23 @@ impl CombatStat {
     pub fn questionmark() -> Self {
         Self(u16::MAX)
     }
+
+    /// Numeric value, or `None` for questionmark.
+    ///
+    /// Questionmark values are undefined until the card is on the field, so aggregates over stats
+    /// should count them separately instead of treating them as any number.
+    #[must_use]
+    pub fn value(self) -> Option<u16> {
+        (self.0 != u16::MAX).then_some(self.0)
+    }
 }
 
 impl Display for CombatStat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.0 == u16::MAX {
-            return f.write_char('?');
+        match self.value() {
+            Some(value) => write!(f, "{value}"),
+            None => f.write_char('?'),
         }
-
-        write!(f, "{}", self.0)
     }
 }
 
@@ -196,6 +480,22 @@ pub enum MonsterType {
     Xyz,
 }
 
+impl MonsterType {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [Self::Fusion, Self::Ritual, Self::Synchro, Self::Xyz].into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fusion => "Fusion",
+            Self::Ritual => "Ritual",
+            Self::Synchro => "Synchro",
+            Self::Xyz => "Xyz",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum MonsterEffect {
     Normal,
@@ -207,6 +507,34 @@ pub enum MonsterEffect {
     Flip,
 }
 
+impl MonsterEffect {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Self::Normal,
+            Self::Effect,
+            Self::Spirit,
+            Self::Toon,
+            Self::Union,
+            Self::Gemini,
+            Self::Flip,
+        ]
+        .into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Effect => "Effect",
+            Self::Spirit => "Spirit",
+            Self::Toon => "Toon",
+            Self::Union => "Union",
+            Self::Gemini => "Gemini",
+            Self::Flip => "Flip",
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct LinkMarkers(u8);
 
@@ -250,6 +578,79 @@ impl LinkMarkers {
         let idx = marker as u8;
         (self.0 & (1 << idx)) >> idx == 1
     }
+
+    /// Number of set markers, which should match the link value.
+    #[must_use]
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+/// Common effects, detected from the card text.
+#[derive(Default, Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub struct EffectTags(u16);
+
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectTag {
+    Destroy,
+    Negate,
+    Draw,
+    Search,
+    SpecialSummonFromGraveyard,
+    Banish,
+    /// Can be activated from the hand during the opponent's turn
+    HandTrap,
+    /// Removes multiple cards at once
+    BoardBreaker,
+    /// Special Summons itself from the hand
+    Extender,
+}
+
+impl EffectTag {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            EffectTag::Destroy,
+            EffectTag::Negate,
+            EffectTag::Draw,
+            EffectTag::Search,
+            EffectTag::SpecialSummonFromGraveyard,
+            EffectTag::Banish,
+            EffectTag::HandTrap,
+            EffectTag::BoardBreaker,
+            EffectTag::Extender,
+        ]
+        .into_iter()
+    }
+}
+
+impl EffectTags {
+    pub fn add(&mut self, tag: EffectTag) {
+        let idx = tag as u16;
+        self.0 |= 1 << idx;
+    }
+
+    pub fn remove(&mut self, tag: EffectTag) {
+        let idx = tag as u16;
+        self.0 &= !(1 << idx);
+    }
+
+    #[must_use]
+    pub fn has(&self, tag: EffectTag) -> bool {
+        let idx = tag as u16;
+        (self.0 & (1 << idx)) >> idx == 1
+    }
+
+    /// Check whether all tags of `other` are also present in `self`.
+    #[must_use]
+    pub fn has_all(&self, other: EffectTags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
@@ -262,6 +663,32 @@ pub enum SpellType {
     Ritual,
 }
 
+impl SpellType {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Self::Normal,
+            Self::Field,
+            Self::Equip,
+            Self::Continuous,
+            Self::QuickPlay,
+            Self::Ritual,
+        ]
+        .into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Field => "Field",
+            Self::Equip => "Equip",
+            Self::Continuous => "Continuous",
+            Self::QuickPlay => "Quick-Play",
+            Self::Ritual => "Ritual",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum TrapType {
     Normal,
@@ -269,6 +696,21 @@ pub enum TrapType {
     Counter,
 }
 
+impl TrapType {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [Self::Normal, Self::Continuous, Self::Counter].into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Continuous => "Continuous",
+            Self::Counter => "Counter",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum CardLimit {
     Unlimited,
@@ -289,6 +731,30 @@ impl CardLimit {
     }
 }
 
+/// Date of the first release of a card (in any region).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReleaseDate {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl ReleaseDate {
+    #[must_use]
+    pub fn new(year: u16, month: u8, day: u8) -> Self {
+        assert!((1..=12).contains(&month), "invalid month: {month}");
+        assert!((1..=31).contains(&day), "invalid day: {day}");
+
+        Self { year, month, day }
+    }
+}
+
+impl Display for ReleaseDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
 pub mod test_util {
     use super::*;
 
@@ -298,9 +764,12 @@ pub mod test_util {
             main_password: password,
             all_passwords: vec![password],
             description: vec![],
+            search_name: String::new(),
             search_text: String::new(),
             card_type: CardType::Spell(SpellType::Normal),
             limit: CardLimit::Unlimited,
+            release_date: None,
+            effect_tags: EffectTags::default(),
         }
     }
 
@@ -310,6 +779,7 @@ pub mod test_util {
             main_password: password,
             all_passwords: vec![password],
             description: vec![],
+            search_name: String::new(),
             search_text: String::new(),
             card_type: CardType::Monster {
                 race: Race::Aqua,
@@ -325,6 +795,222 @@ pub mod test_util {
                 is_tuner: false,
             },
             limit: CardLimit::Unlimited,
+            release_date: None,
+            effect_tags: EffectTags::default(),
         }
     }
+
+    /// Effect monster which is not a Pendulum monster, with the given level or rank and type.
+    pub fn make_monster(
+        password: CardPassword,
+        level: u8,
+        monster_type: Option<MonsterType>,
+    ) -> FullCard {
+        FullCard {
+            name: String::new(),
+            main_password: password,
+            all_passwords: vec![password],
+            description: vec![],
+            search_name: String::new(),
+            search_text: String::new(),
+            card_type: CardType::Monster {
+                race: Race::Aqua,
+                attribute: Attribute::Dark,
+                stats: MonsterStats::Normal {
+                    atk: CombatStat::new(0),
+                    def: CombatStat::new(0),
+                    level,
+                    monster_type,
+                    pendulum_scale: None,
+                },
+                effect: MonsterEffect::Effect,
+                is_tuner: false,
+            },
+            limit: CardLimit::Unlimited,
+            release_date: None,
+            effect_tags: EffectTags::default(),
+        }
+    }
+
+    /// Pendulum monster with the given type, e.g. [`MonsterType::Xyz`] for Pendulum Xyz monsters.
+    pub fn make_pendulum_card(
+        password: CardPassword,
+        monster_type: Option<MonsterType>,
+    ) -> FullCard {
+        FullCard {
+            name: String::new(),
+            main_password: password,
+            all_passwords: vec![password],
+            description: vec![],
+            search_name: String::new(),
+            search_text: String::new(),
+            card_type: CardType::Monster {
+                race: Race::Aqua,
+                attribute: Attribute::Dark,
+                stats: MonsterStats::Normal {
+                    atk: CombatStat::new(0),
+                    def: CombatStat::new(0),
+                    level: 4,
+                    monster_type,
+                    pendulum_scale: Some(1),
+                },
+                effect: MonsterEffect::Effect,
+                is_tuner: false,
+            },
+            limit: CardLimit::Unlimited,
+            release_date: None,
+            effect_tags: EffectTags::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{test_util::*, *};
+
+    #[test]
+    fn names() {
+        fn round_trip<T: Copy + Display + FromStr + PartialEq + std::fmt::Debug>(
+            values: impl Iterator<Item = T>,
+        ) {
+            for value in values {
+                assert_eq!(value.to_string().parse::<T>().ok(), Some(value));
+            }
+        }
+
+        round_trip(Race::iter());
+        round_trip(Attribute::iter());
+        round_trip(MonsterType::iter());
+        round_trip(MonsterEffect::iter());
+        round_trip(SpellType::iter());
+        round_trip(TrapType::iter());
+
+        // Spellings of the card database
+        assert_eq!("Creator-God".parse(), Ok(Race::CreatorGod));
+        assert_eq!("Sea Serpent".parse(), Ok(Race::SeaSerpent));
+        assert_eq!("DARK".parse(), Ok(Attribute::Dark));
+        assert_eq!("Quick-Play".parse(), Ok(SpellType::QuickPlay));
+        assert_eq!("XYZ".parse(), Ok(MonsterType::Xyz));
+
+        assert_eq!(Race::BeastWarrior.to_string(), "Beast-Warrior");
+        assert_eq!(
+            "Unknown".parse::<Race>(),
+            Err(UnknownName("Unknown".to_owned()))
+        );
+        assert!("Light ".parse::<Attribute>().is_err());
+    }
+
+    #[test]
+    fn effect_tags() {
+        let mut tags = EffectTags::default();
+        assert!(tags.is_empty());
+
+        // Every tag has its own bit
+        for tag in EffectTag::iter() {
+            tags.add(tag);
+        }
+        assert_eq!(tags.0.count_ones() as usize, EffectTag::iter().count());
+
+        tags.remove(EffectTag::Draw);
+        assert!(!tags.has(EffectTag::Draw));
+        assert!(tags.has(EffectTag::Extender));
+
+        let mut search = EffectTags::default();
+        search.add(EffectTag::Search);
+        assert!(tags.has_all(search));
+        search.add(EffectTag::Draw);
+        assert!(!tags.has_all(search));
+        assert!(tags.has_all(EffectTags::default()));
+    }
+
+    #[test]
+    fn summary() {
+        assert_eq!(make_card(1).card_type.summary(), "Spell");
+        assert_eq!(
+            make_extra_deck_card(2).card_type.summary(),
+            "DARK Aqua / Level 0 / 0/0 / Fusion Normal"
+        );
+        assert_eq!(
+            make_pendulum_card(3, Some(MonsterType::Xyz))
+                .card_type
+                .summary(),
+            "DARK Aqua / Rank 4 / 0/0 / Xyz Pendulum Effect"
+        );
+
+        let card_type = CardType::Monster {
+            race: Race::Fiend,
+            attribute: Attribute::Dark,
+            stats: MonsterStats::Link {
+                atk: CombatStat::new(2400),
+                link_value: 2,
+                link_markers: LinkMarkers::default(),
+            },
+            effect: MonsterEffect::Effect,
+            is_tuner: true,
+        };
+        assert_eq!(
+            card_type.summary(),
+            "DARK Fiend / Link 2 / 2400 / Link Tuner Effect"
+        );
+        assert_eq!(CardType::Trap(TrapType::Counter).summary(), "Counter Trap");
+    }
+
+    #[test]
+    fn predicates() {
+        let spell = make_card(1).card_type;
+        assert!(!spell.is_extra_deck_monster());
+        assert_eq!(spell.monster_type(), None);
+        assert!(!spell.is_link());
+        assert!(!spell.is_pendulum());
+        assert!(!spell.is_tuner());
+
+        let fusion = make_extra_deck_card(2).card_type;
+        assert!(fusion.is_extra_deck_monster());
+        assert_eq!(fusion.monster_type(), Some(MonsterType::Fusion));
+        assert!(!fusion.is_link());
+        assert!(!fusion.is_pendulum());
+
+        let pendulum = make_pendulum_card(3, None).card_type;
+        assert!(!pendulum.is_extra_deck_monster());
+        assert_eq!(pendulum.monster_type(), None);
+        assert!(pendulum.is_pendulum());
+
+        let pendulum_xyz = make_pendulum_card(4, Some(MonsterType::Xyz)).card_type;
+        assert!(pendulum_xyz.is_extra_deck_monster());
+        assert_eq!(pendulum_xyz.monster_type(), Some(MonsterType::Xyz));
+        assert!(pendulum_xyz.is_pendulum());
+
+        let link = CardType::Monster {
+            race: Race::Cyberse,
+            attribute: Attribute::Dark,
+            stats: MonsterStats::Link {
+                atk: CombatStat::new(2300),
+                link_value: 3,
+                link_markers: LinkMarkers::default(),
+            },
+            effect: MonsterEffect::Effect,
+            is_tuner: false,
+        };
+        assert!(link.is_extra_deck_monster());
+        assert_eq!(link.monster_type(), None);
+        assert!(link.is_link());
+        assert!(!link.is_pendulum());
+        assert!(!link.is_tuner());
+
+        let tuner = CardType::Monster {
+            race: Race::Machine,
+            attribute: Attribute::Earth,
+            stats: MonsterStats::Normal {
+                atk: CombatStat::new(1300),
+                def: CombatStat::new(500),
+                level: 3,
+                monster_type: None,
+                pendulum_scale: None,
+            },
+            effect: MonsterEffect::Effect,
+            is_tuner: true,
+        };
+        assert!(tuner.is_tuner());
+        assert!(!tuner.is_extra_deck_monster());
+    }
 }
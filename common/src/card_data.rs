@@ -1,13 +1,17 @@
 use std::ops::Index;
 
+use log::warn;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
-use crate::card::{Card, CardLimit, CardPassword, CardType, FullCard, TextPart};
+use crate::card::{
+    Card, CardLimit, CardPassword, CardType, EffectTags, FullCard, ReleaseDate, TextPart,
+};
 
 /// Internal id for cards.
 ///
-/// The mapping will change between builds, so it should not be used for storage.
+/// The mapping will change between builds, so it should not be used for storage. Persisted and
+/// shared data uses the [`CardPassword`] instead, see [`CardData::password_for_id`].
 ///
 /// Uses [`u16`], as it can comfortably map the current amount of cards (~13 000).
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -25,9 +29,12 @@ pub struct CardStorage {
     pub name: String,
     pub password: CardPassword,
     pub description: Vec<TextPart<String>>,
+    pub search_name: String,
     pub search_text: String,
     pub card_type: CardType,
     pub limit: CardLimit,
+    pub release_date: Option<ReleaseDate>,
+    pub effect_tags: EffectTags,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +42,8 @@ pub struct CardDataStorage {
     cards: Vec<CardStorage>,
     staples: Vec<Id>,
     passwords: FxHashMap<CardPassword, Id>,
+    /// Cards sharing the image of another card, see [`CardData::image_password`]
+    shared_images: FxHashMap<CardPassword, CardPassword>,
 }
 
 impl CardDataStorage {
@@ -56,24 +65,46 @@ impl CardDataStorage {
                 name: card.name,
                 password: card.main_password,
                 description: card.description,
+                search_name: card.search_name,
                 search_text: card.search_text,
                 card_type: card.card_type,
                 limit: card.limit,
+                release_date: card.release_date,
+                effect_tags: card.effect_tags,
             })
             .collect();
 
+        // The staple list is downloaded separately, so it might be slightly out of sync
         let staples = staples
             .into_iter()
-            .map(|password| passwords.get(&password).unwrap())
-            .copied()
+            .filter_map(|password| {
+                let id = passwords.get(&password).copied();
+                if id.is_none() {
+                    warn!("Skipping unknown staple password {password}");
+                }
+                id
+            })
             .collect();
 
         Self {
             cards,
             staples,
             passwords,
+            shared_images: FxHashMap::default(),
         }
     }
+
+    /// Set the cards which share an identical image with another card, which is only stored once.
+    ///
+    /// Maps the main password of each such card to the one under which the image is stored.
+    #[must_use]
+    pub fn with_shared_images(
+        mut self,
+        shared_images: impl IntoIterator<Item = (CardPassword, CardPassword)>,
+    ) -> Self {
+        self.shared_images = shared_images.into_iter().collect();
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -81,6 +112,7 @@ pub struct CardData {
     cards: &'static [Card],
     staples: &'static [Id],
     passwords: &'static FxHashMap<CardPassword, Id>,
+    shared_images: &'static FxHashMap<CardPassword, CardPassword>,
 }
 
 impl CardData {
@@ -99,10 +131,77 @@ impl CardData {
         self.staples.iter().copied()
     }
 
+    /// Number of cards in the data set.
+    #[must_use]
+    pub fn len(self) -> usize {
+        self.cards.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Number of staple cards in the data set.
+    #[must_use]
+    pub fn staples_len(self) -> usize {
+        self.staples.len()
+    }
+
+    /// Look up the id for a password, or `None` if the password is unknown.
+    ///
+    /// Ids are only created from the data set, so decoding cards by password never yields an id
+    /// which is out of range.
     #[must_use]
     pub fn id_for_password(self, password: CardPassword) -> Option<Id> {
         self.passwords.get(&password).copied()
     }
+
+    /// Main password of a card, the stable identifier to use when storing or sharing it.
+    #[must_use]
+    pub fn password_for_id(self, id: Id) -> CardPassword {
+        self.get(id).password
+    }
+
+    /// Password under which the image of a card is stored.
+    ///
+    /// This is the main password, unless the card shares an identical image with another card.
+    #[must_use]
+    pub fn image_password(self, id: Id) -> CardPassword {
+        let password = self.password_for_id(id);
+        self.shared_images
+            .get(&password)
+            .copied()
+            .unwrap_or(password)
+    }
+
+    /// Passwords of all printings of a card, starting with the main password.
+    #[must_use]
+    pub fn printings(self, id: Id) -> Vec<CardPassword> {
+        let main = self.password_for_id(id);
+        let mut alternatives = self
+            .passwords
+            .iter()
+            .filter(|(password, other)| **other == id && **password != main)
+            .map(|(password, _)| *password)
+            .collect::<Vec<_>>();
+        alternatives.sort_unstable();
+
+        let mut result = vec![main];
+        result.extend(alternatives);
+        result
+    }
+
+    /// Look up the ids for multiple passwords at once.
+    ///
+    /// The result has one entry per password, which is `None` if the password is unknown.
+    #[must_use]
+    pub fn ids_for_passwords(self, passwords: &[CardPassword]) -> Vec<Option<Id>> {
+        passwords
+            .iter()
+            .map(|&password| self.id_for_password(password))
+            .collect()
+    }
 }
 
 impl From<CardDataStorage> for CardData {
@@ -121,9 +220,12 @@ impl From<CardDataStorage> for CardData {
                     name: Box::leak(card.name.into_boxed_str()),
                     password: card.password,
                     description: Box::leak(description),
+                    search_name: Box::leak(card.search_name.into_boxed_str()),
                     search_text: Box::leak(card.search_text.into_boxed_str()),
                     card_type: card.card_type,
                     limit: card.limit,
+                    release_date: card.release_date,
+                    effect_tags: card.effect_tags,
                 }
             })
             .collect();
@@ -131,6 +233,7 @@ impl From<CardDataStorage> for CardData {
             cards: Box::leak(cards),
             staples: Box::leak(value.staples.into_boxed_slice()),
             passwords: Box::leak(Box::new(value.passwords)),
+            shared_images: Box::leak(Box::new(value.shared_images)),
         }
     }
 }
@@ -142,3 +245,92 @@ impl Index<Id> for CardData {
         self.get(index)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::card::test_util::make_card;
+
+    use super::*;
+
+    #[test]
+    fn unknown_staple() {
+        let cards = vec![make_card(1234), make_card(2345)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![9876, 2345]));
+
+        assert_eq!(data.staples().collect::<Vec<_>>(), &[Id::new(1)]);
+    }
+
+    #[test]
+    fn shared_images() {
+        let cards = vec![make_card(1234), make_card(2345)];
+        let data =
+            CardData::from(CardDataStorage::new(cards, vec![]).with_shared_images([(2345, 1234)]));
+
+        assert_eq!(data.image_password(Id::new(0)), 1234);
+        assert_eq!(data.image_password(Id::new(1)), 1234);
+        assert_eq!(data.password_for_id(Id::new(1)), 2345);
+    }
+
+    #[test]
+    fn unknown_passwords() {
+        let cards = vec![make_card(1234), make_card(2345)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        assert_eq!(data.id_for_password(2345), Some(Id::new(1)));
+        assert_eq!(data.id_for_password(9999), None);
+        assert_eq!(
+            data.ids_for_passwords(&[9999, 1234, 0]),
+            [None, Some(Id::new(0)), None]
+        );
+    }
+
+    #[test]
+    fn printings() {
+        let mut card = make_card(1234);
+        card.all_passwords = vec![1236, 1234, 1235];
+        let cards = vec![card, make_card(2345)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        assert_eq!(data.printings(Id::new(0)), &[1234, 1235, 1236]);
+        assert_eq!(data.printings(Id::new(1)), &[2345]);
+    }
+
+    #[test]
+    fn lengths() {
+        let cards = vec![make_card(1234), make_card(2345), make_card(3456)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![2345]));
+
+        assert_eq!(data.len(), 3);
+        assert!(!data.is_empty());
+        assert_eq!(data.staples_len(), 1);
+
+        let data = CardData::from(CardDataStorage::new(vec![], vec![]));
+        assert_eq!(data.len(), 0);
+        assert!(data.is_empty());
+        assert_eq!(data.staples_len(), 0);
+    }
+
+    #[test]
+    fn batch_password_lookup() {
+        let cards = vec![make_card(1234), make_card(2345)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        assert_eq!(
+            data.ids_for_passwords(&[2345, 9876, 1234]),
+            &[Some(Id::new(1)), None, Some(Id::new(0))]
+        );
+        assert!(data.ids_for_passwords(&[]).is_empty());
+    }
+
+    #[test]
+    fn password_round_trip() {
+        let mut card = make_card(1234);
+        card.all_passwords.push(1235);
+        let data = CardData::from(CardDataStorage::new(vec![make_card(2345), card], vec![]));
+
+        assert_eq!(data.password_for_id(Id::new(1)), 1234);
+        for (id, _) in data.entries() {
+            assert_eq!(data.id_for_password(data.password_for_id(id)), Some(id));
+        }
+    }
+}
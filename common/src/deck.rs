@@ -1,4 +1,21 @@
-use crate::{card_data::Id, deck_part::DeckPart};
+use thiserror::Error;
+
+use crate::{
+    card::{Card, CardPassword, CardType},
+    card_data::{CardData, Id},
+    deck_part::DeckPart,
+};
+
+/// Maximum number of copies of a card across all parts of a deck.
+pub const MAX_COPIES: u8 = 3;
+
+/// Error when trying to add more copies of a card than allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("can not add {requested} more copies, only {available} allowed")]
+pub struct CopyLimitExceeded {
+    pub requested: u8,
+    pub available: u8,
+}
 
 /// The two types of deck part a card can be in
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -53,27 +70,60 @@ impl DeckEntry {
     pub fn set_count(&mut self, part_type: PartType, count: u8) {
         self.counts[part_type.idx()] = count
     }
+
+    /// Copies of this card across all parts.
+    #[must_use]
+    pub fn total(&self) -> u8 {
+        self.counts[0].saturating_add(self.counts[1])
+    }
+}
+
+/// Change of the count of a card in one part type, see [`Deck::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountChange {
+    pub id: Id,
+    pub part_type: PartType,
+    pub from: u8,
+    pub to: u8,
 }
 
 #[derive(Debug, Default, Clone)]
-pub struct Deck(Vec<DeckEntry>);
+pub struct Deck {
+    entries: Vec<DeckEntry>,
+    /// Extra deck monsters which are deliberately kept in the main deck
+    main_overrides: Vec<Id>,
+    /// Printings chosen for export, if they differ from the main printing
+    printings: Vec<(Id, CardPassword)>,
+    /// Cards guarded against removal, with the number of copies which have to stay in the deck
+    locked: Vec<(Id, u8)>,
+}
 
 impl Deck {
     pub fn new(mut entries: Vec<DeckEntry>) -> Self {
         entries.sort_unstable_by_key(DeckEntry::id);
-        Self(entries)
+        Self {
+            entries,
+            main_overrides: Vec::new(),
+            printings: Vec::new(),
+            locked: Vec::new(),
+        }
     }
 
     pub fn increment(&mut self, id: Id, part_type: PartType, amount: u8) -> u8 {
+        // Do not insert an entry without any copies
+        if amount == 0 {
+            return 0;
+        }
+
         let idx = self
-            .0
+            .entries
             .binary_search_by_key(&id, DeckEntry::id)
             .unwrap_or_else(|idx| {
-                self.0.insert(idx, DeckEntry::new(id));
+                self.entries.insert(idx, DeckEntry::new(id));
                 idx
             });
 
-        let entry = &mut self.0[idx].counts[part_type.idx()];
+        let entry = &mut self.entries[idx].counts[part_type.idx()];
 
         if let Some(new_val) = entry.checked_add(amount) {
             *entry = new_val;
@@ -85,9 +135,37 @@ impl Deck {
         ret
     }
 
+    /// Add copies of a card, unless the total across all parts would exceed `cap`.
+    ///
+    /// Unlike [`Self::increment`], nothing is added if the full amount does not fit.
+    ///
+    /// # Errors
+    ///
+    /// Returns the number of copies which could still be added if `amount` is too large.
+    pub fn checked_increment(
+        &mut self,
+        id: Id,
+        part_type: PartType,
+        amount: u8,
+        cap: u8,
+    ) -> Result<(), CopyLimitExceeded> {
+        let available = cap.saturating_sub(self.total_count(id));
+        if amount > available {
+            return Err(CopyLimitExceeded {
+                requested: amount,
+                available,
+            });
+        }
+
+        if amount > 0 {
+            self.increment(id, part_type, amount);
+        }
+        Ok(())
+    }
+
     pub fn decrement(&mut self, id: Id, part_type: PartType, amount: u8) -> u8 {
-        if let Ok(idx) = self.0.binary_search_by_key(&id, DeckEntry::id) {
-            let entry = &mut self.0[idx].counts[part_type.idx()];
+        if let Ok(idx) = self.entries.binary_search_by_key(&id, DeckEntry::id) {
+            let entry = &mut self.entries[idx].counts[part_type.idx()];
 
             let ret = if let Some(new_val) = entry.checked_sub(amount) {
                 *entry = new_val;
@@ -98,8 +176,11 @@ impl Deck {
                 ret
             };
 
-            if self.0[idx].counts.iter().all(|count| *count == 0) {
-                self.0.remove(idx);
+            if self.entries[idx].counts.iter().all(|count| *count == 0) {
+                self.entries.remove(idx);
+                self.set_main_override(id, false);
+                self.set_printing(id, None);
+                self.set_locked(id, 0);
             }
 
             ret
@@ -109,7 +190,206 @@ impl Deck {
     }
 
     pub fn entries(&self) -> impl Iterator<Item = DeckEntry> + '_ {
-        self.0.iter().copied()
+        self.entries.iter().copied()
+    }
+
+    /// Monsters which are not Extra deck monsters.
+    pub fn monsters<'a>(&'a self, cards: &'a CardData) -> impl Iterator<Item = DeckEntry> + 'a {
+        self.entries_matching(cards, |card| {
+            matches!(card.card_type, CardType::Monster { .. })
+                && !card.card_type.is_extra_deck_monster()
+        })
+    }
+
+    pub fn spells<'a>(&'a self, cards: &'a CardData) -> impl Iterator<Item = DeckEntry> + 'a {
+        self.entries_matching(cards, |card| matches!(card.card_type, CardType::Spell(_)))
+    }
+
+    pub fn traps<'a>(&'a self, cards: &'a CardData) -> impl Iterator<Item = DeckEntry> + 'a {
+        self.entries_matching(cards, |card| matches!(card.card_type, CardType::Trap(_)))
+    }
+
+    /// Fusion, Synchro, Xyz and Link monsters.
+    pub fn extra_deck<'a>(&'a self, cards: &'a CardData) -> impl Iterator<Item = DeckEntry> + 'a {
+        self.entries_matching(cards, |card| card.card_type.is_extra_deck_monster())
+    }
+
+    fn entries_matching<'a>(
+        &'a self,
+        cards: &'a CardData,
+        predicate: fn(&Card) -> bool,
+    ) -> impl Iterator<Item = DeckEntry> + 'a {
+        self.entries()
+            .filter(move |entry| predicate(&cards[entry.id()]))
+    }
+
+    /// Count changes which turn this deck into `other`, ordered by card id.
+    #[must_use]
+    pub fn diff(&self, other: &Deck) -> Vec<CountChange> {
+        let mut result = Vec::new();
+        let mut lhs = self.entries.iter().peekable();
+        let mut rhs = other.entries.iter().peekable();
+
+        loop {
+            let (from, to) = match (lhs.peek(), rhs.peek()) {
+                (None, None) => break,
+                (Some(from), Some(to)) if from.id == to.id => (lhs.next(), rhs.next()),
+                (Some(from), Some(to)) if from.id < to.id => (lhs.next(), None),
+                (Some(_), None) => (lhs.next(), None),
+                (_, Some(_)) => (None, rhs.next()),
+            };
+            let id = from.or(to).unwrap(/* at least one side is present */).id;
+
+            for part_type in [PartType::Playing, PartType::Side] {
+                let from = from.map_or(0, |entry| entry.count(part_type));
+                let to = to.map_or(0, |entry| entry.count(part_type));
+                if from != to {
+                    result.push(CountChange {
+                        id,
+                        part_type,
+                        from,
+                        to,
+                    });
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Copies of a card across all parts.
+    #[must_use]
+    pub fn total_count(&self, id: Id) -> u8 {
+        self.entries
+            .binary_search_by_key(&id, DeckEntry::id)
+            .map_or(0, |idx| self.entries[idx].total())
+    }
+
+    /// Keep the playing copies of an extra deck monster in the main deck.
+    ///
+    /// This is a workaround for cards which are classified wrongly in the card data.
+    pub fn set_main_override(&mut self, id: Id, value: bool) {
+        match (self.main_overrides.binary_search(&id), value) {
+            (Err(idx), true) => self.main_overrides.insert(idx, id),
+            (Ok(idx), false) => {
+                self.main_overrides.remove(idx);
+            }
+            _ => {}
+        }
+    }
+
+    #[must_use]
+    pub fn has_main_override(&self, id: Id) -> bool {
+        self.main_overrides.binary_search(&id).is_ok()
+    }
+
+    /// Choose the printing of a card which is used on export, or reset it to the main printing.
+    pub fn set_printing(&mut self, id: Id, password: Option<CardPassword>) {
+        match (
+            self.printings.binary_search_by_key(&id, |(id, _)| *id),
+            password,
+        ) {
+            (Ok(idx), Some(password)) => self.printings[idx].1 = password,
+            (Ok(idx), None) => {
+                self.printings.remove(idx);
+            }
+            (Err(idx), Some(password)) => self.printings.insert(idx, (id, password)),
+            (Err(_), None) => {}
+        }
+    }
+
+    /// The printing chosen with [`Self::set_printing`], if any.
+    #[must_use]
+    pub fn printing(&self, id: Id) -> Option<CardPassword> {
+        self.printings
+            .binary_search_by_key(&id, |(id, _)| *id)
+            .ok()
+            .map(|idx| self.printings[idx].1)
+    }
+
+    /// Lock copies of a card, so they are not removed accidentally. A count of zero unlocks it.
+    ///
+    /// The lock is advisory and has to be checked by the caller, see [`Self::locked_count`].
+    pub fn set_locked(&mut self, id: Id, count: u8) {
+        match (self.locked.binary_search_by_key(&id, |(id, _)| *id), count) {
+            (Ok(idx), 0) => {
+                self.locked.remove(idx);
+            }
+            (Ok(idx), count) => self.locked[idx].1 = count,
+            (Err(_), 0) => {}
+            (Err(idx), count) => self.locked.insert(idx, (id, count)),
+        }
+    }
+
+    /// Number of locked copies of a card.
+    #[must_use]
+    pub fn locked_count(&self, id: Id) -> u8 {
+        self.locked
+            .binary_search_by_key(&id, |(id, _)| *id)
+            .map_or(0, |idx| self.locked[idx].1)
+    }
+
+    /// The part containing the playing copies of a card.
+    #[must_use]
+    pub fn playing_part(&self, id: Id, cards: &CardData) -> DeckPart {
+        if cards[id].card_type.is_extra_deck_monster() && !self.has_main_override(id) {
+            DeckPart::Extra
+        } else {
+            DeckPart::Main
+        }
+    }
+
+    /// Number of cards in each part, indexed by `DeckPart as usize`.
+    #[must_use]
+    pub fn part_sizes(&self, cards: &CardData) -> [usize; 3] {
+        let mut sizes = [0; 3];
+        for part in DeckPart::iter() {
+            sizes[part as usize] = self
+                .entries_for_part(part, cards)
+                .map(|(_, count)| usize::from(count))
+                .sum();
+        }
+        sizes
+    }
+
+    /// Like [`EntriesForPart::for_part`](crate::deck_part::EntriesForPart::for_part), but
+    /// respecting the main deck overrides.
+    pub fn entries_for_part<'a>(
+        &'a self,
+        part: DeckPart,
+        cards: &'a CardData,
+    ) -> impl Iterator<Item = (Id, u8)> + 'a {
+        self.entries().filter_map(move |entry| {
+            let count = entry.count(part.into());
+            let contained = match part {
+                DeckPart::Main | DeckPart::Extra => self.playing_part(entry.id(), cards) == part,
+                DeckPart::Side => true,
+            };
+
+            (count > 0 && contained).then_some((entry.id(), count))
+        })
+    }
+
+    /// Cards placed in a part which can not contain them, see [`DeckPart::can_contain`].
+    ///
+    /// Respects the main deck overrides, so this reports Extra deck monsters kept in the Main
+    /// deck. Cards are in the order of [`Deck::entries`].
+    #[must_use]
+    pub fn misplaced_cards(&self, cards: &CardData) -> Vec<(Id, DeckPart)> {
+        self.entries()
+            .flat_map(|entry| {
+                let id = entry.id();
+                [
+                    (self.playing_part(id, cards), PartType::Playing),
+                    (DeckPart::Side, PartType::Side),
+                ]
+                .into_iter()
+                .filter(move |(part, part_type)| {
+                    entry.count(*part_type) > 0 && !part.can_contain(&cards[id])
+                })
+                .map(move |(part, _)| (id, part))
+            })
+            .collect()
     }
 }
 
@@ -133,7 +413,15 @@ pub mod test_util {
 
 #[cfg(test)]
 mod test {
-    use crate::assert_part_eq;
+    use crate::{
+        assert_part_eq,
+        card::{
+            test_util::{make_card, make_extra_deck_card, make_pendulum_card},
+            MonsterType,
+        },
+        card_data::CardDataStorage,
+    };
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -223,4 +511,250 @@ mod test {
             assert_part_eq!(&deck, other(part), &[]);
         }
     }
+
+    /// Number of different ids used by the random operations, small enough to cause collisions.
+    const PROPTEST_IDS: u16 = 8;
+
+    /// Increment or decrement, of a raw id, part type and amount.
+    fn operation() -> impl Strategy<Value = (bool, u16, PartType, u8)> {
+        (
+            any::<bool>(),
+            0..PROPTEST_IDS,
+            prop_oneof![Just(PartType::Playing), Just(PartType::Side)],
+            any::<u8>(),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn random_operations(operations in prop::collection::vec(operation(), 0..64)) {
+            let mut deck = Deck::default();
+            // Expected counts, indexed by id and part type
+            let mut expected = [[0_u8; 2]; PROPTEST_IDS as usize];
+
+            for (is_increment, raw_id, part_type, amount) in operations {
+                let id = Id::new(raw_id);
+                let count = &mut expected[usize::from(raw_id)][part_type.idx()];
+                let previous = *count;
+
+                if is_increment {
+                    *count = count.saturating_add(amount);
+                    prop_assert_eq!(deck.increment(id, part_type, amount), *count - previous);
+                } else {
+                    *count = count.saturating_sub(amount);
+                    prop_assert_eq!(deck.decrement(id, part_type, amount), previous - *count);
+                }
+
+                // Entries are sorted by id, without duplicates or empty entries
+                let entries = deck.entries().collect::<Vec<_>>();
+                prop_assert!(entries.windows(2).all(|pair| pair[0].id() < pair[1].id()));
+                prop_assert!(entries.iter().all(|entry| entry.counts != [0, 0]));
+
+                for (raw, counts) in (0..PROPTEST_IDS).zip(expected) {
+                    let entry = entries.iter().find(|entry| entry.id() == Id::new(raw));
+                    prop_assert_eq!(entry.map_or([0, 0], |entry| entry.counts), counts);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn total_count() {
+        const ID: Id = Id::new(1234);
+        const OTHER_ID: Id = Id::new(42);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2);
+        deck.increment(ID, PartType::Side, 1);
+
+        assert_eq!(deck.total_count(ID), 3);
+        assert_eq!(deck.total_count(OTHER_ID), 0);
+    }
+
+    #[test]
+    fn main_override() {
+        const EXTRA_ID: Id = Id::new(0);
+
+        let data = {
+            let cards = vec![make_extra_deck_card(1234)];
+            let data = CardData::from(CardDataStorage::new(cards, vec![]));
+            Box::leak(Box::new(data))
+        };
+
+        let mut deck = Deck::default();
+        deck.increment(EXTRA_ID, PartType::Playing, 2);
+        assert_eq!(
+            deck.entries_for_part(DeckPart::Extra, data)
+                .collect::<Vec<_>>(),
+            &[(EXTRA_ID, 2)]
+        );
+
+        deck.set_main_override(EXTRA_ID, true);
+        assert_eq!(
+            deck.entries_for_part(DeckPart::Main, data)
+                .collect::<Vec<_>>(),
+            &[(EXTRA_ID, 2)]
+        );
+        assert_eq!(deck.entries_for_part(DeckPart::Extra, data).count(), 0);
+
+        // Removing the card also removes the override
+        deck.decrement(EXTRA_ID, PartType::Playing, 2);
+        assert!(!deck.has_main_override(EXTRA_ID));
+    }
+
+    #[test]
+    fn misplaced_cards() {
+        const MAIN_ID: Id = Id::new(0);
+        const EXTRA_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_extra_deck_card(9876)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(MAIN_ID, PartType::Playing, 1);
+        deck.increment(MAIN_ID, PartType::Side, 1);
+        deck.increment(EXTRA_ID, PartType::Playing, 1);
+        deck.increment(EXTRA_ID, PartType::Side, 1);
+        assert_eq!(deck.misplaced_cards(&data), []);
+
+        // Overrides on Main deck cards do not change their part
+        deck.set_main_override(MAIN_ID, true);
+        deck.set_main_override(EXTRA_ID, true);
+        assert_eq!(deck.misplaced_cards(&data), [(EXTRA_ID, DeckPart::Main)]);
+
+        // Side deck copies alone are never misplaced
+        deck.decrement(EXTRA_ID, PartType::Playing, 1);
+        assert_eq!(deck.misplaced_cards(&data), []);
+    }
+
+    #[test]
+    fn part_sizes_pendulum() {
+        const MAIN_PENDULUM_ID: Id = Id::new(0);
+        const RITUAL_PENDULUM_ID: Id = Id::new(1);
+        const FUSION_PENDULUM_ID: Id = Id::new(2);
+        const SYNCHRO_PENDULUM_ID: Id = Id::new(3);
+        const XYZ_PENDULUM_ID: Id = Id::new(4);
+
+        let data = {
+            let cards = vec![
+                make_pendulum_card(1, None),
+                make_pendulum_card(2, Some(MonsterType::Ritual)),
+                make_pendulum_card(3, Some(MonsterType::Fusion)),
+                make_pendulum_card(4, Some(MonsterType::Synchro)),
+                make_pendulum_card(5, Some(MonsterType::Xyz)),
+            ];
+            let data = CardData::from(CardDataStorage::new(cards, vec![]));
+            Box::leak(Box::new(data))
+        };
+
+        let mut deck = Deck::default();
+        deck.increment(MAIN_PENDULUM_ID, PartType::Playing, 30);
+        deck.increment(MAIN_PENDULUM_ID, PartType::Side, 2);
+        deck.increment(RITUAL_PENDULUM_ID, PartType::Playing, 10);
+        deck.increment(FUSION_PENDULUM_ID, PartType::Playing, 1);
+        deck.increment(SYNCHRO_PENDULUM_ID, PartType::Playing, 2);
+        deck.increment(XYZ_PENDULUM_ID, PartType::Playing, 3);
+        deck.increment(XYZ_PENDULUM_ID, PartType::Side, 1);
+
+        let sizes = deck.part_sizes(data);
+        assert_eq!(sizes[DeckPart::Main as usize], 40);
+        assert_eq!(sizes[DeckPart::Extra as usize], 6);
+        assert_eq!(sizes[DeckPart::Side as usize], 3);
+
+        // Main deck Pendulums stay in the Main deck, even though they end up in the Extra deck
+        // during a duel
+        assert_eq!(deck.playing_part(MAIN_PENDULUM_ID, data), DeckPart::Main);
+        assert_eq!(deck.playing_part(RITUAL_PENDULUM_ID, data), DeckPart::Main);
+        assert_eq!(deck.playing_part(XYZ_PENDULUM_ID, data), DeckPart::Extra);
+        assert!(deck.misplaced_cards(data).is_empty());
+    }
+
+    #[test]
+    fn checked_add() {
+        const ID: Id = Id::new(1234);
+
+        let mut deck = Deck::default();
+        assert_eq!(
+            deck.checked_increment(ID, PartType::Playing, 2, MAX_COPIES),
+            Ok(())
+        );
+        assert_eq!(
+            deck.checked_increment(ID, PartType::Side, 2, MAX_COPIES),
+            Err(CopyLimitExceeded {
+                requested: 2,
+                available: 1
+            })
+        );
+        assert_part_eq!(&deck, PartType::Playing, &[(ID, 2)]);
+        assert_part_eq!(&deck, PartType::Side, &[]);
+
+        assert_eq!(
+            deck.checked_increment(ID, PartType::Side, 1, MAX_COPIES),
+            Ok(())
+        );
+        assert_eq!(deck.total_count(ID), MAX_COPIES);
+    }
+
+    #[test]
+    fn entries_by_card_type() {
+        const SPELL_ID: Id = Id::new(0);
+        const EXTRA_ID: Id = Id::new(1);
+        const MONSTER_ID: Id = Id::new(2);
+
+        let data = {
+            let cards = vec![
+                make_card(1),
+                make_extra_deck_card(2),
+                make_pendulum_card(3, None),
+            ];
+            let data = CardData::from(CardDataStorage::new(cards, vec![]));
+            Box::leak(Box::new(data))
+        };
+
+        let mut deck = Deck::default();
+        deck.increment(SPELL_ID, PartType::Playing, 1);
+        deck.increment(EXTRA_ID, PartType::Side, 2);
+        deck.increment(MONSTER_ID, PartType::Playing, 3);
+
+        let ids = |entries: &mut dyn Iterator<Item = DeckEntry>| {
+            entries.map(|entry| entry.id()).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&mut deck.monsters(data)), &[MONSTER_ID]);
+        assert_eq!(ids(&mut deck.spells(data)), &[SPELL_ID]);
+        assert_eq!(ids(&mut deck.traps(data)), &[]);
+        assert_eq!(ids(&mut deck.extra_deck(data)), &[EXTRA_ID]);
+    }
+
+    #[test]
+    fn diff() {
+        const A: Id = Id::new(0);
+        const B: Id = Id::new(1);
+        const C: Id = Id::new(2);
+
+        let mut lhs = Deck::default();
+        lhs.increment(A, PartType::Playing, 2);
+        lhs.increment(B, PartType::Side, 1);
+
+        let mut rhs = Deck::default();
+        rhs.increment(A, PartType::Playing, 1);
+        rhs.increment(A, PartType::Side, 1);
+        rhs.increment(C, PartType::Playing, 3);
+
+        let change = |id, part_type, from, to| CountChange {
+            id,
+            part_type,
+            from,
+            to,
+        };
+        assert_eq!(
+            lhs.diff(&rhs),
+            &[
+                change(A, PartType::Playing, 2, 1),
+                change(A, PartType::Side, 0, 1),
+                change(B, PartType::Side, 1, 0),
+                change(C, PartType::Playing, 0, 3),
+            ]
+        );
+        assert!(lhs.diff(&lhs).is_empty());
+    }
 }
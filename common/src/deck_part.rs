@@ -6,7 +6,7 @@ use crate::{
     deck::DeckEntry,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeckPart {
     Main,
     Extra,
@@ -34,6 +34,12 @@ impl DeckPart {
         }
     }
 
+    /// Check whether a card can be placed in this part.
+    ///
+    /// Pendulum monsters are routed by their frame, not by being Pendulums: main deck Pendulums
+    /// (Normal, Effect or Ritual) belong to the Main deck, while Pendulum Fusion, Synchro and Xyz
+    /// monsters belong to the Extra deck. Pendulums only enter the Extra deck face-up during a
+    /// duel, which is not part of deck construction.
     #[must_use]
     pub fn can_contain(self, card: &Card) -> bool {
         let is_extra = card.card_type.is_extra_deck_monster();
@@ -73,8 +79,8 @@ impl<I: Iterator<Item = DeckEntry>> EntriesForPart for I {
 mod test {
     use crate::{
         card::{
-            test_util::{make_card, make_extra_deck_card},
-            CardPassword,
+            test_util::{make_card, make_extra_deck_card, make_pendulum_card},
+            CardPassword, CardType, MonsterEffect, MonsterType,
         },
         card_data::CardDataStorage,
         deck::{Deck, PartType},
@@ -122,4 +128,41 @@ mod test {
         side_cards.sort_by_key(|(id, _)| *id);
         assert_eq!(side_cards, &[(MAIN_ID, 3), (EXTRA_ID, 5)]);
     }
+
+    #[test]
+    fn pendulum_routing() {
+        let data = {
+            let mut pendulum_normal = make_pendulum_card(1, None);
+            if let CardType::Monster { effect, .. } = &mut pendulum_normal.card_type {
+                *effect = MonsterEffect::Normal;
+            }
+
+            let cards = vec![
+                pendulum_normal,
+                make_pendulum_card(2, None),
+                make_pendulum_card(3, Some(MonsterType::Ritual)),
+                make_pendulum_card(4, Some(MonsterType::Fusion)),
+                make_pendulum_card(5, Some(MonsterType::Synchro)),
+                make_pendulum_card(6, Some(MonsterType::Xyz)),
+            ];
+            CardData::from(CardDataStorage::new(cards, vec![]))
+        };
+
+        // (card, belongs to the Extra deck)
+        let expected = [
+            ("pendulum normal", false),
+            ("pendulum effect", false),
+            ("pendulum ritual", false),
+            ("pendulum fusion", true),
+            ("pendulum synchro", true),
+            ("pendulum xyz", true),
+        ];
+
+        for (id, (name, is_extra)) in expected.into_iter().enumerate() {
+            let card = &data[Id::new(id.try_into().unwrap())];
+            assert_eq!(DeckPart::Main.can_contain(card), !is_extra, "{name}");
+            assert_eq!(DeckPart::Extra.can_contain(card), is_extra, "{name}");
+            assert!(DeckPart::Side.can_contain(card), "{name}");
+        }
+    }
 }
@@ -0,0 +1,185 @@
+//! Deck files for simulators, in exactly the format each of them expects.
+
+use std::io::{self, Write};
+
+use crate::{card_data::CardData, deck::Deck, deck_part::DeckPart, ydk};
+
+/// Program the deck is exported for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    /// YDK file, see [`ydk::save`]
+    EdoPro,
+    /// Password list of the Main and Extra deck, which can be loaded with [`crate::password_list`]
+    MasterDuel,
+}
+
+impl ExportTarget {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [Self::EdoPro, Self::MasterDuel].into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::EdoPro => "EDOPro",
+            Self::MasterDuel => "Master Duel",
+        }
+    }
+
+    /// Name of the exported file for a deck named `stem`.
+    #[must_use]
+    pub fn file_name(self, stem: &str) -> String {
+        match self {
+            Self::EdoPro => format!("{stem}.ydk"),
+            Self::MasterDuel => format!("{stem}_master_duel.txt"),
+        }
+    }
+
+    #[must_use]
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::EdoPro => "text/ydk",
+            Self::MasterDuel => "text/plain",
+        }
+    }
+
+    /// Parts of the deck which the target would not receive as they are in the app.
+    ///
+    /// The export still works, but the deck does not arrive unchanged.
+    #[must_use]
+    pub fn issues(self, deck: &Deck, cards: &CardData) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        let overrides = deck
+            .entries()
+            .filter(|entry| deck.has_main_override(entry.id()))
+            .count();
+        if overrides > 0 {
+            issues.push(format!(
+                "{overrides} Extra deck monster{} placed in the Main deck will be moved to the \
+                Extra deck",
+                if overrides == 1 { "" } else { "s" }
+            ));
+        }
+
+        if self == Self::MasterDuel {
+            let side = deck.part_sizes(cards)[DeckPart::Side as usize];
+            if side > 0 {
+                issues.push(format!(
+                    "{side} Side deck card{} not part of the list",
+                    if side == 1 { " is" } else { "s are" }
+                ));
+            }
+            if deck
+                .entries()
+                .any(|entry| deck.printing(entry.id()).is_some())
+            {
+                issues.push("Alternative printings are exported as the main printing".to_owned());
+            }
+        }
+
+        issues
+    }
+
+    /// Write the deck in the format of the target.
+    ///
+    /// # Errors
+    ///
+    /// See [`writeln!`].
+    pub fn save(self, deck: &Deck, cards: &CardData, writer: &mut impl Write) -> io::Result<()> {
+        match self {
+            Self::EdoPro => ydk::save(deck, cards, writer),
+            Self::MasterDuel => {
+                for part in [DeckPart::Main, DeckPart::Extra] {
+                    for (id, count) in deck.entries_for_part(part, cards) {
+                        for _ in 0..count {
+                            writeln!(writer, "{}", cards.password_for_id(id))?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        card::test_util::{make_card, make_extra_deck_card},
+        card_data::{CardDataStorage, Id},
+        deck::PartType,
+        password_list,
+    };
+
+    use super::*;
+
+    fn test_deck() -> (Deck, CardData) {
+        let mut card = make_card(100);
+        card.all_passwords.push(101);
+        let cards = vec![card, make_extra_deck_card(200), make_card(300)];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 3);
+        deck.increment(Id::new(1), PartType::Playing, 2);
+        (deck, cards)
+    }
+
+    fn export(target: ExportTarget, deck: &Deck, cards: &CardData) -> String {
+        let mut buffer = Vec::new();
+        target.save(deck, cards, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    fn assert_same_parts(lhs: &Deck, rhs: &Deck, cards: &CardData) {
+        for part in DeckPart::iter() {
+            itertools::assert_equal(
+                lhs.entries_for_part(part, cards),
+                rhs.entries_for_part(part, cards),
+            );
+        }
+    }
+
+    #[test]
+    fn edopro_round_trip() {
+        let (mut deck, cards) = test_deck();
+        deck.increment(Id::new(2), PartType::Side, 1);
+        deck.set_printing(Id::new(0), Some(101));
+        assert!(ExportTarget::EdoPro.issues(&deck, &cards).is_empty());
+
+        let text = export(ExportTarget::EdoPro, &deck, &cards);
+        let loaded = ydk::load(&text, &cards).unwrap();
+        assert_same_parts(&loaded, &deck, &cards);
+        assert_eq!(loaded.printing(Id::new(0)), Some(101));
+
+        deck.set_main_override(Id::new(1), true);
+        assert_eq!(ExportTarget::EdoPro.issues(&deck, &cards).len(), 1);
+    }
+
+    #[test]
+    fn master_duel_round_trip() {
+        let (mut deck, cards) = test_deck();
+        assert!(ExportTarget::MasterDuel.issues(&deck, &cards).is_empty());
+
+        let text = export(ExportTarget::MasterDuel, &deck, &cards);
+        assert_eq!(text, "100\n100\n100\n200\n200\n");
+        let (loaded, unknown) = password_list::load(&text, &cards);
+        assert!(unknown.is_empty());
+        assert_same_parts(&loaded, &deck, &cards);
+
+        deck.increment(Id::new(2), PartType::Side, 1);
+        deck.set_printing(Id::new(0), Some(101));
+        assert_eq!(
+            ExportTarget::MasterDuel.issues(&deck, &cards),
+            [
+                "1 Side deck card is not part of the list",
+                "Alternative printings are exported as the main printing",
+            ]
+        );
+        assert_eq!(
+            export(ExportTarget::MasterDuel, &deck, &cards),
+            "100\n100\n100\n200\n200\n"
+        );
+    }
+}
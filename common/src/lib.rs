@@ -1,6 +1,21 @@
+//! Deck building without a user interface.
+//!
+//! The card database is loaded from the data file produced by the data processor (see
+//! [`transfer`]) into a [`card_data::CardData`]. Decks are edited through [`deck::Deck`], checked
+//! with [`validation::validate`] and exchanged with other tools via [`ydk`] and [`ydke`]. Plain
+//! lists of passwords can be imported with [`password_list`]. Files for specific simulators are
+//! written with [`export_target`].
+//!
+//! See the `deck-validator` crate for a small command line program using this crate.
+
 pub mod card;
 pub mod card_data;
 pub mod deck;
 pub mod deck_part;
+pub mod export_target;
+pub mod password_list;
+pub mod search;
 pub mod transfer;
+pub mod validation;
 pub mod ydk;
+pub mod ydke;
@@ -0,0 +1,59 @@
+//! Plain lists of card passwords, e.g. collected by scanning cards.
+
+use crate::{
+    card::CardPassword, card_data::CardData, deck::Deck, deck_part::DeckPart,
+    ydk::deck_from_passwords,
+};
+
+/// Build a deck from passwords separated by line breaks, commas or other whitespace.
+///
+/// All cards are added as playing copies, so Extra deck monsters end up in the Extra deck. Entries
+/// which are not a known password are skipped and returned in their order of appearance.
+#[must_use]
+pub fn load(text: &str, cards: &CardData) -> (Deck, Vec<String>) {
+    let mut parts = [vec![], vec![], vec![]];
+    let mut unknown = Vec::new();
+    for entry in text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+    {
+        match entry
+            .parse::<CardPassword>()
+            .ok()
+            .filter(|password| cards.id_for_password(*password).is_some())
+        {
+            Some(password) => parts[DeckPart::Main as usize].push(password),
+            None => unknown.push(entry.to_owned()),
+        }
+    }
+
+    let deck = deck_from_passwords(&parts, cards).expect("unknown passwords are skipped");
+    (deck, unknown)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        card::test_util::{make_card, make_extra_deck_card},
+        card_data::{CardDataStorage, Id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn load_list() {
+        let cards = vec![make_card(100), make_extra_deck_card(200)];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let (deck, unknown) = load("100, 200\n100\r\n\n999,abc,", &cards);
+        assert_eq!(unknown, ["999", "abc"]);
+        itertools::assert_equal(
+            deck.entries_for_part(DeckPart::Main, &cards),
+            [(Id::new(0), 2)],
+        );
+        itertools::assert_equal(
+            deck.entries_for_part(DeckPart::Extra, &cards),
+            [(Id::new(1), 1)],
+        );
+    }
+}
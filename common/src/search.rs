@@ -0,0 +1,128 @@
+use crate::{
+    card::{Card, EffectTags},
+    deck_part::DeckPart,
+};
+
+/// Normalize text for searching.
+///
+/// Lowercases the text and replaces accented latin letters with their base letters, so that
+/// e.g. "résonance" matches "Résonance" as well as "resonance".
+#[must_use]
+pub fn fold(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        match strip_diacritic(c) {
+            Some(replacement) => result.push_str(replacement),
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+fn strip_diacritic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// Text and effect filters of the card search, matching cards which satisfy all of them.
+///
+/// Text filters are expected to be [folded](fold) already, empty ones match every card.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub name: String,
+    pub text: String,
+    pub tags: EffectTags,
+    /// Only cards which can be placed in this part, see [`DeckPart::can_contain`]
+    pub part: Option<DeckPart>,
+}
+
+impl Query {
+    #[must_use]
+    pub fn matches(&self, card: &Card) -> bool {
+        (self.name.is_empty() || card.search_name.contains(&self.name))
+            && (self.text.is_empty() || card.search_text.contains(&self.text))
+            && card.effect_tags.has_all(self.tags)
+            && self.part.is_none_or(|part| part.can_contain(card))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        card::{
+            test_util::{make_card, make_extra_deck_card},
+            EffectTag,
+        },
+        card_data::{CardData, CardDataStorage, Id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn folding() {
+        assert_eq!(fold("Pendulum Résonance"), "pendulum resonance");
+        assert_eq!(fold("ÆTHER Œuvre Straße"), "aether oeuvre strasse");
+        assert_eq!(fold("Dark Magician ☆"), "dark magician ☆");
+    }
+
+    #[test]
+    fn query() {
+        let mut card = make_card(1234);
+        card.search_name = fold("Pot of Greed");
+        card.search_text = fold("Draw 2 cards.");
+        card.effect_tags.add(EffectTag::Draw);
+        let cards = vec![card, make_extra_deck_card(9876)];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+        let card = &cards[Id::new(0)];
+        let extra_card = &cards[Id::new(1)];
+
+        let mut draw = EffectTags::default();
+        draw.add(EffectTag::Draw);
+        let mut search = EffectTags::default();
+        search.add(EffectTag::Search);
+
+        assert!(Query::default().matches(card));
+        let query = |name: &str, text: &str, tags| Query {
+            name: name.to_owned(),
+            text: text.to_owned(),
+            tags,
+            part: None,
+        };
+        assert!(query("pot", "draw 2", draw).matches(card));
+        assert!(!query("greedy", "", EffectTags::default()).matches(card));
+        assert!(!query("", "draw 3", EffectTags::default()).matches(card));
+        assert!(!query("pot", "", search).matches(card));
+
+        let part = |part| Query {
+            part: Some(part),
+            ..Query::default()
+        };
+        assert!(part(DeckPart::Main).matches(card));
+        assert!(!part(DeckPart::Main).matches(extra_card));
+        assert!(!part(DeckPart::Extra).matches(card));
+        assert!(part(DeckPart::Extra).matches(extra_card));
+    }
+}
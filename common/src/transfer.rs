@@ -1,9 +1,16 @@
 //! Constants for data transfer between build directories and hosted app.
 
 use bincode::Options;
+use serde::{Deserialize, Serialize};
 
 /// Name for the main data file.
-pub const DATA_FILENAME: &str = "cards.bin.xz";
+///
+/// The data is compressed as a raw LZMA stream rather than xz, as lzma-rs can only decompress LZMA
+/// in pieces, which lets the app show the progress of the decompression.
+pub const DATA_FILENAME: &str = "cards.bin.lzma";
+
+/// Name for the file describing the data file, see [`Metadata`].
+pub const METADATA_FILENAME: &str = "metadata.bin";
 
 /// Directory for individual image files.
 pub const IMAGE_DIRECTORY: &str = "images";
@@ -11,6 +18,19 @@ pub const IMAGE_DIRECTORY: &str = "images";
 /// File ending for individual image files.
 pub const IMAGE_FILE_ENDING: &str = "avif";
 
+/// Width and height of the square image files, in pixels.
+pub const IMAGE_SIZE: u32 = 96;
+
+/// Information about the data file, so users can tell how recent the card pool is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    pub card_count: u32,
+    /// Version of the card database the data file was built from
+    pub database_version: String,
+    /// Build time of the data file, in seconds since the Unix epoch
+    pub build_timestamp: u64,
+}
+
 /// Bincode settings for the data file.
 #[must_use]
 pub fn bincode_options() -> impl bincode::Options {
@@ -0,0 +1,271 @@
+//! Legality checks for decks, shared by the app and the deck validator.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    card::CardPassword,
+    card_data::{CardData, Id},
+    deck::{Deck, MAX_COPIES},
+    deck_part::DeckPart,
+};
+
+/// Maximum number of copies allowed per card.
+///
+/// Follows the [`CardLimit`](crate::card::CardLimit) of each card, unless it is overridden, e.g. for
+/// custom formats. Overrides are keyed by the main password, so they survive card data updates.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CopyLimits {
+    /// Singleton (highlander) format, allowing one copy of each card which is not forbidden
+    pub singleton: bool,
+    overrides: BTreeMap<CardPassword, u8>,
+}
+
+impl CopyLimits {
+    /// Number of copies of the card allowed across all parts of a deck.
+    #[must_use]
+    pub fn allowed(&self, id: Id, cards: &CardData) -> u8 {
+        let card = &cards[id];
+        self.overrides
+            .get(&card.password)
+            .copied()
+            .unwrap_or_else(|| {
+                let count = card.limit.count();
+                if self.singleton {
+                    count.min(1)
+                } else {
+                    count
+                }
+            })
+    }
+
+    /// Number of copies of the card which can be added to a deck at all.
+    ///
+    /// This is [`MAX_COPIES`], unless an override allows more. Like for the ban list, copies above
+    /// lower limits can be added and are reported by [`validate`].
+    #[must_use]
+    pub fn cap(&self, id: Id, cards: &CardData) -> u8 {
+        self.override_for(cards.password_for_id(id))
+            .map_or(MAX_COPIES, |limit| limit.max(MAX_COPIES))
+    }
+
+    #[must_use]
+    pub fn override_for(&self, password: CardPassword) -> Option<u8> {
+        self.overrides.get(&password).copied()
+    }
+
+    /// Set the limit of a card, or follow its [`CardLimit`](crate::card::CardLimit) for `None`.
+    pub fn set_override(&mut self, password: CardPassword, limit: Option<u8>) {
+        match limit {
+            Some(limit) => self.overrides.insert(password, limit),
+            None => self.overrides.remove(&password),
+        };
+    }
+
+    /// Overridden limits, ordered by password.
+    pub fn overrides(&self) -> impl Iterator<Item = (CardPassword, u8)> + '_ {
+        self.overrides
+            .iter()
+            .map(|(&password, &limit)| (password, limit))
+    }
+}
+
+/// Reason why a deck can not be used for play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeckError {
+    PartTooSmall {
+        part: DeckPart,
+        count: usize,
+        min: u8,
+    },
+    PartTooLarge {
+        part: DeckPart,
+        count: usize,
+        max: u8,
+    },
+    /// More copies of the card than allowed, counting all parts
+    OverLimit { id: Id, count: u8, allowed: u8 },
+    /// Card placed in a part it can not be played from
+    IllegalPartPlacement { id: Id, part: DeckPart },
+}
+
+/// Check the deck against the copy limits and the part sizes.
+///
+/// Errors for individual cards come first, in the order of [`Deck::entries`], followed by the
+/// errors for the deck parts.
+#[must_use]
+pub fn validate(deck: &Deck, cards: &CardData, limits: &CopyLimits) -> Vec<DeckError> {
+    let mut errors = vec![];
+    let mut misplaced = deck.misplaced_cards(cards).into_iter().peekable();
+
+    for entry in deck.entries() {
+        let id = entry.id();
+        let allowed = limits.allowed(id, cards);
+        if entry.total() > allowed {
+            errors.push(DeckError::OverLimit {
+                id,
+                count: entry.total(),
+                allowed,
+            });
+        }
+
+        while let Some((id, part)) = misplaced.next_if(|(misplaced_id, _)| *misplaced_id == id) {
+            errors.push(DeckError::IllegalPartPlacement { id, part });
+        }
+    }
+
+    let sizes = deck.part_sizes(cards);
+    for part in DeckPart::iter() {
+        let count = sizes[part as usize];
+
+        if count < usize::from(part.min()) {
+            errors.push(DeckError::PartTooSmall {
+                part,
+                count,
+                min: part.min(),
+            });
+        } else if count > usize::from(part.max()) {
+            errors.push(DeckError::PartTooLarge {
+                part,
+                count,
+                max: part.max(),
+            });
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        card::{
+            test_util::{make_card, make_extra_deck_card},
+            CardLimit,
+        },
+        card_data::CardDataStorage,
+        deck::PartType,
+    };
+
+    use super::*;
+
+    #[test]
+    fn validate_errors() {
+        let mut limited = make_card(1);
+        limited.limit = CardLimit::Limited;
+        let cards = vec![limited, make_card(2), make_extra_deck_card(3)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 1);
+        deck.increment(Id::new(0), PartType::Side, 1);
+        deck.increment(Id::new(1), PartType::Side, 3);
+        deck.increment(Id::new(2), PartType::Playing, 1);
+        deck.set_main_override(Id::new(2), true);
+
+        assert_eq!(
+            validate(&deck, &data, &CopyLimits::default()),
+            &[
+                DeckError::OverLimit {
+                    id: Id::new(0),
+                    count: 2,
+                    allowed: 1
+                },
+                DeckError::IllegalPartPlacement {
+                    id: Id::new(2),
+                    part: DeckPart::Main
+                },
+                DeckError::PartTooSmall {
+                    part: DeckPart::Main,
+                    count: 2,
+                    min: 40
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_part_too_large() {
+        let cards = (1..=6).map(make_card).collect();
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = Deck::default();
+        for id in 0..6 {
+            deck.increment(Id::new(id), PartType::Side, 3);
+        }
+
+        assert_eq!(
+            validate(&deck, &data, &CopyLimits::default()),
+            &[
+                DeckError::PartTooSmall {
+                    part: DeckPart::Main,
+                    count: 0,
+                    min: 40
+                },
+                DeckError::PartTooLarge {
+                    part: DeckPart::Side,
+                    count: 18,
+                    max: 15
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_overrides() {
+        let cards = vec![make_card(1), make_card(2)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut limits = CopyLimits::default();
+        limits.set_override(1, Some(5));
+        limits.set_override(2, Some(1));
+        assert_eq!(limits.allowed(Id::new(0), &data), 5);
+        assert_eq!(limits.cap(Id::new(0), &data), 5);
+        assert_eq!(limits.allowed(Id::new(1), &data), 1);
+        assert_eq!(limits.cap(Id::new(1), &data), MAX_COPIES);
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Side, 5);
+        deck.increment(Id::new(1), PartType::Side, 2);
+        assert_eq!(
+            validate(&deck, &data, &limits)[..1],
+            [DeckError::OverLimit {
+                id: Id::new(1),
+                count: 2,
+                allowed: 1
+            }]
+        );
+
+        limits.set_override(2, None);
+        assert_eq!(limits.overrides().collect::<Vec<_>>(), [(1, 5)]);
+        assert_eq!(limits.allowed(Id::new(1), &data), 3);
+    }
+
+    #[test]
+    fn singleton() {
+        let mut forbidden = make_card(3);
+        forbidden.limit = CardLimit::Forbidden;
+        let cards = vec![make_card(1), make_card(2), forbidden];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut limits = CopyLimits {
+            singleton: true,
+            ..CopyLimits::default()
+        };
+        limits.set_override(2, Some(2));
+        assert_eq!(limits.allowed(Id::new(0), &data), 1);
+        assert_eq!(limits.allowed(Id::new(1), &data), 2);
+        assert_eq!(limits.allowed(Id::new(2), &data), 0);
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 2);
+        deck.increment(Id::new(1), PartType::Playing, 2);
+        assert_eq!(
+            validate(&deck, &data, &limits)[..1],
+            [DeckError::OverLimit {
+                id: Id::new(0),
+                count: 2,
+                allowed: 1
+            }]
+        );
+    }
+}
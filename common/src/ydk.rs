@@ -1,13 +1,11 @@
-use std::io::{self, Write};
+use std::{
+    io::{self, Write},
+    iter,
+};
 
 use thiserror::Error;
 
-use crate::{
-    card::CardPassword,
-    card_data::CardData,
-    deck::Deck,
-    deck_part::{DeckPart, EntriesForPart},
-};
+use crate::{card::CardPassword, card_data::CardData, deck::Deck, deck_part::DeckPart};
 
 /// Section name in the YDK format.
 #[must_use]
@@ -28,43 +26,190 @@ pub fn ydk_prefix(part: DeckPart) -> char {
     }
 }
 
+/// Location of the part of YDK data which could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("line {line}, column {column}: {text:?}")]
+pub struct ParseError {
+    /// Starting at 1
+    pub line: usize,
+    /// Starting at 1, counted in characters
+    pub column: usize,
+    /// Content of the offending line
+    pub text: String,
+}
+
+impl ParseError {
+    /// Locate the `remaining` input, which the parser could not consume, within `data`.
+    fn new(data: &str, remaining: &str) -> Self {
+        let offset = data.len() - remaining.len();
+        let (before, after) = data.split_at(offset);
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = after.find('\n').map_or(data.len(), |idx| offset + idx);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: data[line_start..line_end].trim_end().to_owned(),
+        }
+    }
+}
+
 /// Possible errors when reading YDK data.
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("could not read input")]
     Reader(#[from] io::Error),
-    #[error("could not parse input")]
-    Parser(#[from] parse::Error),
-    #[error("unknown id: {0:?}")]
-    UnknownPassword(CardPassword),
+    #[error("could not parse input at {0}")]
+    Parser(ParseError),
+    #[error("unknown passwords: {0:?}")]
+    UnknownPasswords(Vec<CardPassword>),
+}
+
+/// Deviation from the usual structure (`#main`, `#extra`, `!side`), which does not prevent loading
+/// the deck.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Warning {
+    /// The cards of all occurrences are loaded
+    #[error("the {}{} section appears more than once", ydk_prefix(*.0), ydk_name(*.0))]
+    DuplicateSection(DeckPart),
+    #[error("the {}{} section is missing", ydk_prefix(*.0), ydk_name(*.0))]
+    MissingSection(DeckPart),
+    /// The first section is placed after the second one
+    #[error(
+        "the {}{} section comes after the {}{} section",
+        ydk_prefix(*.0),
+        ydk_name(*.0),
+        ydk_prefix(*.1),
+        ydk_name(*.1)
+    )]
+    OutOfOrder(DeckPart, DeckPart),
+    /// Everything from this location on is skipped
+    #[error("content which could not be read was ignored, starting at {0}")]
+    IgnoredContent(ParseError),
+}
+
+/// Check the order of the sections in a YDK file, reporting each problem once.
+fn section_warnings(sections: impl IntoIterator<Item = DeckPart>) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut seen = Vec::<DeckPart>::new();
+    for part in sections {
+        if seen.contains(&part) {
+            let warning = Warning::DuplicateSection(part);
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+            continue;
+        }
+
+        if let Some(&last) = seen.iter().max_by_key(|part| **part as usize) {
+            if (last as usize) > (part as usize) {
+                warnings.push(Warning::OutOfOrder(part, last));
+            }
+        }
+        seen.push(part);
+    }
+
+    for part in DeckPart::iter() {
+        if !seen.contains(&part) {
+            warnings.push(Warning::MissingSection(part));
+        }
+    }
+    warnings
+}
+
+/// Sections of the input, along with the location of any trailing content which was not read.
+fn parse_sections(data: &str) -> Result<(parse::Sections, Option<ParseError>), Error> {
+    let (sections, remaining) =
+        parse::parse(data).map_err(|err| Error::Parser(ParseError::new(data, &err.input)))?;
+    let ignored = (!remaining.is_empty()).then(|| ParseError::new(data, remaining));
+    Ok((sections, ignored))
+}
+
+/// Passwords of each part, merging repeated sections.
+fn merge_sections(sections: &[(DeckPart, Vec<CardPassword>)]) -> [Vec<CardPassword>; 3] {
+    let mut parts = [vec![], vec![], vec![]];
+    for (part, passwords) in sections {
+        parts[*part as usize].extend(passwords);
+    }
+    parts
 }
 
 /// Deserialize a deck from the YDK format used by `YGOPRODeck`.
 ///
-/// Due to the absence of a centralized standard, this implementation is quite lenient.
+/// Due to the absence of a centralized standard, this implementation is quite lenient: sections
+/// may appear in any order or multiple times, and content after the last readable section is
+/// ignored. Use [`load_with_warnings`] to report these cases.
 ///
 /// # Errors
 ///
-/// If the input can not be parsed, an error is returned.
+/// If the input does not start with a section, an error is returned. If it contains unknown
+/// passwords, all of them are reported in a single error.
 pub fn load(data: &str, cards: &CardData) -> Result<Deck, Error> {
-    let result = parse::parse(data)?;
+    let (sections, _) = parse_sections(data)?;
+    deck_from_passwords(&merge_sections(&sections), cards).map_err(Error::UnknownPasswords)
+}
+
+/// Like [`load`], additionally reporting duplicate, missing or out of order sections and ignored
+/// content.
+///
+/// # Errors
+///
+/// See [`load`].
+pub fn load_with_warnings(data: &str, cards: &CardData) -> Result<(Deck, Vec<Warning>), Error> {
+    let (sections, ignored) = parse_sections(data)?;
+    let mut warnings = section_warnings(sections.iter().map(|(part, _)| *part));
+    warnings.extend(ignored.map(Warning::IgnoredContent));
+    let deck =
+        deck_from_passwords(&merge_sections(&sections), cards).map_err(Error::UnknownPasswords)?;
+    Ok((deck, warnings))
+}
+
+/// Build a deck from the passwords of each part, indexed by `DeckPart as usize`.
+///
+/// Passwords of alternative printings are kept as the printing of the card. Unknown passwords are
+/// collected and returned as the error.
+pub(crate) fn deck_from_passwords(
+    parts: &[Vec<CardPassword>; 3],
+    cards: &CardData,
+) -> Result<Deck, Vec<CardPassword>> {
+    let (deck, unknown) = deck_skipping_unknown(parts, cards);
+    if unknown.is_empty() {
+        Ok(deck)
+    } else {
+        Err(unknown)
+    }
+}
 
+/// Like [`deck_from_passwords`], but unknown passwords are returned along with the deck of the
+/// known ones.
+pub(crate) fn deck_skipping_unknown(
+    parts: &[Vec<CardPassword>; 3],
+    cards: &CardData,
+) -> (Deck, Vec<CardPassword>) {
     let mut deck = Deck::default();
+    let mut unknown = Vec::new();
     for part in DeckPart::iter() {
-        for &password in &result[part as usize] {
-            let id = cards
-                .id_for_password(password)
-                .ok_or(Error::UnknownPassword(password))?;
-
-            deck.increment(id, part.into(), 1);
+        let passwords = &parts[part as usize];
+        for (&password, id) in passwords.iter().zip(cards.ids_for_passwords(passwords)) {
+            match id {
+                Some(id) => {
+                    deck.increment(id, part.into(), 1);
+                    if password != cards.password_for_id(id) {
+                        deck.set_printing(id, Some(password));
+                    }
+                }
+                None => unknown.push(password),
+            }
         }
     }
 
-    Ok(deck)
+    (deck, unknown)
 }
 
 /// Serialize the deck into the YDK format used by `YGOPRODeck`.
 ///
+/// Cards are written with the printing chosen in the deck, falling back to the main printing.
+///
 /// # Errors
 ///
 /// See [`writeln!`].
@@ -72,24 +217,39 @@ pub fn save(deck: &Deck, cards: &CardData, writer: &mut impl Write) -> io::Resul
     for part in DeckPart::iter() {
         writeln!(writer, "{}{}", ydk_prefix(part), ydk_name(part))?;
 
-        for (id, count) in deck.entries().for_part(part, cards) {
-            for _ in 0..count {
-                writeln!(writer, "{}", cards[id].password)?;
-            }
+        for password in part_passwords(deck, part, cards) {
+            writeln!(writer, "{password}")?;
         }
     }
 
     Ok(())
 }
 
+/// Passwords of the cards in a deck part, one per copy.
+///
+/// Cards use the printing chosen in the deck, falling back to the main printing.
+pub(crate) fn part_passwords<'a>(
+    deck: &'a Deck,
+    part: DeckPart,
+    cards: &'a CardData,
+) -> impl Iterator<Item = CardPassword> + 'a {
+    deck.entries_for_part(part, cards).flat_map(|(id, count)| {
+        let password = deck
+            .printing(id)
+            .filter(|password| cards.id_for_password(*password) == Some(id))
+            .unwrap_or(cards.password_for_id(id));
+        iter::repeat_n(password, usize::from(count))
+    })
+}
+
 mod parse {
     use nom::{
         branch::alt,
         bytes::complete::tag,
         character::complete::{self as character, multispace0, multispace1, one_of},
-        combinator::opt,
+        combinator::{eof, opt, peek},
         multi::separated_list1,
-        sequence::{delimited, pair, preceded},
+        sequence::{delimited, pair, preceded, terminated},
         Finish, Parser,
     };
 
@@ -100,12 +260,15 @@ mod parse {
     pub type Error = nom::error::Error<String>;
     pub type Result<T> = std::result::Result<T, nom::error::Error<String>>;
     type IResult<'a, T> = nom::IResult<&'a str, T>;
+    /// Passwords of each section, in the order of the input.
+    pub type Sections = Vec<(DeckPart, Vec<CardPassword>)>;
 
     trait IParser<'a, T>: Parser<&'a str, T, nom::error::Error<&'a str>> {}
     impl<'a, T, U: Parser<&'a str, T, nom::error::Error<&'a str>>> IParser<'a, T> for U {}
 
+    /// A password, which has to be followed by whitespace or the end of the input.
     fn id(input: &str) -> IResult<CardPassword> {
-        character::u32.parse(input)
+        terminated(character::u32, peek(alt((multispace1, eof)))).parse(input)
     }
 
     fn ids(input: &str) -> IResult<Vec<CardPassword>> {
@@ -130,19 +293,13 @@ mod parse {
             .parse(input)
     }
 
-    fn deck(input: &str) -> IResult<[Vec<CardPassword>; 3]> {
-        separated_list1(multispace1, section)
-            .map(|parts| {
-                let mut deck = [vec![], vec![], vec![]];
-                for (part_type, content) in parts {
-                    deck[part_type as usize].extend(&content);
-                }
-                deck
-            })
-            .parse(input)
+    fn deck(input: &str) -> IResult<Sections> {
+        separated_list1(multispace1, section)(input)
     }
 
-    pub fn parse(input: &str) -> Result<[Vec<CardPassword>; 3]> {
+    /// Sections in the order of the input, followed by the remaining input which could not be
+    /// read.
+    pub fn parse(input: &str) -> Result<(Sections, &str)> {
         delimited(multispace0, deck, multispace0)
             .parse(input)
             .finish()
@@ -150,7 +307,7 @@ mod parse {
                 input: input.to_owned(),
                 code,
             })
-            .map(|(_, res)| res)
+            .map(|(remaining, res)| (res, remaining))
     }
 }
 
@@ -255,4 +412,151 @@ mod test {
             itertools::assert_equal(data.deck.entries(), deck.entries());
         }
     }
+
+    #[test]
+    fn ydk_alternate_password() {
+        let mut card = make_card(100);
+        card.all_passwords = vec![100, 101, 102];
+        let cards = CardData::from(CardDataStorage::new(vec![card], vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), DeckPart::Main.into(), 2);
+
+        let mut output = Vec::new();
+        save(&deck, &cards, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#main\n100\n100\n#extra\n!side\n"
+        );
+
+        let imported = load("#main\n101\n102\n#extra\n!side\n", &cards).unwrap();
+        itertools::assert_equal(deck.entries(), imported.entries());
+    }
+
+    #[test]
+    fn ydk_chosen_printing() {
+        let mut card = make_card(100);
+        card.all_passwords = vec![100, 101];
+        let cards = CardData::from(CardDataStorage::new(vec![card], vec![]));
+
+        let imported = load("#main\n101\n#extra\n!side\n", &cards).unwrap();
+        assert_eq!(imported.printing(Id::new(0)), Some(101));
+
+        let mut output = Vec::new();
+        save(&imported, &cards, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#main\n101\n#extra\n!side\n"
+        );
+
+        // Printings of other cards are ignored
+        let mut deck = imported.clone();
+        deck.set_printing(Id::new(0), Some(999));
+        let mut output = Vec::new();
+        save(&deck, &cards, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#main\n100\n#extra\n!side\n"
+        );
+    }
+
+    #[test]
+    fn ydk_unknown_passwords() {
+        let ydk = "#main\n1\n99\n#extra\n2\n!side\n98\n";
+        match load(ydk, &card_data()) {
+            Err(Error::UnknownPasswords(passwords)) => assert_eq!(passwords, &[99, 98]),
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn ydk_parse_errors() {
+        let error = |ydk| match load(ydk, &card_data()) {
+            Err(Error::Parser(error)) => error,
+            result => panic!("unexpected result: {result:?}"),
+        };
+
+        // Cards before the first section
+        assert_eq!(
+            error("  1\n#main\n"),
+            ParseError {
+                line: 1,
+                column: 3,
+                text: "  1".to_owned(),
+            }
+        );
+        assert_eq!(error("").line, 1);
+    }
+
+    #[test]
+    fn ydk_ignored_content() {
+        let ignored = |ydk| {
+            let (deck, warnings) = load_with_warnings(ydk, &card_data()).unwrap();
+            itertools::assert_equal(load(ydk, &card_data()).unwrap().entries(), deck.entries());
+            match warnings.last() {
+                Some(Warning::IgnoredContent(error)) => (deck, error.clone()),
+                warning => panic!("unexpected warning: {warning:?}"),
+            }
+        };
+
+        // Not a password
+        let (deck, error) = ignored("#main\n1\n23x\n#extra\n!side\n");
+        assert_eq!(
+            error,
+            ParseError {
+                line: 3,
+                column: 1,
+                text: "23x".to_owned(),
+            }
+        );
+        itertools::assert_equal(
+            deck.entries_for_part(DeckPart::Main, &card_data()),
+            [(Id::new(0), 1)],
+        );
+        // Unknown section
+        assert_eq!(
+            ignored("#main\n1\r\n#bonus\n2\n").1,
+            ParseError {
+                line: 3,
+                column: 1,
+                text: "#bonus".to_owned(),
+            }
+        );
+        // Garbage after the last section
+        assert_eq!(ignored("#main\n1\n!side\n-").1.line, 4);
+    }
+
+    #[test]
+    fn ydk_section_warnings() {
+        let warnings = |ydk| load_with_warnings(ydk, &card_data()).unwrap().1;
+
+        assert!(warnings("#main\n1\n#extra\n2\n!side\n3\n").is_empty());
+        assert_eq!(
+            warnings("#main\n1\n#main\n23\n#extra\n!side\n#main\n"),
+            [Warning::DuplicateSection(DeckPart::Main)]
+        );
+        assert_eq!(
+            warnings("#extra\n!side\n#main\n1\n"),
+            [Warning::OutOfOrder(DeckPart::Main, DeckPart::Side)]
+        );
+        assert_eq!(
+            warnings("#main\n1\n"),
+            [
+                Warning::MissingSection(DeckPart::Extra),
+                Warning::MissingSection(DeckPart::Side),
+            ]
+        );
+
+        // Repeated sections are still merged
+        let (deck, _) = load_with_warnings("#main\n1\n#main\n1\n", &card_data()).unwrap();
+        itertools::assert_equal(
+            deck.entries_for_part(DeckPart::Main, &card_data()),
+            [(Id::new(0), 2)],
+        );
+
+        assert_eq!(
+            Warning::OutOfOrder(DeckPart::Main, DeckPart::Side).to_string(),
+            "the #main section comes after the !side section"
+        );
+    }
 }
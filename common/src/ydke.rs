@@ -0,0 +1,173 @@
+//! The `ydke://` URL format used by `EDOPro` and many deck sharing sites.
+//!
+//! Each deck part is a base64 encoded list of little endian 32 bit passwords, the parts are
+//! separated by `!` in the order Main, Extra, Side.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use thiserror::Error;
+
+use crate::{
+    card::CardPassword,
+    card_data::CardData,
+    deck::Deck,
+    deck_part::DeckPart,
+    ydk::{deck_from_passwords, deck_skipping_unknown, part_passwords},
+};
+
+pub const PREFIX: &str = "ydke://";
+
+/// Possible errors when reading ydke URLs.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("missing \"{PREFIX}\" prefix")]
+    MissingPrefix,
+    #[error("expected 3 deck parts, found {0}")]
+    PartCount(usize),
+    #[error("invalid base64")]
+    Base64(#[from] base64::DecodeError),
+    #[error("deck part length is not a multiple of 4 bytes")]
+    PartLength,
+    #[error("unknown passwords: {0:?}")]
+    UnknownPasswords(Vec<CardPassword>),
+}
+
+fn decode_part(part: &str) -> Result<Vec<CardPassword>, Error> {
+    let bytes = STANDARD.decode(part)?;
+    if bytes.len() % 4 != 0 {
+        return Err(Error::PartLength);
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| CardPassword::from_le_bytes(chunk.try_into().unwrap()))
+        .collect())
+}
+
+/// Passwords of each part of a ydke URL, indexed by `DeckPart as usize`.
+fn decode_parts(url: &str) -> Result<[Vec<CardPassword>; 3], Error> {
+    let content = url
+        .trim()
+        .strip_prefix(PREFIX)
+        .ok_or(Error::MissingPrefix)?;
+    // The URL usually ends with a separator after the Side deck
+    let content = content.strip_suffix('!').unwrap_or(content);
+
+    let parts = content
+        .split('!')
+        .map(decode_part)
+        .collect::<Result<Vec<_>, _>>()?;
+    <[Vec<CardPassword>; 3]>::try_from(parts).map_err(|parts| Error::PartCount(parts.len()))
+}
+
+/// Deserialize a deck from a ydke URL.
+///
+/// # Errors
+///
+/// If the input is not a ydke URL, an error is returned. If it contains unknown passwords, all of
+/// them are reported in a single error.
+pub fn load(url: &str, cards: &CardData) -> Result<Deck, Error> {
+    deck_from_passwords(&decode_parts(url)?, cards).map_err(Error::UnknownPasswords)
+}
+
+/// Like [`load`], but cards with unknown passwords are skipped and returned along with the deck.
+///
+/// # Errors
+///
+/// If the input is not a ydke URL, an error is returned.
+pub fn load_skipping_unknown(
+    url: &str,
+    cards: &CardData,
+) -> Result<(Deck, Vec<CardPassword>), Error> {
+    Ok(deck_skipping_unknown(&decode_parts(url)?, cards))
+}
+
+/// Serialize the deck into a ydke URL.
+///
+/// Cards are written with the printing chosen in the deck, falling back to the main printing.
+#[must_use]
+pub fn save(deck: &Deck, cards: &CardData) -> String {
+    let mut result = PREFIX.to_owned();
+    for part in DeckPart::iter() {
+        let bytes = part_passwords(deck, part, cards)
+            .flat_map(CardPassword::to_le_bytes)
+            .collect::<Vec<_>>();
+        result.push_str(&STANDARD.encode(bytes));
+        result.push('!');
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        assert_part_eq,
+        card::test_util::{make_card, make_extra_deck_card},
+        card_data::{CardDataStorage, Id},
+        deck::PartType,
+    };
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut card = make_card(1234);
+        card.all_passwords.push(1235);
+        let cards = vec![card, make_extra_deck_card(9876)];
+        let data = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 2);
+        deck.increment(Id::new(1), PartType::Playing, 1);
+        deck.increment(Id::new(1), PartType::Side, 1);
+        deck.set_printing(Id::new(0), Some(1235));
+
+        let url = save(&deck, &data);
+        assert_eq!(url, "ydke://0wQAANMEAAA=!lCYAAA==!lCYAAA==!");
+
+        let loaded = load(&url, &data).unwrap();
+        assert_part_eq!(
+            loaded,
+            PartType::Playing,
+            &[(Id::new(0), 2), (Id::new(1), 1)]
+        );
+        assert_part_eq!(loaded, PartType::Side, &[(Id::new(1), 1)]);
+        assert_eq!(loaded.printing(Id::new(0)), Some(1235));
+    }
+
+    #[test]
+    fn invalid() {
+        let data = CardData::from(CardDataStorage::new(vec![make_card(1234)], vec![]));
+
+        assert!(matches!(load("!!!", &data), Err(Error::MissingPrefix)));
+        assert!(matches!(load("ydke://!!!", &data), Ok(deck) if deck.entries().count() == 0));
+        assert!(matches!(load("ydke://!!", &data), Err(Error::PartCount(2))));
+        assert!(matches!(
+            load("ydke://AAA!!!", &data),
+            Err(Error::Base64(_))
+        ));
+        assert!(matches!(
+            load("ydke://AAA=!!!", &data),
+            Err(Error::PartLength)
+        ));
+        assert!(matches!(
+            load("ydke://lCYAAA==!!!", &data),
+            Err(Error::UnknownPasswords(passwords)) if passwords == [9876]
+        ));
+    }
+
+    #[test]
+    fn skipping_unknown() {
+        let data = CardData::from(CardDataStorage::new(vec![make_card(1234)], vec![]));
+
+        let (deck, unknown) =
+            load_skipping_unknown("ydke://0gQAAJQmAAA=!!lCYAAA==!", &data).unwrap();
+        assert_part_eq!(deck, PartType::Playing, &[(Id::new(0), 1)]);
+        assert_eq!(deck.entries().count(), 1);
+        assert_eq!(unknown, [9876, 9876]);
+
+        assert!(matches!(
+            load_skipping_unknown("ydke://!!", &data),
+            Err(Error::PartCount(2))
+        ));
+    }
+}
@@ -13,13 +13,16 @@ use tokio::{
     try_join,
 };
 
-use crate::{image, ui::UiManager, ygoprodeck, OUTPUT_DIRECTORY, URL};
+use crate::{image, staples::LOCAL_STAPLES, ui::UiManager, ygoprodeck, OUTPUT_DIRECTORY, URL};
 
 /// Location of the cached card data download.
 pub const CARD_INFO_VERSION: &str = "target/card_info_version.txt";
 pub const CARD_INFO_LOCAL: &str = "target/card_info.json";
 pub const CARD_STAPLES: &str = "target/card_staples.json";
 
+/// Query of the card info download, stored with the version so changing it invalidates the cache.
+const CARD_INFO_QUERY: &str = "misc=yes";
+
 #[derive(Debug, Clone, Copy)]
 pub enum CacheResult {
     StillValid,
@@ -48,13 +51,15 @@ pub async fn update_card_info_cache(ui: &UiManager) -> Result<CacheResult> {
     if let Some(version) = should_download_card_info().await? {
         let write_version = async {
             let mut file = BufWriter::new(File::create(CARD_INFO_VERSION).await?);
-            file.write_all(version.as_bytes()).await?;
+            file.write_all(format!("{version}\n{CARD_INFO_QUERY}").as_bytes())
+                .await?;
             file.flush().await?;
             Ok::<_, anyhow::Error>(())
         };
 
         let database_download = async {
-            let mut download = BufReader::new(ui.get("Card Database", ygoprodeck::URL).await?);
+            let url = format!("{}?{CARD_INFO_QUERY}", ygoprodeck::URL);
+            let mut download = BufReader::new(ui.get("Card Database", url).await?);
             let mut file = BufWriter::new(File::create(CARD_INFO_LOCAL).await?);
             tokio::io::copy(&mut download, &mut file).await?;
             file.flush().await?;
@@ -76,8 +81,9 @@ pub async fn update_card_info_cache(ui: &UiManager) -> Result<CacheResult> {
 
     let output_path = &PathBuf::from(OUTPUT_DIRECTORY).join(transfer::DATA_FILENAME);
 
-    // If the output file is missing, request processing.
-    if !output_path.try_exists()? {
+    // If the output file or its metadata is missing, request processing.
+    let metadata_path = &PathBuf::from(OUTPUT_DIRECTORY).join(transfer::METADATA_FILENAME);
+    if !output_path.try_exists()? || !metadata_path.try_exists()? {
         return Ok(CacheResult::ProcessingRequired);
     }
 
@@ -91,6 +97,13 @@ pub async fn update_card_info_cache(ui: &UiManager) -> Result<CacheResult> {
         return Ok(CacheResult::ProcessingRequired);
     }
 
+    // If the local staple list was changed since the last run, request processing.
+    if Path::new(LOCAL_STAPLES).try_exists()?
+        && get_modification_time(LOCAL_STAPLES).await? > output_date
+    {
+        return Ok(CacheResult::ProcessingRequired);
+    }
+
     // Otherwise the output file should be up-to-date.
     Ok(CacheResult::StillValid)
 }
@@ -123,6 +136,11 @@ async fn should_download_card_info() -> Result<Option<String>> {
         return Ok(Some(get_online_version().await?));
     }
 
+    // A cache downloaded with a different query lacks the fields requested now.
+    if read_version_file().await?.lines().nth(1) != Some(CARD_INFO_QUERY) {
+        return Ok(Some(get_online_version().await?));
+    }
+
     // The cache file exists. Check duration since the last update (via modification date).
     if get_modification_time(CARD_INFO_VERSION).await?.elapsed()? > VERSION_CHECK_INTERVAL {
         let (online_version, local_version) = try_join!(get_online_version(), get_local_version())?;
@@ -155,7 +173,14 @@ async fn get_online_version() -> Result<String> {
     Ok(info.database_version)
 }
 
-async fn get_local_version() -> Result<String> {
+/// Version of the cached card info, as reported by the database when it was downloaded.
+pub async fn get_local_version() -> Result<String> {
+    let content = read_version_file().await?;
+    Ok(content.lines().next().unwrap_or_default().to_owned())
+}
+
+/// Content of the version file, the version followed by the query on the next line.
+async fn read_version_file() -> Result<String> {
     let mut tmp = String::new();
     BufReader::new(File::open(CARD_INFO_VERSION).await?)
         .read_to_string(&mut tmp)
@@ -35,6 +35,17 @@ impl ProcessingError {
             error: ProjectionErrorKind::UnknownValue(format!("{value:?}")),
         }
     }
+
+    /// Name of the field which could not be projected.
+    #[must_use]
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> &ProjectionErrorKind {
+        &self.error
+    }
 }
 
 impl fmt::Display for ProcessingError {
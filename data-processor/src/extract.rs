@@ -1,7 +1,10 @@
-use common::card::{
-    Attribute, CardLimit, CardPassword, CardType, CombatStat, FullCard, Header, LinkMarker,
-    LinkMarkers, MonsterEffect, MonsterStats, MonsterType, Race, SpanKind, SpellType, TextBlock,
-    TextPart, TrapType,
+use common::{
+    card::{
+        Attribute, CardLimit, CardPassword, CardType, CombatStat, EffectTag, EffectTags, FullCard,
+        Header, LinkMarker, LinkMarkers, MonsterEffect, MonsterStats, MonsterType, Race,
+        ReleaseDate, SpanKind, SpellType, TextBlock, TextPart, TrapType,
+    },
+    search,
 };
 use log::warn;
 
@@ -10,13 +13,24 @@ use crate::{
     ygoprodeck,
 };
 
-impl TryFrom<ygoprodeck::Card> for FullCard {
-    type Error = ProcessingError;
+/// Settings for converting cards of the database.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExtractOptions {
+    /// Whether unknown races and attributes are replaced by a fallback instead of dropping the card.
+    pub lenient: bool,
+}
 
-    fn try_from(value: ygoprodeck::Card) -> Result<Self, Self::Error> {
+impl ExtractOptions {
+    /// Convert a card of the database.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a field is missing or has an unexpected value.
+    pub fn card(self, value: ygoprodeck::Card) -> Result<FullCard, ProcessingError> {
         let description = (&value).into();
-        let card_type = (&value).try_into()?;
+        let card_type = self.card_type(&value)?;
         let limit = (&value).try_into()?;
+        let release_date = release_date(&value);
 
         let name = value.name;
         let main_password = value.id;
@@ -25,45 +39,58 @@ impl TryFrom<ygoprodeck::Card> for FullCard {
             .into_iter()
             .map(|info| info.id)
             .collect::<Vec<CardPassword>>();
-        let search_text = value.desc.to_lowercase();
+        let search_name = search::fold(&name);
+        let search_text = search::fold(&value.desc);
+        let effect_tags = effect_tags(&search_text);
 
-        Ok(Self {
+        Ok(FullCard {
             name,
             main_password,
             all_passwords,
             description,
+            search_name,
             search_text,
             card_type,
             limit,
+            release_date,
+            effect_tags,
         })
     }
 }
 
 impl From<&ygoprodeck::Card> for Vec<TextPart<String>> {
     fn from(card: &ygoprodeck::Card) -> Self {
-        let mut in_list = false;
-        card.desc
+        let mut current_list = None;
+        let mut result: Vec<_> = card
+            .desc
             .lines()
             .flat_map(|paragraph| {
                 let mut result = vec![];
 
                 // Lists
-                if let Some(paragraph) = paragraph.strip_prefix('●') {
-                    if !in_list {
-                        result.push(TextPart::Block(TextBlock::List));
-                        in_list = true;
+                let list_entry = if let Some(paragraph) = strip_bullet(paragraph) {
+                    Some((TextBlock::List, paragraph))
+                } else {
+                    strip_condition_number(paragraph)
+                        .map(|paragraph| (TextBlock::OrderedList, paragraph))
+                };
+
+                if current_list.is_some() && current_list != list_entry.map(|(list, _)| list) {
+                    result.push(TextPart::EndBlock(current_list.take().unwrap()));
+                }
+
+                if let Some((list, paragraph)) = list_entry {
+                    if current_list.is_none() {
+                        result.push(TextPart::Block(list));
+                        current_list = Some(list);
                     }
 
                     result.push(TextPart::Block(TextBlock::ListEntry));
-                    result.push(TextPart::Span(SpanKind::Normal, paragraph.to_owned()));
+                    result.extend(list_entry_spans(paragraph));
                     result.push(TextPart::EndBlock(TextBlock::ListEntry));
 
                     return result;
                 }
-                if in_list {
-                    result.push(TextPart::EndBlock(TextBlock::List));
-                    in_list = false;
-                }
 
                 // Headers
                 match paragraph.trim() {
@@ -86,33 +113,259 @@ impl From<&ygoprodeck::Card> for Vec<TextPart<String>> {
                         result.push(TextPart::Header(Header::MonsterEffect));
                         return result;
                     }
+                    // Separator between pendulum and monster effect
+                    line if !line.is_empty() && line.chars().all(|c| c == '-') => return result,
+                    "" => {
+                        result.push(TextPart::Separator);
+                        return result;
+                    }
                     _ => {}
                 }
 
-                result.extend_from_slice(&[
-                    TextPart::Block(TextBlock::Paragraph),
-                    TextPart::Span(SpanKind::Normal, paragraph.to_owned()),
-                    TextPart::EndBlock(TextBlock::Paragraph),
-                ]);
+                result.push(TextPart::Block(TextBlock::Paragraph));
+                result.extend(text_spans(paragraph));
+                result.push(TextPart::EndBlock(TextBlock::Paragraph));
 
                 result
             })
-            .collect()
+            .collect();
+
+        if let Some(list) = current_list {
+            result.push(TextPart::EndBlock(list));
+        }
+
+        remove_redundant_separators(result)
     }
 }
 
-impl TryFrom<&ygoprodeck::Card> for CardType {
-    type Error = ProcessingError;
+/// Separators are only kept between blocks of the same section, and repeated ones are merged.
+fn remove_redundant_separators(parts: Vec<TextPart<String>>) -> Vec<TextPart<String>> {
+    let mut result = Vec::with_capacity(parts.len());
+    for part in parts {
+        match part {
+            TextPart::Separator
+                if matches!(
+                    result.last(),
+                    None | Some(TextPart::Separator | TextPart::Header(_))
+                ) => {}
+            TextPart::Header(_) if matches!(result.last(), Some(TextPart::Separator)) => {
+                result.pop();
+                result.push(part);
+            }
+            _ => result.push(part),
+        }
+    }
 
-    fn try_from(value: &ygoprodeck::Card) -> Result<Self, Self::Error> {
+    if matches!(result.last(), Some(TextPart::Separator)) {
+        result.pop();
+    }
+    result
+}
+
+/// Game terms to highlight in card text.
+///
+/// Matches are extended to the end of the word, to include e.g. "Special Summoned". If multiple
+/// terms match at the same position, the first one is used.
+const KEYWORDS: &[&str] = &[
+    "Normal Summon",
+    "Tribute Summon",
+    "Special Summon",
+    "Flip Summon",
+    "Ritual Summon",
+    "Fusion Summon",
+    "Synchro Summon",
+    "Xyz Summon",
+    "Pendulum Summon",
+    "Link Summon",
+    "Quick Effect",
+    "Once per turn",
+    "once per turn",
+    "Once per Chain",
+    "once per Chain",
+];
+
+/// Split text into spans, highlighting quoted card names and [`KEYWORDS`].
+fn text_spans(text: &str) -> Vec<TextPart<String>> {
+    let mut result = Vec::new();
+
+    // Card names are quoted, so every other segment is a name. An unmatched quote is kept as is.
+    let segments = text.split('"').collect::<Vec<_>>();
+    for (idx, segment) in segments.iter().enumerate() {
+        if idx % 2 == 1 && idx + 1 < segments.len() {
+            result.push(TextPart::Span(SpanKind::CardName, format!("\"{segment}\"")));
+        } else if idx % 2 == 1 {
+            keyword_spans(&format!("\"{segment}"), &mut result);
+        } else {
+            keyword_spans(segment, &mut result);
+        }
+    }
+
+    result
+}
+
+/// Like [`text_spans`], but emphasizes a leading condition (the text up to the first colon).
+fn list_entry_spans(text: &str) -> Vec<TextPart<String>> {
+    match text.split_once(':') {
+        // Do not split quoted card names
+        Some((condition, rest)) if !condition.contains('"') => {
+            let mut result = vec![TextPart::Span(SpanKind::Bold, format!("{condition}:"))];
+            result.extend(text_spans(rest));
+            result
+        }
+        _ => text_spans(text),
+    }
+}
+
+fn keyword_spans(mut text: &str, result: &mut Vec<TextPart<String>>) {
+    let is_word_char = |c: char| c.is_alphanumeric();
+
+    while !text.is_empty() {
+        let next = KEYWORDS
+            .iter()
+            .flat_map(|keyword| {
+                text.match_indices(keyword)
+                    .find(|(start, _)| !text[..*start].ends_with(is_word_char))
+            })
+            .min_by_key(|(start, _)| *start);
+
+        let Some((start, keyword)) = next else {
+            break;
+        };
+
+        let rest = &text[start + keyword.len()..];
+        let end = start + keyword.len() + rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+
+        if start > 0 {
+            result.push(TextPart::Span(SpanKind::Normal, text[..start].to_owned()));
+        }
+        result.push(TextPart::Span(
+            SpanKind::Keyword,
+            text[start..end].to_owned(),
+        ));
+        text = &text[end..];
+    }
+
+    if !text.is_empty() {
+        result.push(TextPart::Span(SpanKind::Normal, text.to_owned()));
+    }
+}
+
+/// Glyphs used as bullets of unordered lists, including the full- and half-width middle dots.
+const BULLETS: [char; 4] = ['●', '•', '・', '･'];
+
+fn strip_bullet(text: &str) -> Option<&str> {
+    text.trim_start().strip_prefix(BULLETS).map(str::trim_start)
+}
+
+/// Strip a leading condition number (`①`, `②`, ...) including the following colon.
+fn strip_condition_number(text: &str) -> Option<&str> {
+    let mut chars = text.chars();
+    let first = chars.next()?;
+
+    ('①'..='⑳')
+        .contains(&first)
+        .then(|| chars.as_str().trim_start_matches([':', '：']).trim_start())
+}
+
+/// Phrases used to detect [`EffectTag`]s in the folded card text.
+///
+/// A tag is added if any sentence contains all phrases of one of its alternatives.
+const EFFECT_TAGS: &[(EffectTag, &[&[&str]])] = &[
+    (
+        EffectTag::Destroy,
+        &[
+            &["destroy 1"],
+            &["destroy 2"],
+            &["destroy up to"],
+            &["destroy all"],
+            &["destroy as many"],
+            &["destroy that"],
+            &["destroy them"],
+            &["destroy it"],
+            &["destroy target"],
+        ],
+    ),
+    (EffectTag::Negate, &[&["negate"]]),
+    (
+        EffectTag::Draw,
+        &[&["draw 1"], &["draw 2"], &["draw 3"], &["draw until"]],
+    ),
+    (
+        EffectTag::Search,
+        &[&["add", "from your deck to your hand"]],
+    ),
+    (
+        EffectTag::SpecialSummonFromGraveyard,
+        &[
+            &["special summon", "from your gy"],
+            &["special summon", "from either gy"],
+        ],
+    ),
+    (EffectTag::Banish, &[&["banish"]]),
+    (
+        EffectTag::HandTrap,
+        &[
+            &["quick effect", "discard this card;"],
+            &["quick effect", "send this card from your hand to the gy"],
+            &["quick effect", "banish this card from your hand"],
+            &["quick effect", "reveal this card in your hand"],
+        ],
+    ),
+    (
+        EffectTag::BoardBreaker,
+        &[
+            &["destroy all"],
+            &["destroy as many"],
+            &["banish all"],
+            &["shuffle all"],
+            &["return all"],
+            &["send all", "to the gy"],
+        ],
+    ),
+    (
+        EffectTag::Extender,
+        &[
+            &["special summon this card from your hand"],
+            &["special summon this card (from your hand)"],
+        ],
+    ),
+];
+
+fn effect_tags(search_text: &str) -> EffectTags {
+    let mut result = EffectTags::default();
+    for (tag, alternatives) in EFFECT_TAGS {
+        let found = search_text.split('.').any(|sentence| {
+            alternatives
+                .iter()
+                .any(|phrases| phrases.iter().all(|phrase| sentence.contains(phrase)))
+        });
+        if found {
+            result.add(*tag);
+        }
+    }
+    result
+}
+
+impl ExtractOptions {
+    /// Use the fallback for an unknown value in lenient mode, otherwise fail.
+    fn fallback<T>(self, fallback: T, error: ProcessingError) -> Result<T, ProcessingError> {
+        if self.lenient {
+            warn!("{error}\nUsing fallback value, the new value should be added to the enum");
+            Ok(fallback)
+        } else {
+            Err(error)
+        }
+    }
+
+    fn card_type(self, value: &ygoprodeck::Card) -> Result<CardType, ProcessingError> {
         macro_rules! monster {
             ($effect:expr) => {
                 monster! {$effect, is_tuner: false}
             };
             ($effect:expr, is_tuner: $tuner:expr) => {
                 Ok(CardType::Monster {
-                    race: Race::try_from(value)?,
-                    attribute: Attribute::try_from(value)?,
+                    race: self.race(value)?,
+                    attribute: self.attribute(value)?,
                     stats: MonsterStats::try_from(value)?,
                     effect: $effect,
                     is_tuner: $tuner,
@@ -163,76 +416,33 @@ impl TryFrom<&ygoprodeck::Card> for CardType {
             )),
         }
     }
-}
-
-impl TryFrom<&ygoprodeck::Card> for Race {
-    type Error = ProcessingError;
 
-    fn try_from(value: &ygoprodeck::Card) -> Result<Self, Self::Error> {
-        let result = match value
+    fn race(self, value: &ygoprodeck::Card) -> Result<Race, ProcessingError> {
+        let name = value
             .race
             .as_deref()
-            .try_unwrap_field(value.id, "race (monster)")?
-        {
-            "Aqua" => Race::Aqua,
-            "Beast" => Race::Beast,
-            "Beast-Warrior" => Race::BeastWarrior,
-            "Creator-God" | "Creator God" => Race::CreatorGod,
-            "Cyberse" => Race::Cyberse,
-            "Dinosaur" => Race::Dinosaur,
-            "Divine-Beast" => Race::DivineBeast,
-            "Dragon" => Race::Dragon,
-            "Fairy" => Race::Fairy,
-            "Fiend" => Race::Fiend,
-            "Fish" => Race::Fish,
-            "Illusion" => Race::Illusion,
-            "Insect" => Race::Insect,
-            "Machine" => Race::Machine,
-            "Plant" => Race::Plant,
-            "Psychic" => Race::Psychic,
-            "Pyro" => Race::Pyro,
-            "Reptile" => Race::Reptile,
-            "Rock" => Race::Rock,
-            "Sea Serpent" => Race::SeaSerpent,
-            "Spellcaster" => Race::Spellcaster,
-            "Thunder" => Race::Thunder,
-            "Warrior" => Race::Warrior,
-            "Winged Beast" => Race::WingedBeast,
-            "Wyrm" => Race::Wyrm,
-            "Zombie" => Race::Zombie,
-            name => {
-                return Err(ProcessingError::new_unknown(
-                    value.id,
-                    "race (monster)",
-                    &name,
-                ))
-            }
-        };
+            .try_unwrap_field(value.id, "race (monster)")?;
 
-        Ok(result)
+        name.parse().or_else(|_| {
+            self.fallback(
+                Race::Unknown,
+                ProcessingError::new_unknown(value.id, "race (monster)", &name),
+            )
+        })
     }
-}
-
-impl TryFrom<&ygoprodeck::Card> for Attribute {
-    type Error = ProcessingError;
 
-    fn try_from(value: &ygoprodeck::Card) -> Result<Self, Self::Error> {
-        let result = match value
+    fn attribute(self, value: &ygoprodeck::Card) -> Result<Attribute, ProcessingError> {
+        let name = value
             .attribute
             .as_deref()
-            .try_unwrap_field(value.id, "attribute")?
-        {
-            "DARK" => Attribute::Dark,
-            "EARTH" => Attribute::Earth,
-            "FIRE" => Attribute::Fire,
-            "LIGHT" => Attribute::Light,
-            "WATER" => Attribute::Water,
-            "WIND" => Attribute::Wind,
-            "DIVINE" => Attribute::Divine,
-            name => return Err(ProcessingError::new_unknown(value.id, "attribute", &name)),
-        };
+            .try_unwrap_field(value.id, "attribute")?;
 
-        Ok(result)
+        name.parse().or_else(|_| {
+            self.fallback(
+                Attribute::Unknown,
+                ProcessingError::new_unknown(value.id, "attribute", &name),
+            )
+        })
     }
 }
 
@@ -244,10 +454,16 @@ impl TryFrom<&ygoprodeck::Card> for MonsterStats {
         let atk = to_combat_stat(atk, value.id, "atk stat")?;
 
         if value.card_type == "Link Monster" {
+            let link_value = value.linkval.try_unwrap_field(value.id, "link value")?;
+            let link_markers = LinkMarkers::try_from(value)?;
+            if let Err(err) = check_link_markers(value.id, link_value, &link_markers) {
+                warn!("{err}");
+            }
+
             Ok(MonsterStats::Link {
                 atk,
-                link_value: value.linkval.try_unwrap_field(value.id, "link value")?,
-                link_markers: LinkMarkers::try_from(value)?,
+                link_value,
+                link_markers,
             })
         } else {
             let def = value.def.try_unwrap_field(value.id, "def stat")?;
@@ -301,6 +517,24 @@ impl TryFrom<&ygoprodeck::Card> for LinkMarkers {
     }
 }
 
+/// A mismatch between the number of markers and the link value hints at a data or parsing error.
+fn check_link_markers(
+    password: CardPassword,
+    link_value: u8,
+    markers: &LinkMarkers,
+) -> Result<(), ProcessingError> {
+    let count = markers.count();
+    if count == u32::from(link_value) {
+        Ok(())
+    } else {
+        Err(ProcessingError::new_unexpected(
+            password,
+            "link marker count",
+            &count,
+        ))
+    }
+}
+
 fn to_link_marker(value: &str, password: CardPassword) -> Result<LinkMarker, ProcessingError> {
     Ok(match value {
         "Top" => LinkMarker::Top,
@@ -345,27 +579,13 @@ impl TryFrom<&ygoprodeck::Card> for SpellType {
     type Error = ProcessingError;
 
     fn try_from(value: &ygoprodeck::Card) -> Result<Self, Self::Error> {
-        let result = match value
+        let race = value
             .race
             .as_deref()
-            .try_unwrap_field(value.id, "race (spell)")?
-        {
-            "Normal" => SpellType::Normal,
-            "Field" => SpellType::Field,
-            "Equip" => SpellType::Equip,
-            "Continuous" => SpellType::Continuous,
-            "Quick-Play" => SpellType::QuickPlay,
-            "Ritual" => SpellType::Ritual,
-            race => {
-                return Err(ProcessingError::new_unexpected(
-                    value.id,
-                    "race (spell)",
-                    &race,
-                ))
-            }
-        };
+            .try_unwrap_field(value.id, "race (spell)")?;
 
-        Ok(result)
+        race.parse()
+            .map_err(|_| ProcessingError::new_unexpected(value.id, "race (spell)", &race))
     }
 }
 
@@ -373,24 +593,13 @@ impl TryFrom<&ygoprodeck::Card> for TrapType {
     type Error = ProcessingError;
 
     fn try_from(value: &ygoprodeck::Card) -> Result<Self, Self::Error> {
-        let result = match value
+        let race = value
             .race
             .as_deref()
-            .try_unwrap_field(value.id, "race (trap)")?
-        {
-            "Normal" => TrapType::Normal,
-            "Continuous" => TrapType::Continuous,
-            "Counter" => TrapType::Counter,
-            race => {
-                return Err(ProcessingError::new_unexpected(
-                    value.id,
-                    "race (trap)",
-                    &race,
-                ))
-            }
-        };
+            .try_unwrap_field(value.id, "race (trap)")?;
 
-        Ok(result)
+        race.parse()
+            .map_err(|_| ProcessingError::new_unexpected(value.id, "race (trap)", &race))
     }
 }
 
@@ -419,3 +628,317 @@ impl TryFrom<&ygoprodeck::Card> for CardLimit {
         )
     }
 }
+
+/// Get the earliest release date of a card in either the TCG or OCG.
+///
+/// The date is only used for sorting, so a malformed one is reported and leaves the card without a
+/// release date instead of dropping it.
+fn release_date(card: &ygoprodeck::Card) -> Option<ReleaseDate> {
+    let dates = card
+        .misc_info
+        .iter()
+        .flatten()
+        .flat_map(|info| [&info.tcg_date, &info.ocg_date])
+        .flatten();
+
+    let mut result = None;
+    for date in dates {
+        match to_release_date(date, card.id) {
+            Ok(date) => result = Some(result.map_or(date, |prev: ReleaseDate| prev.min(date))),
+            Err(error) => {
+                warn!("{error}\nStoring the card without a release date");
+                return None;
+            }
+        }
+    }
+
+    result
+}
+
+fn to_release_date(value: &str, password: CardPassword) -> Result<ReleaseDate, ProcessingError> {
+    let parts = value.split('-').map(str::parse).collect::<Vec<_>>();
+
+    match parts[..] {
+        [Ok(year), Ok(month @ 1..=12), Ok(day @ 1..=31)] => Ok(ReleaseDate::new(
+            year,
+            u8::try_from(month).unwrap(),
+            u8::try_from(day).unwrap(),
+        )),
+        _ => Err(ProcessingError::new_unexpected(
+            password,
+            "release date",
+            value,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn bullets() {
+        for bullet in ["●", "•", "・", "･", " ● "] {
+            let card: ygoprodeck::Card = serde_json::from_value(json!({
+                "id": 1234,
+                "name": "Test",
+                "type": "Spell Card",
+                "desc": format!("Apply:\n{bullet}First: effect\n{bullet}Second"),
+                "card_images": [],
+            }))
+            .unwrap();
+            let parts = Vec::<TextPart<String>>::from(&card);
+
+            assert_eq!(
+                parts
+                    .iter()
+                    .filter(|part| **part == TextPart::Block(TextBlock::List))
+                    .count(),
+                1,
+                "{bullet:?}"
+            );
+            assert_eq!(
+                parts
+                    .iter()
+                    .filter(|part| **part == TextPart::Block(TextBlock::ListEntry))
+                    .count(),
+                2,
+                "{bullet:?}"
+            );
+            assert!(
+                parts.contains(&TextPart::Span(SpanKind::Bold, "First:".to_owned())),
+                "{bullet:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn text_spans_names() {
+        let normal = |text: &str| TextPart::Span(SpanKind::Normal, text.to_owned());
+        let name = |text: &str| TextPart::Span(SpanKind::CardName, text.to_owned());
+        let keyword = |text: &str| TextPart::Span(SpanKind::Keyword, text.to_owned());
+
+        assert_eq!(
+            text_spans(r#"Add 1 "Dark Magician" or "Special Summon Kid"."#),
+            [
+                normal("Add 1 "),
+                name(r#""Dark Magician""#),
+                normal(" or "),
+                name(r#""Special Summon Kid""#),
+                normal("."),
+            ]
+        );
+
+        // An unmatched quote does not start a name, but keywords after it are still found
+        assert_eq!(
+            text_spans(r#"Its "effect can be Special Summoned"#),
+            [
+                normal("Its "),
+                normal(r#""effect can be "#),
+                keyword("Special Summoned"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keyword_spans_matching() {
+        let spans = |text: &str| {
+            let mut result = Vec::new();
+            keyword_spans(text, &mut result);
+            result
+        };
+        let normal = |text: &str| TextPart::Span(SpanKind::Normal, text.to_owned());
+        let keyword = |text: &str| TextPart::Span(SpanKind::Keyword, text.to_owned());
+
+        // Matches are extended to the end of the word and do not overlap
+        assert_eq!(
+            spans("Special Summoned, Tribute Summon."),
+            [
+                keyword("Special Summoned"),
+                normal(", "),
+                keyword("Tribute Summon"),
+                normal("."),
+            ]
+        );
+
+        // Only the listed capitalizations match
+        assert_eq!(
+            spans("You can only use this effect once per turn. special summon"),
+            [
+                normal("You can only use this effect "),
+                keyword("once per turn"),
+                normal(". special summon"),
+            ]
+        );
+        assert_eq!(spans("ONCE PER TURN"), [normal("ONCE PER TURN")]);
+
+        // Matches must start at a word boundary
+        assert_eq!(
+            spans("XNormal Summon, Normal Summon"),
+            [normal("XNormal Summon, "), keyword("Normal Summon")]
+        );
+        assert_eq!(spans(""), []);
+    }
+
+    #[test]
+    fn effect_tag_detection() {
+        let tags = |text: &str| {
+            let tags = effect_tags(&search::fold(text));
+            EffectTag::iter()
+                .filter(|tag| tags.has(*tag))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(tags("Draw 2 cards."), [EffectTag::Draw]);
+        assert_eq!(
+            tags("Add 1 Spell from your Deck to your hand."),
+            [EffectTag::Search]
+        );
+        // Phrases of an alternative must be in the same sentence
+        assert_eq!(
+            tags("Add this card to your hand. It is from your Deck to your hand."),
+            []
+        );
+        assert_eq!(tags("Target 1 monster in your GY; Special Summon it."), []);
+        assert_eq!(
+            tags("Special Summon 1 monster from your GY."),
+            [EffectTag::SpecialSummonFromGraveyard]
+        );
+        assert_eq!(
+            tags("Destroy all monsters on the field."),
+            [EffectTag::Destroy, EffectTag::BoardBreaker]
+        );
+        assert_eq!(
+            tags("(Quick Effect): You can discard this card; negate the activation."),
+            [EffectTag::Negate, EffectTag::HandTrap]
+        );
+        assert_eq!(
+            tags("You can Special Summon this card from your hand."),
+            [EffectTag::Extender]
+        );
+        assert_eq!(tags("This card gains 500 ATK."), []);
+    }
+
+    #[test]
+    fn blank_lines() {
+        let card: ygoprodeck::Card = serde_json::from_value(json!({
+            "id": 1234,
+            "name": "Test",
+            "type": "Effect Monster",
+            "desc": "\n[ Pendulum Effect ]\nScale effect.\n\n----------------------------------------\n[ Monster Effect ]\nCannot be used as material.\n\n\nFirst effect.\nSecond effect.\n",
+            "card_images": [],
+        }))
+        .unwrap();
+        let parts = Vec::<TextPart<String>>::from(&card);
+
+        let paragraph = |text: &str| {
+            [
+                TextPart::Block(TextBlock::Paragraph),
+                TextPart::Span(SpanKind::Normal, text.to_owned()),
+                TextPart::EndBlock(TextBlock::Paragraph),
+            ]
+        };
+        let expected = [
+            &[TextPart::Header(Header::PendulumEffect)][..],
+            &paragraph("Scale effect."),
+            &[TextPart::Header(Header::MonsterEffect)],
+            &paragraph("Cannot be used as material."),
+            &[TextPart::Separator],
+            &paragraph("First effect."),
+            &paragraph("Second effect."),
+        ]
+        .concat();
+        assert_eq!(parts, expected);
+    }
+
+    #[test]
+    fn link_marker_count() {
+        let card: ygoprodeck::Card = serde_json::from_value(json!({
+            "id": 1861629,
+            "name": "Decode Talker",
+            "type": "Link Monster",
+            "desc": "",
+            "atk": 2300,
+            "linkval": 3,
+            "linkmarkers": ["Top", "Bottom-Left", "Bottom-Right"],
+            "card_images": [],
+        }))
+        .unwrap();
+        let markers = LinkMarkers::try_from(&card).unwrap();
+        assert_eq!(markers.count(), 3);
+        assert!(check_link_markers(card.id, 3, &markers).is_ok());
+
+        let card: ygoprodeck::Card = serde_json::from_value(json!({
+            "id": 98127546,
+            "name": "Firewall Dragon",
+            "type": "Link Monster",
+            "desc": "",
+            "atk": 2500,
+            "linkval": 4,
+            "linkmarkers": ["Top", "Left", "Right", "Bottom"],
+            "card_images": [],
+        }))
+        .unwrap();
+        let markers = LinkMarkers::try_from(&card).unwrap();
+        assert!(markers.has(LinkMarker::Left));
+        assert!(!markers.has(LinkMarker::TopLeft));
+        assert!(check_link_markers(card.id, 4, &markers).is_ok());
+        assert!(check_link_markers(card.id, 3, &markers).is_err());
+    }
+
+    #[test]
+    fn ban_status() {
+        let limit = |status: Option<&str>| {
+            let card: ygoprodeck::Card = serde_json::from_value(json!({
+                "id": 1234,
+                "name": "Test",
+                "type": "Spell Card",
+                "desc": "",
+                "card_images": [],
+                "banlist_info": status.map(|status| json!({ "ban_tcg": status })),
+            }))
+            .unwrap();
+            CardLimit::try_from(&card)
+        };
+
+        assert_eq!(limit(None).unwrap(), CardLimit::Unlimited);
+        assert_eq!(limit(Some("Semi-Limited")).unwrap(), CardLimit::SemiLimited);
+        assert_eq!(limit(Some("Limited")).unwrap(), CardLimit::Limited);
+        // Forbidden cards must not be playable at all
+        let forbidden = limit(Some("Forbidden")).unwrap();
+        assert_eq!(forbidden, CardLimit::Forbidden);
+        assert_eq!(forbidden.count(), 0);
+
+        assert!(limit(Some("Banned")).is_err());
+    }
+
+    #[test]
+    fn release_dates() {
+        let release_date = |dates: &[&str]| {
+            let card: ygoprodeck::Card = serde_json::from_value(json!({
+                "id": 1234,
+                "name": "Test",
+                "type": "Spell Card",
+                "race": "Normal",
+                "desc": "",
+                "card_images": [],
+                "misc_info": dates
+                    .iter()
+                    .map(|date| json!({ "tcg_date": date }))
+                    .collect::<Vec<_>>(),
+            }))
+            .unwrap();
+            ExtractOptions::default().card(card).unwrap().release_date
+        };
+
+        assert_eq!(
+            release_date(&["2020-05-01", "2019-12-24"]),
+            Some(ReleaseDate::new(2019, 12, 24))
+        );
+        assert_eq!(release_date(&[]), None);
+        // Malformed dates keep the card
+        assert_eq!(release_date(&["2020-13-01"]), None);
+    }
+}
@@ -1,15 +1,17 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fs::{self, File, OpenOptions},
+    hash::{DefaultHasher, Hash, Hasher},
     io::{self, BufReader, BufWriter, Read, Write},
     path::PathBuf,
+    str::FromStr,
     time::Duration,
 };
 
 use anyhow::{anyhow, Context, Result};
 use common::{
     card::CardPassword,
-    transfer::{self, IMAGE_DIRECTORY, IMAGE_FILE_ENDING},
+    transfer::{self, IMAGE_DIRECTORY, IMAGE_FILE_ENDING, IMAGE_SIZE},
 };
 use governor::{DefaultDirectRateLimiter, Jitter, Quota, RateLimiter};
 use image::{codecs::avif::AvifEncoder, imageops::FilterType, DynamicImage};
@@ -30,13 +32,61 @@ pub const CACHE_FILENAME: &str = "images.zip";
 pub const VERSION_FILE: &str = "version.txt";
 
 /// Current version of the image process.
-pub const VERSION: u32 = 1;
-
-const OUTPUT_SIZE: u32 = 96;
+///
+/// The version file also contains the [`ResizeFilter`], so changing it reprocesses all images.
+pub const VERSION: u32 = 3;
 
 const DOWNLOAD_LIMIT: Quota = Quota::per_second(nonzero!(15_u32));
 const DOWNLOAD_JITTER_MAX: Duration = Duration::from_millis(100);
 
+/// Filter used to scale artworks down to [`IMAGE_SIZE`], from best quality to fastest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResizeFilter {
+    #[default]
+    Lanczos3,
+    CatmullRom,
+    Triangle,
+}
+
+impl ResizeFilter {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [Self::Lanczos3, Self::CatmullRom, Self::Triangle].into_iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lanczos3 => "lanczos3",
+            Self::CatmullRom => "catmull-rom",
+            Self::Triangle => "triangle",
+        }
+    }
+
+    fn filter_type(self) -> FilterType {
+        match self {
+            Self::Lanczos3 => FilterType::Lanczos3,
+            Self::CatmullRom => FilterType::CatmullRom,
+            Self::Triangle => FilterType::Triangle,
+        }
+    }
+}
+
+impl FromStr for ResizeFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::iter()
+            .find(|filter| filter.name() == s)
+            .ok_or_else(|| {
+                let names = Self::iter().map(Self::name).collect::<Vec<_>>();
+                anyhow!(
+                    "Unknown resize filter {s}, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
 fn output_file(password: CardPassword) -> PathBuf {
     let mut path = PathBuf::from(OUTPUT_DIRECTORY);
     path.push(transfer::IMAGE_DIRECTORY);
@@ -49,14 +99,42 @@ fn zip_file(password: CardPassword) -> String {
     format!("{password}.{IMAGE_FILE_ENDING}")
 }
 
+fn zip_file_password(file_name: &str) -> Option<CardPassword> {
+    file_name
+        .strip_suffix(IMAGE_FILE_ENDING)?
+        .strip_suffix('.')?
+        .parse()
+        .ok()
+}
+
+/// Groups artworks which might be identical, which are shared by many alternate printings.
+///
+/// Hashes can collide, so artworks with the same hash are compared before being shared.
+fn artwork_hash(artwork: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    artwork.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Downloads and processes artworks, backed by the image cache.
+///
+/// Identical artworks are only stored once, both in the output and in the cache. Further passwords
+/// are added as symlinks to the first one in the cache, and reported by
+/// [`ImageLoader::shared_images`] for the card data.
 pub struct ImageLoader {
     cache_contents: HashSet<CardPassword>,
-    new_images: Mutex<Vec<CardPassword>>,
+    /// Passwords under which artworks are stored, by [`artwork_hash`]
+    artworks: Mutex<HashMap<u64, Vec<CardPassword>>>,
+    /// Passwords sharing the artwork stored under another password
+    shared_images: Mutex<HashMap<CardPassword, CardPassword>>,
+    /// Images to add to the cache, with the password of an identical artwork if there is one
+    new_images: Mutex<Vec<(CardPassword, Option<CardPassword>)>>,
     rate_limiter: DefaultDirectRateLimiter,
+    filter: ResizeFilter,
 }
 
 impl ImageLoader {
-    pub fn new() -> Result<Self> {
+    pub fn new(filter: ResizeFilter) -> Result<Self> {
         let output_path = &PathBuf::from(OUTPUT_DIRECTORY).join(IMAGE_DIRECTORY);
         if !output_path.try_exists()? {
             fs::create_dir(output_path)?;
@@ -72,12 +150,15 @@ impl ImageLoader {
             let mut cache = open_for_reading()?;
             let mut output = String::new();
             cache.by_name(VERSION_FILE)?.read_to_string(&mut output)?;
-            Ok(output.parse()?)
+            Ok(output)
         }()
-        .unwrap_or(0);
+        .unwrap_or_default();
+        let current_version = format!("{VERSION} {}", filter.name());
 
         let mut cache_contents = HashSet::new();
-        if version != VERSION {
+        let mut artworks = HashMap::<_, Vec<_>>::new();
+        let mut shared_images = HashMap::new();
+        if version != current_version {
             info!("Image cache out of date. All images will be processed.");
             let cache = BufWriter::new(File::create(cache_path)?);
             let mut cache = ZipWriter::new(cache);
@@ -85,39 +166,61 @@ impl ImageLoader {
                 VERSION_FILE,
                 SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
             )?;
-            write!(&mut cache, "{VERSION}")?;
+            cache.write_all(current_version.as_bytes())?;
             cache.finish()?;
         } else {
             let mut cache = open_for_reading()?;
 
-            let suffix = format!(".{IMAGE_FILE_ENDING}");
             for file_name in cache.file_names() {
                 if file_name == VERSION_FILE {
                     continue;
                 }
 
-                let password = file_name
-                    .strip_suffix(&suffix)
-                    .and_then(|password| password.parse().ok())
+                let password = zip_file_password(file_name)
                     .ok_or_else(|| anyhow!("Unexpected file in image cache: {file_name}"))?;
 
                 cache_contents.insert(password);
             }
 
+            let mut links = vec![];
             for &password in &cache_contents {
+                let mut file = cache.by_name(&zip_file(password))?;
+                let mut content = vec![];
+                file.read_to_end(&mut content)?;
+
+                if file.is_symlink() {
+                    let target = String::from_utf8(content)?;
+                    let target = zip_file_password(&target)
+                        .ok_or_else(|| anyhow!("Unexpected link in image cache: {target}"))?;
+                    links.push((password, target));
+                    continue;
+                }
+
+                artworks
+                    .entry(artwork_hash(&content))
+                    .or_default()
+                    .push(password);
                 if !output_file(password).try_exists()? {
-                    io::copy(
-                        &mut cache.by_name(&zip_file(password))?,
-                        &mut BufWriter::new(File::create_new(output_file(password))?),
-                    )?;
+                    fs::write(output_file(password), &content)?;
+                }
+            }
+
+            for (password, target) in links {
+                // Remove copies written by earlier versions
+                if output_file(password).try_exists()? {
+                    fs::remove_file(output_file(password))?;
                 }
+                shared_images.insert(password, target);
             }
         }
 
         Ok(Self {
             cache_contents,
+            artworks: Mutex::new(artworks),
+            shared_images: Mutex::new(shared_images),
             new_images: Mutex::default(),
             rate_limiter: RateLimiter::direct(DOWNLOAD_LIMIT),
+            filter,
         })
     }
 
@@ -133,20 +236,47 @@ impl ImageLoader {
         let image = download(password).await?;
 
         // Process and save
-        spawn_blocking(move || {
-            let image = process_image(&image);
-            let writer = BufWriter::new(File::create(output_file(password))?);
-            let encoder = AvifEncoder::new_with_speed_quality(writer, 1, 30);
+        let filter = self.filter;
+        let artwork = spawn_blocking(move || {
+            let image = process_image(&image, filter);
+            let mut artwork = vec![];
+            let encoder = AvifEncoder::new_with_speed_quality(&mut artwork, 1, 30);
             image.write_with_encoder(encoder)?;
-            Ok::<_, anyhow::Error>(())
+            Ok::<_, anyhow::Error>(artwork)
         })
         .await??;
 
+        // Save, unless an identical artwork is already stored
+        let mut artworks = self.artworks.lock().await;
+        let candidates = artworks.entry(artwork_hash(&artwork)).or_default();
+        let mut original = None;
+        for &candidate in candidates.iter() {
+            if fs::read(output_file(candidate))? == artwork {
+                original = Some(candidate);
+                break;
+            }
+        }
+        match original {
+            Some(original) => {
+                self.shared_images.lock().await.insert(password, original);
+            }
+            None => {
+                fs::write(output_file(password), &artwork)?;
+                candidates.push(password);
+            }
+        }
+        drop(artworks);
+
         // Register for caching
-        self.new_images.lock().await.push(password);
+        self.new_images.lock().await.push((password, original));
         Ok(())
     }
 
+    /// Passwords whose artwork is stored under another password, with that password.
+    pub async fn shared_images(&self) -> HashMap<CardPassword, CardPassword> {
+        self.shared_images.lock().await.clone()
+    }
+
     pub async fn finish(&self) -> Result<()> {
         let path = &PathBuf::from(OUTPUT_DIRECTORY).join(CACHE_FILENAME);
         let cache = OpenOptions::new()
@@ -157,7 +287,12 @@ impl ImageLoader {
         let mut cache = ZipWriter::new_append(cache)?;
 
         let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
-        for password in self.new_images.lock().await.iter().copied() {
+        for (password, original) in self.new_images.lock().await.iter().copied() {
+            if let Some(original) = original {
+                cache.add_symlink(zip_file(password), zip_file(original), options)?;
+                continue;
+            }
+
             let mut input = BufReader::new(File::open(output_file(password))?);
             cache.start_file(zip_file(password), options)?;
             io::copy(&mut input, &mut cache)?;
@@ -177,7 +312,7 @@ async fn download(password: CardPassword) -> Result<DynamicImage> {
     Ok(image)
 }
 
-fn process_image(image: &DynamicImage) -> DynamicImage {
+fn process_image(image: &DynamicImage, filter: ResizeFilter) -> DynamicImage {
     let size = image.width().min(image.height());
     // Center horizontally for wide artworks
     let x = (image.width() - size) / 2;
@@ -186,5 +321,5 @@ fn process_image(image: &DynamicImage) -> DynamicImage {
 
     image
         .crop_imm(x, y, size, size)
-        .resize(OUTPUT_SIZE, OUTPUT_SIZE, FilterType::Lanczos3)
+        .resize(IMAGE_SIZE, IMAGE_SIZE, filter.filter_type())
 }
@@ -2,6 +2,7 @@ pub mod cache;
 pub mod error;
 pub mod extract;
 pub mod image;
+pub mod staples;
 pub mod ui;
 pub mod ygoprodeck;
 
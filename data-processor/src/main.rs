@@ -1,20 +1,29 @@
 use std::{
+    collections::BTreeMap,
+    env,
     fs::{self, File},
     future,
     io::{BufReader, BufWriter},
     os::unix::prelude::MetadataExt,
     path::PathBuf,
-    time::Instant,
+    time::{Instant, SystemTime},
 };
 
 use anyhow::Result;
 use bincode::Options;
-use common::{card::FullCard, card_data::CardDataStorage, transfer};
+use common::{
+    card_data::CardDataStorage,
+    transfer::{self, Metadata},
+};
 use data_processor::{
     cache::{
-        ensure_image_cache, update_card_info_cache, CacheResult, CARD_INFO_LOCAL, CARD_STAPLES,
+        ensure_image_cache, get_local_version, update_card_info_cache, CacheResult,
+        CARD_INFO_LOCAL, CARD_STAPLES,
     },
-    image::ImageLoader,
+    error::{ProcessingError, ProjectionErrorKind},
+    extract::ExtractOptions,
+    image::{ImageLoader, ResizeFilter},
+    staples,
     ui::UiManager,
     ygoprodeck, OUTPUT_DIRECTORY,
 };
@@ -22,11 +31,24 @@ use futures::{stream::FuturesUnordered, StreamExt, TryFutureExt};
 use indicatif::{HumanBytes, HumanCount, HumanDuration};
 use log::{info, warn};
 use tokio::{task::spawn_blocking, try_join};
-use xz2::write::XzEncoder;
+use xz2::{
+    stream::{LzmaOptions, Stream},
+    write::XzEncoder,
+};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let ui = UiManager::new();
+    let options = ExtractOptions {
+        lenient: env::args().any(|arg| arg == "--lenient"),
+    };
+    let resize_filter = env::args()
+        .find_map(|arg| {
+            arg.strip_prefix("--resize-filter=")
+                .map(str::parse::<ResizeFilter>)
+        })
+        .transpose()?
+        .unwrap_or_default();
 
     let (data_result, image_result) =
         try_join!(update_card_info_cache(&ui), ensure_image_cache(&ui))?;
@@ -40,7 +62,7 @@ async fn main() -> Result<()> {
     let staples = ygoprodeck::parse(BufReader::new(File::open(CARD_STAPLES)?))?;
 
     info!("Checking images");
-    let loader = ImageLoader::new()?;
+    let loader = ImageLoader::new(resize_filter)?;
 
     info!("Processing cards");
     let stream: FuturesUnordered<_> = cards
@@ -49,24 +71,35 @@ async fn main() -> Result<()> {
         .map(|card| async {
             let password = card.id;
             let (card, ()) = try_join!(
-                spawn_blocking(|| FullCard::try_from(card)).map_err(anyhow::Error::from),
+                spawn_blocking(move || options.card(card)).map_err(anyhow::Error::from),
                 loader.ensure_image(password)
             )?;
 
             Ok(card?)
         })
         .collect();
+    // Card types missing from the mapping, with the number of cards dropped because of them
+    let mut unknown_types = BTreeMap::<String, usize>::new();
     let cards = ui
         .stream(stream)
         .filter_map(|card| {
-            future::ready(card.map_err(|err: anyhow::Error| warn!("{:?}", err)).ok())
+            future::ready(
+                card.map_err(|err: anyhow::Error| {
+                    if let Some(card_type) = unknown_card_type(&err) {
+                        *unknown_types.entry(card_type.to_owned()).or_default() += 1;
+                    }
+                    warn!("{:?}", err);
+                })
+                .ok(),
+            )
         })
         .collect::<Vec<_>>()
         .await;
     let count = cards.len();
 
-    let staples = staples.into_iter().map(|card| card.id).collect();
-    let data = CardDataStorage::new(cards, staples);
+    let staples = staples::merge(staples.into_iter().map(|card| card.id))?;
+    let data =
+        CardDataStorage::new(cards, staples).with_shared_images(loader.shared_images().await);
 
     info!("Saving images");
     loader.finish().await?;
@@ -77,7 +110,11 @@ async fn main() -> Result<()> {
 
     let saving_start = Instant::now();
     let file = BufWriter::new(File::create(path)?);
-    transfer::bincode_options().serialize_into(XzEncoder::new(file, 9), &data)?;
+    let encoder = XzEncoder::new_stream(
+        file,
+        Stream::new_lzma_encoder(&LzmaOptions::new_preset(9)?)?,
+    );
+    transfer::bincode_options().serialize_into(encoder, &data)?;
     let elapsed = saving_start.elapsed();
     let size = fs::metadata(path)?.size();
 
@@ -88,6 +125,16 @@ async fn main() -> Result<()> {
         HumanDuration(elapsed)
     );
 
+    let metadata = Metadata {
+        card_count: count.try_into()?,
+        database_version: get_local_version().await?,
+        build_timestamp: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs(),
+    };
+    let path = PathBuf::from(OUTPUT_DIRECTORY).join(transfer::METADATA_FILENAME);
+    transfer::bincode_options().serialize_into(BufWriter::new(File::create(path)?), &metadata)?;
+
     if let Some(prev_size) = prev_size {
         let change = (size as f64 - prev_size as f64) * 100.0 / prev_size as f64;
         if change.abs() > 0.5 {
@@ -98,5 +145,21 @@ async fn main() -> Result<()> {
         }
     }
 
+    if !unknown_types.is_empty() {
+        warn!("Cards with unknown card types were dropped:");
+        for (card_type, count) in &unknown_types {
+            warn!("  {card_type}: {} cards", HumanCount(*count as u64));
+        }
+    }
+
     Ok(())
 }
+
+/// The card type string, if the error was caused by a card type without mapping.
+fn unknown_card_type(err: &anyhow::Error) -> Option<&str> {
+    let err = err.downcast_ref::<ProcessingError>()?;
+    match err.kind() {
+        ProjectionErrorKind::UnexpectedValue(value) if err.field() == "card_type" => Some(value),
+        _ => None,
+    }
+}
@@ -0,0 +1,56 @@
+//! Local adjustments to the staple list provided by `YGOPRODeck`.
+
+use std::{collections::HashSet, fs, io};
+
+use anyhow::{anyhow, Result};
+use common::card::CardPassword;
+
+/// Location of the local staple list.
+///
+/// Contains one password per line. Passwords prefixed with `-` are removed from the upstream list,
+/// all others are added to it. Empty lines and lines starting with `#` are ignored.
+pub const LOCAL_STAPLES: &str = "data-processor/staples.txt";
+
+/// Merge the upstream staple list with the local one.
+///
+/// The result is de-duplicated, upstream staples come first. A missing local list is not an error.
+pub fn merge(upstream: impl IntoIterator<Item = CardPassword>) -> Result<Vec<CardPassword>> {
+    let local = match fs::read_to_string(LOCAL_STAPLES) {
+        Ok(local) => local,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut added = Vec::new();
+    let mut removed = HashSet::new();
+    for (idx, line) in local.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (is_removal, password) = match line.strip_prefix('-') {
+            Some(password) => (true, password.trim()),
+            None => (false, line),
+        };
+        let password: CardPassword = password.parse().map_err(|_| {
+            anyhow!(
+                "Invalid password in {LOCAL_STAPLES}:{}: \"{line}\"",
+                idx + 1
+            )
+        })?;
+
+        if is_removal {
+            removed.insert(password);
+        } else {
+            added.push(password);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    Ok(upstream
+        .into_iter()
+        .chain(added)
+        .filter(|password| !removed.contains(password) && seen.insert(*password))
+        .collect())
+}
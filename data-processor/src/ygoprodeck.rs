@@ -43,6 +43,9 @@ pub struct Card {
     pub card_images: Vec<ImageInfo>,
 
     pub banlist_info: Option<BanlistInfo>,
+
+    // Additional Response for `misc=yes`
+    pub misc_info: Option<Vec<MiscInfo>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,6 +53,12 @@ pub struct BanlistInfo {
     pub ban_tcg: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MiscInfo {
+    pub tcg_date: Option<String>,
+    pub ocg_date: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ImageInfo {
     pub id: CardPassword,
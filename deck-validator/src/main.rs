@@ -0,0 +1,94 @@
+//! Check a YDK deck file for errors and print statistics.
+
+use std::{env, fs, io::BufReader, process::ExitCode};
+
+use anyhow::{Context, Result};
+use bincode::Options;
+use common::{
+    card::CardType,
+    card_data::{CardData, CardDataStorage},
+    deck::Deck,
+    deck_part::DeckPart,
+    transfer,
+    validation::{validate, CopyLimits, DeckError},
+    ydk,
+};
+use xz2::{read::XzDecoder, stream::Stream};
+
+fn load_cards(path: &str) -> Result<CardData> {
+    let file = fs::File::open(path).with_context(|| format!("could not open {path}"))?;
+    let reader = XzDecoder::new_stream(BufReader::new(file), Stream::new_lzma_decoder(u64::MAX)?);
+    let storage: CardDataStorage = transfer::bincode_options()
+        .deserialize_from(reader)
+        .with_context(|| format!("could not read card data from {path}"))?;
+    Ok(storage.into())
+}
+
+fn load_deck(path: &str, cards: &CardData) -> Result<Deck> {
+    let text = fs::read_to_string(path).with_context(|| format!("could not open {path}"))?;
+    ydk::load(&text, cards).with_context(|| format!("could not load deck from {path}"))
+}
+
+fn print_stats(deck: &Deck, cards: &CardData) {
+    for part in DeckPart::iter() {
+        let mut counts = [0; 3];
+        for (id, count) in deck.entries_for_part(part, cards) {
+            let index = match cards[id].card_type {
+                CardType::Monster { .. } => 0,
+                CardType::Spell(_) => 1,
+                CardType::Trap(_) => 2,
+            };
+            counts[index] += usize::from(count);
+        }
+
+        let [monsters, spells, traps] = counts;
+        println!(
+            "{part}: {} cards ({monsters} monsters, {spells} spells, {traps} traps)",
+            monsters + spells + traps
+        );
+    }
+}
+
+fn describe(error: DeckError, cards: &CardData) -> String {
+    match error {
+        DeckError::PartTooSmall { part, count, min } => {
+            format!("{part} deck contains {count} cards, at least {min} required")
+        }
+        DeckError::PartTooLarge { part, count, max } => {
+            format!("{part} deck contains {count} cards, at most {max} allowed")
+        }
+        DeckError::OverLimit { id, count, allowed } => {
+            format!(
+                "{count} copies of {}, at most {allowed} allowed",
+                cards[id].name
+            )
+        }
+        DeckError::IllegalPartPlacement { id, part } => {
+            format!("{} can not be placed in the {part} deck", cards[id].name)
+        }
+    }
+}
+
+fn main() -> Result<ExitCode> {
+    let args = env::args().collect::<Vec<_>>();
+    let [_, data_path, deck_path] = args.as_slice() else {
+        eprintln!("Usage: deck-validator <cards.bin.lzma> <deck.ydk>");
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let cards = load_cards(data_path)?;
+    let deck = load_deck(deck_path, &cards)?;
+    print_stats(&deck, &cards);
+
+    let errors = validate(&deck, &cards, &CopyLimits::default());
+    if errors.is_empty() {
+        println!("The deck is legal.");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    println!("The deck is not legal:");
+    for error in errors {
+        println!("  - {}", describe(error, &cards));
+    }
+    Ok(ExitCode::FAILURE)
+}
@@ -0,0 +1,94 @@
+use std::{
+    env, fs,
+    io::Write,
+    iter,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use common::{card::test_util::make_card, card_data::CardDataStorage, transfer::bincode_options};
+use xz2::{
+    stream::{LzmaOptions, Stream},
+    write::XzEncoder,
+};
+
+/// Directory with a card data file of 20 spells with the passwords 100 to 119.
+fn setup(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("deck-validator-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let cards = (100..120)
+        .map(|password| {
+            let mut card = make_card(password);
+            card.name = format!("Card {password}");
+            card
+        })
+        .collect();
+    let storage = CardDataStorage::new(cards, vec![]);
+
+    let stream = Stream::new_lzma_encoder(&LzmaOptions::new_preset(6).unwrap()).unwrap();
+    let mut encoder = XzEncoder::new_stream(Vec::new(), stream);
+    bincode::Options::serialize_into(bincode_options(), &mut encoder, &storage).unwrap();
+    fs::write(dir.join("cards.bin.lzma"), encoder.finish().unwrap()).unwrap();
+    dir
+}
+
+fn validate(dir: &Path, ydk: &str) -> Output {
+    let mut file = fs::File::create(dir.join("deck.ydk")).unwrap();
+    file.write_all(ydk.as_bytes()).unwrap();
+
+    Command::new(env!("CARGO_BIN_EXE_deck-validator"))
+        .arg(dir.join("cards.bin.lzma"))
+        .arg(dir.join("deck.ydk"))
+        .output()
+        .unwrap()
+}
+
+fn ydk(main: impl Iterator<Item = u32>) -> String {
+    let main = main
+        .map(|password| format!("{password}\n"))
+        .collect::<String>();
+    format!("#main\n{main}#extra\n!side\n")
+}
+
+#[test]
+fn legal_deck() {
+    let dir = setup("legal");
+    let output = validate(&dir, &ydk((100..120).flat_map(|id| iter::repeat_n(id, 2))));
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Main: 40 cards (0 monsters, 40 spells, 0 traps)\n\
+         Extra: 0 cards (0 monsters, 0 spells, 0 traps)\n\
+         Side: 0 cards (0 monsters, 0 spells, 0 traps)\n\
+         The deck is legal.\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn illegal_deck() {
+    let dir = setup("illegal");
+    let output = validate(&dir, &ydk(iter::repeat_n(100, 4)));
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Main: 4 cards (0 monsters, 4 spells, 0 traps)\n"));
+    assert!(stdout.contains("The deck is not legal:\n"));
+    assert!(stdout.contains("  - Main deck contains 4 cards, at least 40 required\n"));
+    assert!(stdout.contains("  - 4 copies of Card 100, at most 3 allowed\n"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn usage() {
+    let output = Command::new(env!("CARGO_BIN_EXE_deck-validator"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Usage: deck-validator"));
+}
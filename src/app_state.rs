@@ -0,0 +1,252 @@
+//! Deck and drawers, saved as a single text so they can not get out of sync.
+
+use std::{fmt, iter};
+
+use common::card_data::{CardData, Id};
+use leptos::{expect_context, logging};
+
+use crate::{deck::Deck, deck_order::PartGroupings, text_encoding::TextEncoding};
+
+/// Prefix of the line storing the [`PartGroupings`].
+///
+/// The line contains no tab, so it can not be mistaken for a drawer.
+const PART_GROUPINGS_PREFIX: &str = "groupings:";
+
+/// Cards set aside outside of the deck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drawer {
+    pub name: String,
+    /// Cards in display order, each with its number of copies
+    pub content: Vec<(Id, u8)>,
+}
+
+impl Drawer {
+    /// Line breaks and tabs are removed from the name, as they are used by the text encoding.
+    #[must_use]
+    pub fn new(name: &str, content: Vec<(Id, u8)>) -> Self {
+        Self {
+            name: name.replace(['\n', '\r', '\t'], " "),
+            content,
+        }
+    }
+}
+
+impl TextEncoding for Drawer {
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        let cards = expect_context::<CardData>();
+
+        write!(writer, "{}\t", self.name)?;
+        // Copies are written as repeated passwords
+        let passwords = self.content.iter().flat_map(|(id, count)| {
+            iter::repeat_n(cards.password_for_id(*id), usize::from(*count))
+        });
+        for (idx, password) in passwords.enumerate() {
+            if idx > 0 {
+                writer.write_char(',')?;
+            }
+            write!(writer, "{password}")?;
+        }
+        Ok(())
+    }
+
+    /// Cards which are not part of the current card data are dropped.
+    ///
+    /// Repeated passwords are counted as copies, in the position of their first occurrence.
+    fn decode(text: &str) -> Option<Self> {
+        let cards = expect_context::<CardData>();
+
+        let (name, content) = text.split_once('\t')?;
+        let passwords = content
+            .split(',')
+            .filter(|password| !password.is_empty())
+            .map(|password| password.parse().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut content = Vec::<(Id, u8)>::new();
+        for id in cards.ids_for_passwords(&passwords).into_iter().flatten() {
+            match content.iter_mut().find(|(entry_id, _)| *entry_id == id) {
+                Some((_, count)) => *count = count.saturating_add(1),
+                None => content.push((id, 1)),
+            }
+        }
+
+        Some(Self {
+            name: name.to_owned(),
+            content,
+        })
+    }
+}
+
+/// Everything which is persisted between sessions, apart from settings.
+#[derive(Debug, Default, Clone)]
+pub struct AppState {
+    pub deck: Deck,
+    pub drawers: Vec<Drawer>,
+    pub part_groupings: PartGroupings,
+}
+
+impl AppState {
+    /// Decode the state, dropping cards which are not part of the current card data.
+    ///
+    /// Returns the state and the number of dropped deck entries, see
+    /// [`Deck::decode_skipping_unknown`]. Only the deck is required, drawers and part groupings
+    /// which can not be decoded are skipped, so they do not take the deck with them.
+    #[must_use]
+    pub fn decode_skipping_unknown(text: &str) -> Option<(Self, usize)> {
+        let mut lines = text.split('\n').peekable();
+        let (deck, dropped) = Deck::decode_skipping_unknown(lines.next()?)?;
+        let part_groupings = lines
+            .next_if(|line| !line.contains('\t'))
+            .and_then(|line| {
+                let part_groupings = line
+                    .strip_prefix(PART_GROUPINGS_PREFIX)
+                    .and_then(PartGroupings::decode);
+                if part_groupings.is_none() {
+                    logging::warn!("Skipping part groupings which could not be decoded: {line}");
+                }
+                part_groupings
+            })
+            .unwrap_or_default();
+        let drawers = lines
+            .filter_map(|line| {
+                let drawer = Drawer::decode(line);
+                if drawer.is_none() {
+                    logging::warn!("Skipping drawer which could not be decoded: {line}");
+                }
+                drawer
+            })
+            .collect();
+
+        Some((
+            Self {
+                deck,
+                drawers,
+                part_groupings,
+            },
+            dropped,
+        ))
+    }
+
+    /// Encode a state from its parts, without moving them into an [`AppState`] first.
+    #[must_use]
+    pub fn encode_parts(deck: &Deck, drawers: &[Drawer], part_groupings: PartGroupings) -> String {
+        let mut result = String::new();
+        encode_parts(deck, drawers, part_groupings, &mut result)
+            .unwrap(/* Write for String should never fail */);
+        result
+    }
+}
+
+/// The deck on the first line, followed by the part groupings if any are set and one line per
+/// drawer.
+fn encode_parts(
+    deck: &Deck,
+    drawers: &[Drawer],
+    part_groupings: PartGroupings,
+    writer: &mut impl fmt::Write,
+) -> fmt::Result {
+    deck.encode(writer)?;
+    if part_groupings != PartGroupings::default() {
+        write!(writer, "\n{PART_GROUPINGS_PREFIX}")?;
+        part_groupings.encode(writer)?;
+    }
+    for drawer in drawers {
+        writer.write_char('\n')?;
+        drawer.encode(writer)?;
+    }
+    Ok(())
+}
+
+impl TextEncoding for AppState {
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        encode_parts(&self.deck, &self.drawers, self.part_groupings, writer)
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        Self::decode_skipping_unknown(text).map(|(state, _)| state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        assert_part_eq, card::test_util::make_card, card_data::CardDataStorage, deck::PartType,
+        deck_part::DeckPart,
+    };
+    use leptos::provide_context;
+
+    use crate::deck_order::Grouping;
+
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_card(9876)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+        let state = AppState {
+            deck,
+            drawers: vec![
+                Drawer::new("Hand\ttraps", vec![(OTHER_ID, 1), (ID, 3)]),
+                Drawer::new("Empty", vec![]),
+            ],
+            part_groupings: PartGroupings::default(),
+        };
+
+        let text = state.encode_string();
+        assert!(text.ends_with("\nHand traps\t9876,1234,1234,1234\nEmpty\t"));
+
+        let decoded = AppState::decode(&text).unwrap();
+        assert_part_eq!(&decoded.deck, PartType::Playing, &[(ID, 2)]);
+        assert_eq!(decoded.drawers, state.drawers);
+
+        // Unknown cards are dropped from drawers
+        let (decoded, dropped) =
+            AppState::decode_skipping_unknown("v3  0;\nOld\t1234,5555").unwrap();
+        assert_eq!(dropped, 0);
+        assert_eq!(decoded.drawers, [Drawer::new("Old", vec![(ID, 1)])]);
+
+        // Malformed drawers are skipped, keeping the deck
+        let decoded = AppState::decode(&format!("{text}\nno tab\nBroken\tx")).unwrap();
+        assert_part_eq!(&decoded.deck, PartType::Playing, &[(ID, 2)]);
+        assert_eq!(decoded.drawers, state.drawers);
+    }
+
+    #[test]
+    fn part_groupings() {
+        let cards = vec![make_card(1234)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut state = AppState {
+            drawers: vec![Drawer::new("Drawer", vec![(Id::new(0), 1)])],
+            ..AppState::default()
+        };
+        state
+            .part_groupings
+            .set(DeckPart::Main, Some(Grouping::Attribute));
+        state
+            .part_groupings
+            .set(DeckPart::Side, Some(Grouping::Color));
+
+        let text = state.encode_string();
+        assert!(text.contains("\ngroupings:attribute,,color\nDrawer\t1234"));
+
+        let decoded = AppState::decode(&text).unwrap();
+        assert_eq!(decoded.part_groupings, state.part_groupings);
+        assert_eq!(decoded.drawers, state.drawers);
+
+        // Malformed part groupings are skipped, keeping the deck and drawers
+        let decoded =
+            AppState::decode("v3 1234:1:0 0;\ngroupings:unknown,,\nDrawer\t1234").unwrap();
+        assert_eq!(decoded.part_groupings, PartGroupings::default());
+        assert_part_eq!(&decoded.deck, PartType::Playing, &[(Id::new(0), 1)]);
+        assert_eq!(decoded.drawers, state.drawers);
+    }
+}
@@ -0,0 +1,239 @@
+//! Inventory of owned cards, to plan decks around an existing collection
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Write},
+};
+
+use common::{
+    card::CardPassword,
+    card_data::{CardData, Id},
+    deck::Deck,
+};
+
+use crate::text_encoding::TextEncoding;
+
+/// Owned copies per card, keyed by the main password so they survive card data updates.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Collection {
+    /// Whether owned copies are shown and checked
+    pub enabled: bool,
+    owned: BTreeMap<CardPassword, u8>,
+}
+
+impl Collection {
+    #[must_use]
+    pub fn owned(&self, password: CardPassword) -> u8 {
+        self.owned.get(&password).copied().unwrap_or(0)
+    }
+
+    /// Whether no copies of any card are owned.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.owned.is_empty()
+    }
+
+    pub fn set_owned(&mut self, password: CardPassword, count: u8) {
+        if count == 0 {
+            self.owned.remove(&password);
+        } else {
+            self.owned.insert(password, count);
+        }
+    }
+
+    /// Copies of a card the deck uses beyond the owned ones.
+    #[must_use]
+    pub fn missing_count(&self, deck: &Deck, id: Id, cards: &CardData) -> u8 {
+        deck.total_count(id)
+            .saturating_sub(self.owned(cards.password_for_id(id)))
+    }
+
+    /// Cards the deck uses beyond the owned copies, together with the number of missing copies.
+    pub fn missing<'a>(
+        &'a self,
+        deck: &'a Deck,
+        cards: &'a CardData,
+    ) -> impl Iterator<Item = (Id, u8)> + 'a {
+        deck.entries().filter_map(move |entry| {
+            let missing = self.missing_count(deck, entry.id(), cards);
+            (missing > 0).then_some((entry.id(), missing))
+        })
+    }
+
+    /// Cards needed to build all decks at the same time, beyond the owned copies.
+    ///
+    /// Copies can not be shared between decks, so the requirements of the decks are added up.
+    /// Returns the total number of copies needed and the number of missing copies per card.
+    #[must_use]
+    pub fn budget<'a>(
+        &self,
+        decks: impl IntoIterator<Item = &'a Deck>,
+        cards: &CardData,
+    ) -> Vec<(Id, u32, u32)> {
+        let mut needed = BTreeMap::<Id, u32>::new();
+        for deck in decks {
+            for entry in deck.entries() {
+                *needed.entry(entry.id()).or_default() += u32::from(entry.total());
+            }
+        }
+
+        needed
+            .into_iter()
+            .map(|(id, needed)| {
+                let owned = u32::from(self.owned(cards.password_for_id(id)));
+                (id, needed, needed.saturating_sub(owned))
+            })
+            .collect()
+    }
+}
+
+/// Write the cards the deck uses beyond the owned copies as CSV, for use as a shopping list.
+///
+/// # Errors
+///
+/// See [`writeln!`].
+pub fn write_missing_csv(
+    collection: &Collection,
+    deck: &Deck,
+    cards: &CardData,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(writer, "Name,Password,Missing")?;
+    for (id, missing) in collection.missing(deck, cards) {
+        let card = &cards[id];
+        let name = card.name.replace('"', "\"\"");
+        writeln!(writer, "\"{name}\",{},{missing}", card.password)?;
+    }
+    Ok(())
+}
+
+impl TextEncoding for Collection {
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        write!(writer, "{}", u8::from(self.enabled))?;
+        for (password, count) in &self.owned {
+            write!(writer, ";{password}:{count}")?;
+        }
+        Ok(())
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        let mut parts = text.split(';');
+        let enabled = match parts.next()? {
+            "0" => false,
+            "1" => true,
+            _ => return None,
+        };
+
+        let owned = parts
+            .map(|part| {
+                let (password, count) = part.split_once(':')?;
+                Some((password.parse().ok()?, count.parse().ok()?))
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { enabled, owned })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{card::test_util::make_card, card_data::CardDataStorage, deck::PartType};
+
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        let mut collection = Collection {
+            enabled: true,
+            ..Collection::default()
+        };
+        collection.set_owned(1234, 2);
+        collection.set_owned(99, 1);
+        collection.set_owned(5, 3);
+        collection.set_owned(5, 0);
+        assert!(!collection.is_empty());
+
+        assert_eq!(collection.encode_string(), "1;99:1;1234:2");
+        assert_eq!(
+            Collection::decode(&collection.encode_string()),
+            Some(collection)
+        );
+        assert_eq!(Collection::decode("0"), Some(Collection::default()));
+        assert!(Collection::decode("1;99:x").is_none());
+        assert!(Collection::decode("2").is_none());
+    }
+
+    #[test]
+    fn missing() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_card(9876)];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2);
+        deck.increment(ID, PartType::Side, 1);
+        deck.increment(OTHER_ID, PartType::Playing, 1);
+
+        let mut collection = Collection::default();
+        collection.set_owned(1234, 1);
+        collection.set_owned(9876, 3);
+
+        assert_eq!(
+            collection.missing(&deck, &cards).collect::<Vec<_>>(),
+            &[(ID, 2)]
+        );
+        assert_eq!(collection.missing_count(&deck, OTHER_ID, &cards), 0);
+    }
+
+    #[test]
+    fn budget_across_decks() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_card(9876)];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut first = Deck::default();
+        first.increment(ID, PartType::Playing, 3);
+        let mut second = Deck::default();
+        second.increment(ID, PartType::Side, 2);
+        second.increment(OTHER_ID, PartType::Playing, 1);
+
+        let mut collection = Collection::default();
+        collection.set_owned(1234, 3);
+        collection.set_owned(9876, 2);
+
+        // Each deck on its own is covered, but not both at once
+        assert_eq!(collection.missing(&first, &cards).count(), 0);
+        assert_eq!(collection.missing(&second, &cards).count(), 0);
+        assert_eq!(
+            collection.budget(&[first, second], &cards),
+            &[(ID, 5, 2), (OTHER_ID, 1, 0)]
+        );
+    }
+
+    #[test]
+    fn missing_csv() {
+        let mut card = make_card(1234);
+        card.name = "Say \"Hi\", Friend".to_owned();
+        let cards = CardData::from(CardDataStorage::new(vec![card, make_card(9876)], vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 3);
+        deck.increment(Id::new(1), PartType::Side, 1);
+
+        let mut collection = Collection::default();
+        collection.set_owned(1234, 1);
+        collection.set_owned(9876, 1);
+
+        let mut output = Vec::new();
+        write_missing_csv(&collection, &deck, &cards, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Name,Password,Missing\n\"Say \"\"Hi\"\", Friend\",1234,2\n"
+        );
+    }
+}
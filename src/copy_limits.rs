@@ -0,0 +1,56 @@
+//! Text encoding of the per-card copy limit overrides, for saving them between sessions.
+
+use std::fmt;
+
+use common::validation::CopyLimits;
+
+use crate::text_encoding::TextEncoding;
+
+impl TextEncoding for CopyLimits {
+    /// Whether the singleton format is used, followed by the overrides as `password:limit`, all
+    /// separated by `;`.
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        write!(writer, "{}", u8::from(self.singleton))?;
+        for (password, limit) in self.overrides() {
+            write!(writer, ";{password}:{limit}")?;
+        }
+        Ok(())
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        let mut parts = text.split(';');
+        let singleton = match parts.next()? {
+            "0" => false,
+            "1" => true,
+            _ => return None,
+        };
+
+        let mut limits = Self::default();
+        limits.singleton = singleton;
+        for part in parts {
+            let (password, limit) = part.split_once(':')?;
+            limits.set_override(password.parse().ok()?, Some(limit.parse().ok()?));
+        }
+        Some(limits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        let mut limits = CopyLimits::default();
+        limits.singleton = true;
+        limits.set_override(1234, Some(5));
+        limits.set_override(99, Some(1));
+
+        assert_eq!(limits.encode_string(), "1;99:1;1234:5");
+        assert_eq!(CopyLimits::decode(&limits.encode_string()), Some(limits));
+        assert_eq!(CopyLimits::decode("0"), Some(CopyLimits::default()));
+        assert!(CopyLimits::decode("0;99:x").is_none());
+        assert!(CopyLimits::decode("0;99").is_none());
+        assert!(CopyLimits::decode("").is_none());
+    }
+}
@@ -1,12 +1,16 @@
 use std::{fmt, ops::Deref};
 
 use common::{
+    card::CardPassword,
     card_data::{CardData, Id},
-    deck::{DeckEntry, PartType},
+    deck::{CopyLimitExceeded, CountChange, DeckEntry, PartType, MAX_COPIES},
+    deck_part::DeckPart,
+    validation::CopyLimits,
 };
 use leptos::expect_context;
 
 use crate::{
+    deck_meta::DeckMeta,
     text_encoding::TextEncoding,
     undo_redo::{UndoRedo, UndoRedoMessage},
 };
@@ -15,33 +19,93 @@ impl TextEncoding for DeckEntry {
     fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
         let cards = expect_context::<CardData>();
 
-        let password = cards[self.id()].password;
+        let password = cards.password_for_id(self.id());
         let playing = self.count(PartType::Playing);
         let side = self.count(PartType::Side);
         write!(writer, "{password}:{playing}:{side}")
     }
 
     fn decode(text: &str) -> Option<Self> {
-        let cards = expect_context::<CardData>();
+        decode_entry(text)?.ok().map(|(entry, _)| entry)
+    }
+}
 
-        let (password, text) = text.split_once(':')?;
-        let (playing, side) = text.split_once(':')?;
+/// Flag appended to entries of extra deck monsters which are kept in the main deck.
+const MAIN_OVERRIDE_FLAG: &str = "m";
+/// Prefix of the flag appended to locked entries, followed by the number of locked copies.
+const LOCKED_FLAG: &str = "l";
+/// Prefix of the flag appended to entries exported with an alternative printing, followed by its
+/// password.
+const PRINTING_FLAG: &str = "p";
 
-        let id = cards.id_for_password(password.parse().ok()?)?;
-        let playing = playing.parse().ok()?;
-        let side = side.parse().ok()?;
+/// Deck level information about an entry, which is stored as flags after the counts.
+#[derive(Debug, Default, Clone, Copy)]
+struct EntryFlags {
+    main_override: bool,
+    locked: u8,
+    printing: Option<CardPassword>,
+}
 
-        let mut result = Self::new(id);
-        result.set_count(PartType::Playing, playing);
-        result.set_count(PartType::Side, side);
-        Some(result)
+/// Whether `password` belongs to another printing of the card than the main one.
+fn is_alternative_printing(cards: CardData, id: Id, password: CardPassword) -> bool {
+    password != cards.password_for_id(id) && cards.id_for_password(password) == Some(id)
+}
+
+/// Decode a deck entry, distinguishing between malformed input (`None`) and passwords which are
+/// not part of the current card data (`Some(Err(password))`).
+fn decode_entry(text: &str) -> Option<Result<(DeckEntry, EntryFlags), CardPassword>> {
+    let cards = expect_context::<CardData>();
+
+    let mut parts = text.split(':');
+    let password = parts.next()?.parse().ok()?;
+    let playing = parts.next()?.parse().ok()?;
+    let side = parts.next()?.parse().ok()?;
+
+    let mut flags = EntryFlags::default();
+    for flag in parts {
+        if flag == MAIN_OVERRIDE_FLAG {
+            flags.main_override = true;
+        } else if let Some(locked) = flag.strip_prefix(LOCKED_FLAG) {
+            flags.locked = locked.parse().ok()?;
+        } else {
+            flags.printing = Some(flag.strip_prefix(PRINTING_FLAG)?.parse().ok()?);
+        }
     }
+
+    let Some(id) = cards.id_for_password(password) else {
+        return Some(Err(password));
+    };
+    // Printings which no longer belong to the card fall back to the main printing
+    flags.printing = flags
+        .printing
+        .filter(|printing| is_alternative_printing(cards, id, *printing));
+
+    let mut result = DeckEntry::new(id);
+    result.set_count(PartType::Playing, playing);
+    result.set_count(PartType::Side, side);
+    Some(Ok((result, flags)))
 }
 
 #[derive(Debug, Clone, Copy)]
 enum DeckMessage {
     Inc(Id, PartType, u8),
     Dec(Id, PartType, u8),
+    /// Set the main deck override to the value, which it did not have before
+    Override(Id, bool),
+    /// Change the number of locked copies from the first to the second value
+    Lock(Id, u8, u8),
+    /// Change the printing from the first to the second one, `None` being the main printing
+    Printing(Id, Option<CardPassword>, Option<CardPassword>),
+}
+
+impl DeckMessage {
+    /// Whether the message resets a flag, which happens implicitly when removing the last copy.
+    fn is_reset(self) -> bool {
+        matches!(
+            self,
+            Self::Override(_, false) | Self::Lock(_, _, 0) | Self::Printing(_, _, None)
+        )
+    }
 }
 
 impl UndoRedoMessage for DeckMessage {
@@ -49,31 +113,85 @@ impl UndoRedoMessage for DeckMessage {
         match self {
             Self::Inc(id, part_type, amount) => Self::Dec(id, part_type, amount),
             Self::Dec(id, part_type, amount) => Self::Inc(id, part_type, amount),
+            Self::Override(id, value) => Self::Override(id, !value),
+            Self::Lock(id, from, to) => Self::Lock(id, to, from),
+            Self::Printing(id, from, to) => Self::Printing(id, to, from),
         }
     }
 }
 
 impl TextEncoding for DeckMessage {
+    /// Count changes are encoded as `<sign><part><password>:<count>`, flag changes start with the
+    /// flag of the entry encoding instead.
     fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
         let cards = expect_context::<CardData>();
 
-        let sign = match self {
-            Self::Inc(..) => '+',
-            Self::Dec(..) => '-',
-        };
-
-        let (Self::Inc(id, part, count) | Self::Dec(id, part, count)) = self;
-        let part = match part {
-            PartType::Playing => 'p',
-            PartType::Side => 's',
-        };
-
-        write!(writer, "{sign}{part}{}:{count}", cards[*id].password)
+        match *self {
+            Self::Inc(id, part, count) | Self::Dec(id, part, count) => {
+                let sign = if matches!(self, Self::Inc(..)) {
+                    '+'
+                } else {
+                    '-'
+                };
+                let part = match part {
+                    PartType::Playing => 'p',
+                    PartType::Side => 's',
+                };
+                write!(writer, "{sign}{part}{}:{count}", cards.password_for_id(id))
+            }
+            Self::Override(id, value) => {
+                let password = cards.password_for_id(id);
+                write!(writer, "{MAIN_OVERRIDE_FLAG}{password}:{}", u8::from(value))
+            }
+            Self::Lock(id, from, to) => {
+                let password = cards.password_for_id(id);
+                write!(writer, "{LOCKED_FLAG}{password}:{from}:{to}")
+            }
+            Self::Printing(id, from, to) => {
+                // The main printing is left empty
+                let password = cards.password_for_id(id);
+                let from = from.map(|from| from.to_string()).unwrap_or_default();
+                let to = to.map(|to| to.to_string()).unwrap_or_default();
+                write!(writer, "{PRINTING_FLAG}{password}:{from}:{to}")
+            }
+        }
     }
 
     fn decode(text: &str) -> Option<Self> {
         let cards = expect_context::<CardData>();
 
+        if let Some(text) = text.strip_prefix(MAIN_OVERRIDE_FLAG) {
+            let (password, value) = text.split_once(':')?;
+            let id = cards.id_for_password(password.parse().ok()?)?;
+            let value = match value {
+                "0" => false,
+                "1" => true,
+                _ => return None,
+            };
+            return Some(Self::Override(id, value));
+        }
+
+        if let Some(text) = text.strip_prefix(LOCKED_FLAG) {
+            let (password, text) = text.split_once(':')?;
+            let (from, to) = text.split_once(':')?;
+            let id = cards.id_for_password(password.parse().ok()?)?;
+            return Some(Self::Lock(id, from.parse().ok()?, to.parse().ok()?));
+        }
+
+        if let Some(text) = text.strip_prefix(PRINTING_FLAG) {
+            let (password, text) = text.split_once(':')?;
+            let (from, to) = text.split_once(':')?;
+            let id = cards.id_for_password(password.parse().ok()?)?;
+            let printing = |text: &str| match text {
+                "" => Some(None),
+                text => {
+                    let printing = text.parse().ok()?;
+                    is_alternative_printing(cards, id, printing).then_some(Some(printing))
+                }
+            };
+            return Some(Self::Printing(id, printing(from)?, printing(to)?));
+        }
+
         text.starts_with(['+', '-']).then_some(())?;
         let (sign, text) = text.split_at(1);
 
@@ -100,9 +218,17 @@ impl TextEncoding for DeckMessage {
     }
 }
 
+/// Version of the deck text encoding.
+///
+/// Encoded decks are prefixed with `v<version> `. Decks saved before the introduction of the prefix
+/// are treated as version 0.
+const ENCODING_VERSION: u32 = 5;
+
 #[derive(Debug, Default, Clone)]
+#[allow(clippy::struct_field_names)] // the wrapped deck
 pub struct Deck {
     deck: common::deck::Deck,
+    meta: DeckMeta,
     undo_redo: UndoRedo<DeckMessage>,
 }
 
@@ -111,42 +237,352 @@ impl Deck {
     pub fn new(deck: common::deck::Deck) -> Self {
         Self {
             deck,
+            meta: DeckMeta::default(),
             undo_redo: UndoRedo::default(),
         }
     }
 
-    pub fn increment(&mut self, id: Id, part_type: PartType, amount: u8) {
-        let amount = self.deck.increment(id, part_type, amount);
+    #[must_use]
+    pub fn meta(&self) -> &DeckMeta {
+        &self.meta
+    }
+
+    /// Replace the name, notes and other information of the deck, which are not part of the
+    /// undo history.
+    pub fn set_meta(&mut self, meta: DeckMeta) {
+        self.meta = meta;
+    }
+
+    /// Add copies of a card, up to [`MAX_COPIES`] across all parts.
+    ///
+    /// # Errors
+    ///
+    /// Nothing is added if the amount exceeds the remaining copies.
+    pub fn increment(
+        &mut self,
+        id: Id,
+        part_type: PartType,
+        amount: u8,
+    ) -> Result<(), CopyLimitExceeded> {
+        self.increment_up_to(id, part_type, amount, MAX_COPIES)
+    }
+
+    /// Add copies of a card, up to `cap` across all parts, see [`CopyLimits::cap`].
+    ///
+    /// # Errors
+    ///
+    /// Nothing is added if the amount exceeds the remaining copies.
+    pub fn increment_up_to(
+        &mut self,
+        id: Id,
+        part_type: PartType,
+        amount: u8,
+        cap: u8,
+    ) -> Result<(), CopyLimitExceeded> {
+        self.deck.checked_increment(id, part_type, amount, cap)?;
         if amount > 0 {
             self.undo_redo
                 .push_action(DeckMessage::Inc(id, part_type, amount));
         }
+        Ok(())
     }
 
+    /// Remove copies of a card, keeping the locked copies in the deck.
     pub fn decrement(&mut self, id: Id, part_type: PartType, amount: u8) {
-        let amount = self.deck.decrement(id, part_type, amount);
+        let removable = self.total_count(id).saturating_sub(self.locked_count(id));
+        let had_override = self.has_main_override(id);
+        let printing = self.printing(id);
+        let amount = self.deck.decrement(id, part_type, amount.min(removable));
+        if amount == 0 {
+            return;
+        }
+
+        let mut messages = vec![DeckMessage::Dec(id, part_type, amount)];
+        // Removing the last copy resets the override and the printing, which have to be restored
+        // on undo
+        if self.total_count(id) == 0 {
+            if had_override {
+                messages.push(DeckMessage::Override(id, false));
+            }
+            if printing.is_some() {
+                messages.push(DeckMessage::Printing(id, printing, None));
+            }
+        }
+        self.undo_redo.push_group(messages);
+    }
+
+    /// Add copies of a card to a deck part, up to `cap` across all parts.
+    ///
+    /// Extra deck monsters added to the Main deck are kept there, while adding them to the Extra
+    /// deck moves them back, see [`Self::set_main_override`]. Both changes are a single undo step.
+    ///
+    /// # Errors
+    ///
+    /// Nothing is added if the amount exceeds the remaining copies.
+    pub fn increment_in_part(
+        &mut self,
+        id: Id,
+        part: DeckPart,
+        amount: u8,
+        cap: u8,
+        cards: &CardData,
+    ) -> Result<(), CopyLimitExceeded> {
+        self.deck.checked_increment(id, part.into(), amount, cap)?;
+
+        let mut messages = Vec::new();
         if amount > 0 {
-            self.undo_redo
-                .push_action(DeckMessage::Dec(id, part_type, amount));
+            messages.push(DeckMessage::Inc(id, part.into(), amount));
+        }
+        let main_override = match part {
+            DeckPart::Main if cards[id].card_type.is_extra_deck_monster() => Some(true),
+            DeckPart::Extra => Some(false),
+            DeckPart::Main | DeckPart::Side => None,
+        };
+        if let Some(message) = main_override.and_then(|value| self.override_message(id, value)) {
+            self.apply(message);
+            messages.push(message);
+        }
+
+        if !messages.is_empty() {
+            self.undo_redo.push_group(messages);
+        }
+        Ok(())
+    }
+
+    /// See [`common::deck::Deck::set_main_override`].
+    pub fn set_main_override(&mut self, id: Id, value: bool) {
+        if let Some(message) = self.override_message(id, value) {
+            self.apply(message);
+            self.undo_redo.push_action(message);
+        }
+    }
+
+    fn override_message(&self, id: Id, value: bool) -> Option<DeckMessage> {
+        (self.has_main_override(id) != value).then_some(DeckMessage::Override(id, value))
+    }
+
+    /// Lock all current copies of a card, or unlock it if it is already locked.
+    ///
+    /// See [`common::deck::Deck::set_locked`].
+    pub fn toggle_locked(&mut self, id: Id) {
+        let locked = self.locked_count(id);
+        let count = if locked > 0 { 0 } else { self.total_count(id) };
+        if count != locked {
+            let message = DeckMessage::Lock(id, locked, count);
+            self.apply(message);
+            self.undo_redo.push_action(message);
+        }
+    }
+
+    /// Choose the printing of a card in the deck, see [`common::deck::Deck::set_printing`].
+    pub fn set_printing(&mut self, id: Id, password: Option<CardPassword>) {
+        let printing = self.printing(id);
+        if self.total_count(id) > 0 && printing != password {
+            let message = DeckMessage::Printing(id, printing, password);
+            self.apply(message);
+            self.undo_redo.push_action(message);
+        }
+    }
+
+    /// Messages which change the flags of a card to the ones it has in `deck`.
+    ///
+    /// Locks are limited to the copies in `deck`.
+    fn flag_messages(&self, id: Id, deck: &common::deck::Deck) -> Vec<DeckMessage> {
+        let locked = self.locked_count(id);
+        let new_locked = deck.locked_count(id).min(deck.total_count(id));
+
+        let mut result = Vec::new();
+        result.extend(self.override_message(id, deck.has_main_override(id)));
+        if locked != new_locked {
+            result.push(DeckMessage::Lock(id, locked, new_locked));
         }
+        let printing = self.printing(id);
+        let new_printing = deck.printing(id);
+        if printing != new_printing {
+            result.push(DeckMessage::Printing(id, printing, new_printing));
+        }
+        result
+    }
+
+    /// Replace the deck content, recording the changes as a single undo step.
+    ///
+    /// Main deck overrides, printings and locks are taken from `deck`.
+    pub fn set_entries(&mut self, deck: &common::deck::Deck) {
+        // Removing the last copy of a card resets its flags, so flags are reset before the counts
+        // change and set afterwards, which lets undo restore them
+        let mut ids = self
+            .entries()
+            .chain(deck.entries())
+            .map(|entry| entry.id())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        let (resets, sets): (Vec<_>, Vec<_>) = ids
+            .into_iter()
+            .flat_map(|id| self.flag_messages(id, deck))
+            .partition(|message| message.is_reset());
+
+        let counts = self.deck.diff(deck).into_iter().map(
+            |CountChange {
+                 id,
+                 part_type,
+                 from,
+                 to,
+             }| {
+                if from < to {
+                    DeckMessage::Inc(id, part_type, to - from)
+                } else {
+                    DeckMessage::Dec(id, part_type, from - to)
+                }
+            },
+        );
+
+        self.apply_group(resets.into_iter().chain(counts).chain(sets).collect());
+    }
+
+    /// Remove all cards, including locked ones, recording the removal as a single undo step.
+    pub fn clear(&mut self) {
+        self.set_entries(&common::deck::Deck::default());
+    }
+
+    /// Add the content of another deck, recording the changes as a single undo step.
+    ///
+    /// The total copies of each card are capped at its limit. Printings are taken from `deck` for
+    /// cards without a chosen printing. Returns the cards which were capped, each with the number
+    /// of skipped copies.
+    pub fn merge_entries(
+        &mut self,
+        deck: &common::deck::Deck,
+        cards: &CardData,
+        limits: &CopyLimits,
+    ) -> Vec<(Id, u8)> {
+        let mut messages = Vec::new();
+        let mut skipped = Vec::new();
+
+        for entry in deck.entries() {
+            let id = entry.id();
+            let mut available = limits
+                .allowed(id, cards)
+                .saturating_sub(self.total_count(id));
+            let mut capped = 0;
+            let mut added = false;
+
+            for part_type in [PartType::Playing, PartType::Side] {
+                let requested = entry.count(part_type);
+                let amount = requested.min(available);
+                available -= amount;
+                capped += requested - amount;
+
+                if amount > 0 {
+                    messages.push(DeckMessage::Inc(id, part_type, amount));
+                    added = true;
+                }
+            }
+
+            let printing = deck.printing(id);
+            let present = added || self.total_count(id) > 0;
+            if printing.is_some() && self.printing(id).is_none() && present {
+                messages.push(DeckMessage::Printing(id, None, printing));
+            }
+
+            if capped > 0 {
+                skipped.push((id, capped));
+            }
+        }
+
+        self.apply_group(messages);
+        skipped
     }
 
     pub fn undo(&mut self) {
-        if let Some(message) = self.undo_redo.undo() {
+        for message in self.undo_redo.undo_group() {
             self.apply(message);
         }
     }
 
     pub fn redo(&mut self) {
-        if let Some(message) = self.undo_redo.redo() {
+        for message in self.undo_redo.redo_group() {
             self.apply(message);
         }
     }
 
+    /// Decode a deck, dropping entries for cards which are not part of the current card data.
+    ///
+    /// Returns the deck and the number of dropped entries. If the undo history can not be decoded
+    /// (e.g. because it refers to dropped cards), it is reset instead of failing the whole deck.
+    #[must_use]
+    pub fn decode_skipping_unknown(text: &str) -> Option<(Self, usize)> {
+        let (version, text) = match text.strip_prefix('v') {
+            Some(text) => {
+                let (version, text) = text.split_once(' ')?;
+                (version.parse().ok()?, text)
+            }
+            None => (0, text),
+        };
+
+        match version {
+            // Version 1 only introduced the version prefix, version 2 added main deck overrides and
+            // version 3 added locks
+            0..=3 => Self::decode_v1(text),
+            // Version 4 added the deck meta in front of the entries, version 5 added printings
+            4 | 5 => {
+                let (meta, text) = text.split_once(' ')?;
+                let meta = DeckMeta::decode(meta)?;
+                let (mut deck, dropped) = Self::decode_v1(text)?;
+                deck.meta = meta;
+                Some((deck, dropped))
+            }
+            _ => None,
+        }
+    }
+
+    fn decode_v1(text: &str) -> Option<(Self, usize)> {
+        let (entries, undo_redo) = text.split_once(' ')?;
+
+        let mut dropped = 0;
+        let mut deck_entries = Vec::new();
+        let mut entry_flags = Vec::new();
+        if !entries.is_empty() {
+            for entry in entries.split(',') {
+                match decode_entry(entry)? {
+                    Ok((entry, flags)) => {
+                        entry_flags.push((entry.id(), flags));
+                        deck_entries.push(entry);
+                    }
+                    Err(_) => dropped += 1,
+                }
+            }
+        }
+        let mut deck = common::deck::Deck::new(deck_entries);
+        for (id, flags) in entry_flags {
+            deck.set_main_override(id, flags.main_override);
+            deck.set_locked(id, flags.locked.min(deck.total_count(id)));
+            deck.set_printing(id, flags.printing);
+        }
+
+        let undo_redo = TextEncoding::decode(undo_redo).unwrap_or_default();
+
+        Some((
+            Self {
+                deck,
+                meta: DeckMeta::default(),
+                undo_redo,
+            },
+            dropped,
+        ))
+    }
+
     pub fn reset_history(&mut self) {
         self.undo_redo = UndoRedo::default();
     }
 
+    fn apply_group(&mut self, messages: Vec<DeckMessage>) {
+        for message in &messages {
+            self.apply(*message);
+        }
+        self.undo_redo.push_group(messages);
+    }
+
     fn apply(&mut self, message: DeckMessage) {
         match message {
             DeckMessage::Inc(id, part_type, amount) => {
@@ -154,7 +590,15 @@ impl Deck {
             }
             DeckMessage::Dec(id, part_type, amount) => {
                 debug_assert_eq!(amount, self.deck.decrement(id, part_type, amount));
+                // Histories saved before locks were part of them can remove locked copies
+                let total = self.total_count(id);
+                if self.locked_count(id) > total {
+                    self.deck.set_locked(id, total);
+                }
             }
+            DeckMessage::Override(id, value) => self.deck.set_main_override(id, value),
+            DeckMessage::Lock(id, _, to) => self.deck.set_locked(id, to),
+            DeckMessage::Printing(id, _, to) => self.deck.set_printing(id, to),
         }
     }
 }
@@ -169,13 +613,25 @@ impl Deref for Deck {
 
 impl TextEncoding for Deck {
     fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
-        let mut entries = self.deck.entries();
-        if let Some(entry) = entries.next() {
-            entry.encode(writer)?;
-        }
-        for entry in entries {
-            writer.write_char(',')?;
+        write!(writer, "v{ENCODING_VERSION} ")?;
+        self.meta.encode(writer)?;
+        writer.write_char(' ')?;
+
+        for (idx, entry) in self.deck.entries().enumerate() {
+            if idx > 0 {
+                writer.write_char(',')?;
+            }
             entry.encode(writer)?;
+            if self.deck.has_main_override(entry.id()) {
+                write!(writer, ":{MAIN_OVERRIDE_FLAG}")?;
+            }
+            let locked = self.deck.locked_count(entry.id());
+            if locked > 0 {
+                write!(writer, ":{LOCKED_FLAG}{locked}")?;
+            }
+            if let Some(printing) = self.deck.printing(entry.id()) {
+                write!(writer, ":{PRINTING_FLAG}{printing}")?;
+            }
         }
 
         writer.write_char(' ')?;
@@ -183,28 +639,19 @@ impl TextEncoding for Deck {
     }
 
     fn decode(text: &str) -> Option<Self> {
-        let (entries, undo_redo) = text.split_once(' ')?;
-
-        let entries = if entries.is_empty() {
-            Vec::new()
-        } else {
-            entries
-                .split(',')
-                .map(DeckEntry::decode)
-                .collect::<Option<_>>()?
-        };
-        let deck = common::deck::Deck::new(entries);
-
-        let undo_redo = TextEncoding::decode(undo_redo)?;
-
-        Some(Self { deck, undo_redo })
+        Self::decode_skipping_unknown(text).map(|(deck, _)| deck)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use common::{assert_part_eq, card::test_util::make_card, card_data::CardDataStorage};
+    use common::{
+        assert_part_eq,
+        card::test_util::{make_card, make_extra_deck_card},
+        card_data::CardDataStorage,
+    };
     use leptos::provide_context;
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -224,8 +671,8 @@ mod test {
     fn encoding() {
         const ID: Id = Id::new(0);
         const OTHER_ID: Id = Id::new(1);
-        const AMOUNT: u8 = 43;
-        const OTHER_AMOUNT: u8 = 65;
+        const AMOUNT: u8 = 2;
+        const OTHER_AMOUNT: u8 = 3;
 
         let cards = vec![make_card(1234), make_card(9876)];
         let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
@@ -236,8 +683,8 @@ mod test {
             (PartType::Side, PartType::Playing),
         ] {
             let mut deck = Deck::default();
-            deck.increment(ID, current, AMOUNT);
-            deck.increment(OTHER_ID, current, OTHER_AMOUNT);
+            deck.increment(ID, current, AMOUNT).unwrap();
+            deck.increment(OTHER_ID, current, OTHER_AMOUNT).unwrap();
             deck.undo();
             deck.undo();
             deck.redo();
@@ -256,4 +703,428 @@ mod test {
             assert_part_eq!(&deck, other, []);
         }
     }
+
+    #[test]
+    fn decoding_previous_versions() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_card(9876)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        for text in [
+            // Version 0
+            "1234:2:1 1;+p1234:2,+s1234:1,+s9876:3",
+            // Version 1
+            "v1 1234:2:1 1;+p1234:2,+s1234:1,+s9876:3",
+        ] {
+            let mut deck = Deck::decode(text).unwrap();
+            assert_part_eq!(&deck, PartType::Playing, &[(ID, 2)]);
+            assert_part_eq!(&deck, PartType::Side, &[(ID, 1)]);
+
+            deck.redo();
+            assert_part_eq!(&deck, PartType::Side, &[(ID, 1), (OTHER_ID, 3)]);
+        }
+
+        assert!(Deck::decode("v999 1234:2:1 0;").is_none());
+    }
+
+    #[test]
+    fn decoding_unknown_cards() {
+        const ID: Id = Id::new(0);
+
+        let cards = vec![make_card(1234)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let (mut deck, dropped) =
+            Deck::decode_skipping_unknown("1234:2:1,9876:1:0 0;+p1234:2,+s1234:1,+p9876:1")
+                .unwrap();
+        assert_eq!(dropped, 1);
+        assert_part_eq!(&deck, PartType::Playing, &[(ID, 2)]);
+        assert_part_eq!(&deck, PartType::Side, &[(ID, 1)]);
+
+        // History referring to the unknown card is discarded
+        deck.undo();
+        assert_part_eq!(&deck, PartType::Playing, &[(ID, 2)]);
+        assert_part_eq!(&deck, PartType::Side, &[(ID, 1)]);
+
+        assert!(Deck::decode_skipping_unknown("1234:x:1 0;").is_none());
+    }
+
+    #[test]
+    fn encoding_main_override() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_extra_deck_card(1234), make_extra_deck_card(9876)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 1).unwrap();
+        deck.increment(OTHER_ID, PartType::Playing, 1).unwrap();
+        deck.set_main_override(ID, true);
+
+        let deck = Deck::decode(&deck.encode_string()).unwrap();
+        assert!(deck.has_main_override(ID));
+        assert!(!deck.has_main_override(OTHER_ID));
+
+        assert!(Deck::decode("v2 1234:1:0:x 0;").is_none());
+    }
+
+    #[test]
+    fn main_override_undo() {
+        const ID: Id = Id::new(0);
+
+        let cards = vec![make_extra_deck_card(1234)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment_in_part(ID, DeckPart::Main, 1, MAX_COPIES, &card_data)
+            .unwrap();
+        assert!(deck.has_main_override(ID));
+
+        let mut deck = Deck::decode(&deck.encode_string()).unwrap();
+        deck.undo();
+        assert_eq!(deck.total_count(ID), 0);
+        assert!(!deck.has_main_override(ID));
+
+        deck.redo();
+        deck.set_main_override(ID, false);
+        deck.undo();
+        assert!(deck.has_main_override(ID));
+        assert_eq!(deck.total_count(ID), 1);
+
+        // Removing the last copy resets the override until it is undone
+        for remove in [
+            |deck: &mut Deck| deck.decrement(ID, PartType::Playing, 1),
+            Deck::clear,
+        ] {
+            remove(&mut deck);
+            assert!(!deck.has_main_override(ID));
+            deck.undo();
+            assert!(deck.has_main_override(ID));
+        }
+
+        // Malformed history is reset
+        let mut deck = Deck::decode("v4 ;;;; 1234:1:0 1;m1234:2").unwrap();
+        deck.undo();
+        assert_eq!(deck.total_count(ID), 1);
+    }
+
+    #[test]
+    fn locked_cards() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_card(9876)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+        deck.increment(OTHER_ID, PartType::Playing, 1).unwrap();
+        deck.toggle_locked(ID);
+        deck.increment(ID, PartType::Side, 1).unwrap();
+
+        let mut deck = Deck::decode(&deck.encode_string()).unwrap();
+        assert_eq!(deck.locked_count(ID), 2);
+        assert_eq!(deck.locked_count(OTHER_ID), 0);
+
+        // Only the copies added after locking can be removed
+        deck.decrement(ID, PartType::Playing, 2);
+        deck.decrement(ID, PartType::Side, 1);
+        assert_eq!(deck.total_count(ID), 2);
+
+        deck.toggle_locked(ID);
+        deck.decrement(ID, PartType::Playing, 1);
+        deck.decrement(ID, PartType::Side, 1);
+        assert_eq!(deck.total_count(ID), 0);
+
+        assert!(Deck::decode("v3 1234:1:0:m:l1 0;").is_some());
+        assert!(Deck::decode("v3 1234:1:0:lx 0;").is_none());
+    }
+
+    #[test]
+    fn locked_cards_undo() {
+        const ID: Id = Id::new(0);
+
+        let cards = vec![make_card(1234)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+        deck.toggle_locked(ID);
+
+        let mut deck = Deck::decode(&deck.encode_string()).unwrap();
+        deck.undo();
+        assert_eq!(deck.locked_count(ID), 0);
+        deck.redo();
+        assert_eq!(deck.locked_count(ID), 2);
+
+        // Clearing removes locked copies, undo restores them together with the lock
+        deck.clear();
+        deck.undo();
+        assert_eq!(deck.total_count(ID), 2);
+        assert_eq!(deck.locked_count(ID), 2);
+
+        // Locks are limited to the copies in the deck
+        let mut imported = common::deck::Deck::default();
+        imported.increment(ID, PartType::Playing, 1);
+        imported.set_locked(ID, 3);
+        deck.set_entries(&imported);
+        assert_eq!(deck.locked_count(ID), 1);
+        deck.undo();
+        assert_eq!(deck.locked_count(ID), 2);
+
+        assert_eq!(
+            Deck::decode("v3 1234:1:0:l3 0;").unwrap().locked_count(ID),
+            1
+        );
+
+        // Undoing histories saved without locks limits the locks to the remaining copies
+        let mut deck = Deck::decode("v3 1234:2:0:l2 0;+p1234:1,+p1234:1").unwrap();
+        deck.undo();
+        assert_eq!(deck.total_count(ID), 1);
+        assert_eq!(deck.locked_count(ID), 1);
+    }
+
+    #[test]
+    fn printings() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let mut card = make_card(1234);
+        card.all_passwords = vec![1234, 1235];
+        let cards = vec![card, make_card(9876)];
+        provide_context(CardData::from(CardDataStorage::new(cards, vec![])));
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+        deck.increment(OTHER_ID, PartType::Playing, 1).unwrap();
+        deck.set_printing(ID, Some(1235));
+
+        let mut deck = Deck::decode(&deck.encode_string()).unwrap();
+        assert_eq!(deck.printing(ID), Some(1235));
+        assert_eq!(deck.printing(OTHER_ID), None);
+
+        deck.undo();
+        assert_eq!(deck.printing(ID), None);
+        deck.redo();
+
+        // Removing the last copy resets the printing until it is undone
+        deck.decrement(ID, PartType::Playing, 2);
+        assert_eq!(deck.printing(ID), None);
+        deck.undo();
+        assert_eq!(deck.printing(ID), Some(1235));
+
+        // Printings of other cards fall back to the main printing
+        let deck = Deck::decode("v5 ;;;; 1234:1:0:p1235,9876:1:0:p1235 0;").unwrap();
+        assert_eq!(deck.printing(ID), Some(1235));
+        assert_eq!(deck.printing(OTHER_ID), None);
+        assert!(Deck::decode("v5 ;;;; 1234:1:0:px 0;").is_none());
+    }
+
+    #[test]
+    fn merge_entries_printings() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let mut card = make_card(1234);
+        card.all_passwords = vec![1234, 1235, 1236];
+        let mut other_card = make_card(9876);
+        other_card.all_passwords = vec![9876, 9877];
+        let card_data = CardData::from(CardDataStorage::new(vec![card, other_card], vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 1).unwrap();
+        deck.set_printing(ID, Some(1235));
+
+        let mut imported = common::deck::Deck::default();
+        imported.increment(ID, PartType::Playing, 1);
+        imported.set_printing(ID, Some(1236));
+        imported.increment(OTHER_ID, PartType::Playing, 1);
+        imported.set_printing(OTHER_ID, Some(9877));
+        deck.merge_entries(&imported, &card_data, &CopyLimits::default());
+
+        // Printings chosen before are kept
+        assert_eq!(deck.printing(ID), Some(1235));
+        assert_eq!(deck.printing(OTHER_ID), Some(9877));
+
+        deck.undo();
+        assert_eq!(deck.printing(OTHER_ID), None);
+    }
+
+    #[test]
+    fn encoding_meta() {
+        const ID: Id = Id::new(0);
+
+        let cards = vec![make_card(1234)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 1).unwrap();
+        deck.set_meta(DeckMeta {
+            name: "My Deck".to_owned(),
+            cover: Some(ID),
+            ..DeckMeta::default()
+        });
+
+        let mut decoded = Deck::decode(&deck.encode_string()).unwrap();
+        assert_eq!(decoded.meta(), deck.meta());
+        decoded.undo();
+        assert_eq!(decoded.meta(), deck.meta());
+
+        let deck = Deck::decode("v3 1234:1:0 0;").unwrap();
+        assert_eq!(deck.meta(), &DeckMeta::default());
+        assert!(Deck::decode("v4 1234:1:0 0;").is_none());
+    }
+
+    #[test]
+    fn set_entries_single_undo() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_card(9876)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+
+        let mut imported = common::deck::Deck::default();
+        imported.increment(ID, PartType::Side, 1);
+        imported.increment(OTHER_ID, PartType::Playing, 3);
+        deck.set_entries(&imported);
+        assert_part_eq!(&deck, PartType::Playing, &[(OTHER_ID, 3)]);
+        assert_part_eq!(&deck, PartType::Side, &[(ID, 1)]);
+
+        deck.undo();
+        assert_part_eq!(&deck, PartType::Playing, &[(ID, 2)]);
+        assert_part_eq!(&deck, PartType::Side, []);
+
+        deck.redo();
+        assert_part_eq!(&deck, PartType::Playing, &[(OTHER_ID, 3)]);
+        assert_part_eq!(&deck, PartType::Side, &[(ID, 1)]);
+    }
+
+    #[test]
+    fn clear_undo() {
+        const ID: Id = Id::new(0);
+
+        let cards = vec![make_card(1234)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+        deck.increment(ID, PartType::Side, 1).unwrap();
+        deck.toggle_locked(ID);
+
+        deck.clear();
+        assert_eq!(deck.total_count(ID), 0);
+
+        deck.undo();
+        assert_part_eq!(&deck, PartType::Playing, &[(ID, 2)]);
+        assert_part_eq!(&deck, PartType::Side, &[(ID, 1)]);
+    }
+
+    #[test]
+    fn merge_entries_capped() {
+        const ID: Id = Id::new(0);
+        const OTHER_ID: Id = Id::new(1);
+
+        let cards = vec![make_card(1234), make_card(9876)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+
+        let mut imported = common::deck::Deck::default();
+        imported.increment(ID, PartType::Playing, 1);
+        imported.increment(ID, PartType::Side, 1);
+        imported.increment(OTHER_ID, PartType::Side, 2);
+        assert_eq!(
+            deck.merge_entries(&imported, &card_data, &CopyLimits::default()),
+            [(ID, 1)]
+        );
+        assert_part_eq!(&deck, PartType::Playing, &[(ID, 3)]);
+        assert_part_eq!(&deck, PartType::Side, &[(OTHER_ID, 2)]);
+
+        deck.undo();
+        assert_part_eq!(&deck, PartType::Playing, &[(ID, 2)]);
+        assert_part_eq!(&deck, PartType::Side, []);
+    }
+
+    /// Arbitrary text, or text close to the deck encoding of the cards `1234` and `9876`.
+    fn deck_text() -> impl Strategy<Value = String> {
+        const ENTRY: &str = "(1234|9876|5555):[0-3]:[0-3](:m)?(:l[0-3])?(:p(1235|9876))?";
+        const MESSAGE: &str = "([+-][ps](1234|9876|5555):[0-3]|m(1234|9876|5555):[0-2]|l(1234|9876|5555):[0-3]:[0-3]|p(1234|9876):(1235)?:(1235)?)";
+
+        let encoding = format!(
+            "(v[0-5] )?({ENTRY}(,{ENTRY}){{0,3}})? [0-5];({MESSAGE}([,&]{MESSAGE}){{0,4}})?"
+        );
+        prop_oneof![
+            any::<String>(),
+            proptest::string::string_regex(&encoding).unwrap(),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn decoding_arbitrary_text(text in deck_text()) {
+            let mut card = make_card(1234);
+            card.all_passwords = vec![1234, 1235];
+            let cards = vec![card, make_extra_deck_card(9876)];
+            provide_context(CardData::from(CardDataStorage::new(cards, vec![])));
+
+            if let Some(deck) = Deck::decode(&text) {
+                let encoded = deck.encode_string();
+                let decoded = Deck::decode(&encoded).unwrap();
+                prop_assert_eq!(decoded.encode_string(), encoded);
+            }
+        }
+
+        #[test]
+        fn encoding_random_decks(
+            operations in prop::collection::vec((0..7_u8, any::<bool>(), 0..4_u8), 0..32),
+        ) {
+            let mut card = make_card(1234);
+            card.all_passwords = vec![1234, 1235];
+            let cards = vec![card, make_extra_deck_card(9876)];
+            provide_context(CardData::from(CardDataStorage::new(cards, vec![])));
+
+            let mut deck = Deck::default();
+            for (operation, is_other, amount) in operations {
+                let id = Id::new(is_other.into());
+                let part_type = if amount % 2 == 0 { PartType::Playing } else { PartType::Side };
+                match operation {
+                    0 => {
+                        let _ = deck.increment(id, part_type, amount);
+                    }
+                    1 => deck.decrement(id, part_type, amount),
+                    2 => deck.toggle_locked(id),
+                    3 => deck.set_main_override(id, amount > 1),
+                    4 => deck.set_printing(Id::new(0), (amount > 1).then_some(1235)),
+                    5 => deck.undo(),
+                    _ => deck.redo(),
+                }
+
+                let encoded = deck.encode_string();
+                let decoded = Deck::decode(&encoded).unwrap();
+                prop_assert_eq!(decoded.encode_string(), encoded);
+                prop_assert!(decoded.entries().eq(deck.entries()));
+                for entry in deck.entries() {
+                    prop_assert!(deck.locked_count(entry.id()) <= deck.total_count(entry.id()));
+                }
+            }
+        }
+    }
 }
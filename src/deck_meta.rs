@@ -0,0 +1,147 @@
+//! Descriptive information about a deck, stored and shared together with its content.
+
+use std::fmt::{self, Write};
+
+use common::card_data::{CardData, Id};
+use leptos::expect_context;
+
+use crate::text_encoding::TextEncoding;
+
+/// Characters which are escaped as `%<hex>`, as they separate the fields of the deck and app
+/// state encodings.
+const ESCAPED: &[char] = &['%', ' ', ';', '\n', '\r', '\t'];
+
+fn escape(text: &str, writer: &mut impl Write) -> fmt::Result {
+    for char in text.chars() {
+        if ESCAPED.contains(&char) {
+            write!(writer, "%{:02X}", u32::from(char))?;
+        } else {
+            writer.write_char(char)?;
+        }
+    }
+    Ok(())
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char == '%' {
+            let code = chars.next()?.to_digit(16)? * 16 + chars.next()?.to_digit(16)?;
+            result.push(char::from_u32(code)?);
+        } else {
+            result.push(char);
+        }
+    }
+    Some(result)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeckMeta {
+    pub name: String,
+    pub author: String,
+    /// Free text, e.g. the banlist or event the deck is built for
+    pub format: String,
+    pub notes: String,
+    /// Card representing the deck
+    pub cover: Option<Id>,
+}
+
+impl DeckMeta {
+    /// Name to use for exported files, `fallback` if the deck has no usable name.
+    ///
+    /// Characters which are not allowed in file names on common systems are replaced.
+    #[must_use]
+    pub fn file_stem(&self, fallback: &str) -> String {
+        let stem = self
+            .name
+            .trim()
+            .replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
+        if stem.is_empty() {
+            fallback.to_owned()
+        } else {
+            stem
+        }
+    }
+}
+
+impl TextEncoding for DeckMeta {
+    fn encode(&self, writer: &mut impl Write) -> fmt::Result {
+        for text in [&self.name, &self.author, &self.format, &self.notes] {
+            escape(text, writer)?;
+            writer.write_char(';')?;
+        }
+        if let Some(cover) = self.cover {
+            let cards = expect_context::<CardData>();
+            write!(writer, "{}", cards.password_for_id(cover))?;
+        }
+        Ok(())
+    }
+
+    /// A cover card which is not part of the current card data is dropped.
+    fn decode(text: &str) -> Option<Self> {
+        let mut parts = text.split(';');
+        let mut next_text = || unescape(parts.next()?);
+        let name = next_text()?;
+        let author = next_text()?;
+        let format = next_text()?;
+        let notes = next_text()?;
+
+        let cover = match parts.next()? {
+            "" => None,
+            password => expect_context::<CardData>().id_for_password(password.parse().ok()?),
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            name,
+            author,
+            format,
+            notes,
+            cover,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{card::test_util::make_card, card_data::CardDataStorage};
+    use leptos::provide_context;
+
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        let cards = vec![make_card(1234)];
+        provide_context(CardData::from(CardDataStorage::new(cards, vec![])));
+
+        let meta = DeckMeta {
+            name: "Blue-Eyes; 100% Dragons".to_owned(),
+            author: "Kaiba".to_owned(),
+            format: String::new(),
+            notes: "Line one\nLine two".to_owned(),
+            cover: Some(Id::new(0)),
+        };
+        assert_eq!(
+            meta.encode_string(),
+            "Blue-Eyes%3B%20100%25%20Dragons;Kaiba;;Line%20one%0ALine%20two;1234"
+        );
+        assert_eq!(DeckMeta::decode(&meta.encode_string()), Some(meta));
+
+        assert_eq!(DeckMeta::decode(";;;;"), Some(DeckMeta::default()));
+        assert_eq!(DeckMeta::decode(";;;;9876"), Some(DeckMeta::default()));
+        assert!(DeckMeta::decode("%4;;;;").is_none());
+        assert!(DeckMeta::decode(";;;").is_none());
+    }
+
+    #[test]
+    fn file_stem() {
+        let mut meta = DeckMeta::default();
+        assert_eq!(meta.file_stem("deck"), "deck");
+
+        meta.name = " Dragons: Reloaded? ".to_owned();
+        assert_eq!(meta.file_stem("deck"), "Dragons_ Reloaded_");
+    }
+}
@@ -1,8 +1,14 @@
 //! Order by color, then "impact", then name
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt};
 
-use common::card::{Card, CardType, MonsterEffect, MonsterStats, MonsterType, SpellType, TrapType};
+use crate::{i18n::Text, text_encoding::TextEncoding, ui::settings::Setting};
+use common::{
+    card::{
+        Attribute, Card, CardType, MonsterEffect, MonsterStats, MonsterType, SpellType, TrapType,
+    },
+    deck_part::DeckPart,
+};
 
 const fn spell_index(spell_type: SpellType) -> u32 {
     match spell_type {
@@ -94,13 +100,127 @@ fn type_indices(card_type: &CardType) -> Vec<u32> {
     result
 }
 
+/// Groups placed before the color order of the deck views.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Only the color order
+    #[default]
+    Color,
+    /// Extra deck monsters first, regardless of their color
+    ExtraDeck,
+    /// Monsters by attribute, followed by spells and traps
+    Attribute,
+}
+
+impl Grouping {
+    /// Index of the group of the card, higher ones are ordered first.
+    fn group_index(self, card_type: &CardType) -> u32 {
+        match (self, card_type) {
+            (Self::Color, _) | (Self::Attribute, CardType::Trap(_)) => 0,
+            (Self::ExtraDeck, _) => card_type.is_extra_deck_monster().into(),
+            (Self::Attribute, CardType::Monster { attribute, .. }) => {
+                // Unknown attributes are grouped after all known ones
+                let position = Attribute::iter()
+                    .position(|other| other == *attribute)
+                    .unwrap_or(Attribute::iter().count());
+                2 + u32::try_from(Attribute::iter().count() - position).unwrap()
+            }
+            (Self::Attribute, CardType::Spell(_)) => 1,
+        }
+    }
+
+    /// Order by group, then by color, "impact" and name.
+    ///
+    /// Cards sharing a name are ordered by password, so only the same card compares equal and
+    /// sorting gives the same order on every update.
+    #[must_use]
+    pub fn order(self, lhs: &Card, rhs: &Card) -> Ordering {
+        Ordering::Equal
+            .then(
+                self.group_index(&lhs.card_type)
+                    .cmp(&self.group_index(&rhs.card_type))
+                    .reverse(),
+            )
+            .then(
+                type_indices(&lhs.card_type)
+                    .cmp(&type_indices(&rhs.card_type))
+                    .reverse(),
+            )
+            .then(lhs.name.cmp(rhs.name))
+            .then(lhs.password.cmp(&rhs.password))
+    }
+}
+
+impl Setting for Grouping {
+    const STORAGE_KEY: &'static str = "grouping";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Color, Self::ExtraDeck, Self::Attribute].into_iter()
+    }
+
+    /// Identifier also used in the text encoding of [`PartGroupings`].
+    fn key(self) -> &'static str {
+        match self {
+            Self::Color => "color",
+            Self::ExtraDeck => "extra",
+            Self::Attribute => "attribute",
+        }
+    }
+
+    fn name(self) -> Text {
+        match self {
+            Self::Color => Text::GroupByColor,
+            Self::ExtraDeck => Text::GroupExtraDeck,
+            Self::Attribute => Text::GroupByAttribute,
+        }
+    }
+}
+
+/// Grouping chosen for individual deck parts, overriding the [`Grouping`] of the menu.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PartGroupings([Option<Grouping>; 3]);
+
+impl PartGroupings {
+    #[must_use]
+    pub fn get(self, part: DeckPart) -> Option<Grouping> {
+        self.0[part as usize]
+    }
+
+    /// Set the grouping of `part`, `None` to follow the menu again.
+    pub fn set(&mut self, part: DeckPart, grouping: Option<Grouping>) {
+        self.0[part as usize] = grouping;
+    }
+}
+
+impl TextEncoding for PartGroupings {
+    /// Groupings of the parts separated by commas, empty for parts following the menu.
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        for (idx, grouping) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writer.write_char(',')?;
+            }
+            if let Some(grouping) = grouping {
+                writer.write_str(grouping.key())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        let mut result = Self::default();
+        let mut parts = text.split(',');
+        for grouping in &mut result.0 {
+            *grouping = match parts.next()? {
+                "" => None,
+                key => Some(Grouping::iter().find(|grouping| grouping.key() == key)?),
+            };
+        }
+        parts.next().is_none().then_some(result)
+    }
+}
+
+/// Order of [`Grouping::Color`], used wherever the grouping is not configurable.
 #[must_use]
 pub fn deck_order(lhs: &Card, rhs: &Card) -> Ordering {
-    Ordering::Equal
-        .then(
-            type_indices(&lhs.card_type)
-                .cmp(&type_indices(&rhs.card_type))
-                .reverse(),
-        )
-        .then(lhs.name.cmp(rhs.name))
+    Grouping::Color.order(lhs, rhs)
 }
@@ -0,0 +1,514 @@
+//! Translations of the user interface.
+
+use common::{
+    card::{CardLimit, EffectTag},
+    deck_part::DeckPart,
+    export_target::ExportTarget,
+};
+
+use crate::ui::settings::Setting;
+
+/// Language of the user interface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Setting for Locale {
+    const STORAGE_KEY: &'static str = "locale";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::English, Self::German].into_iter()
+    }
+
+    /// Language tag prefix as used by `navigator.language`.
+    fn key(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+        }
+    }
+
+    fn name(self) -> Text {
+        Text::Language(self)
+    }
+
+    /// The browser language, if it is supported.
+    fn initial() -> Self {
+        let language = leptos::window().navigator().language().unwrap_or_default();
+        Self::iter()
+            .find(|locale| language.starts_with(locale.key()))
+            .unwrap_or_default()
+    }
+}
+
+impl Locale {
+    /// Name of the language, in the language itself.
+    fn native_name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+}
+
+/// Translatable text of the user interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    New,
+    Import,
+    Merge,
+    ImportPasswords,
+    DeckInfo,
+    ExportFor(ExportTarget),
+    /// Copy a link which imports the deck
+    CopyLink,
+    ExportReport,
+    ExportImage,
+    /// Prompt for the title of the exported deck image
+    ImageTitle,
+    EffectTag(EffectTag),
+    /// Number of cards exceeding their copy limit
+    TooManyCopies(usize),
+    /// Number of Extra deck monsters kept in the Main deck
+    MainOverrides(usize),
+    PartTooSmall(DeckPart, u8),
+    PartTooLarge(DeckPart, u8),
+
+    /// Name of a language, in the language itself
+    Language(Locale),
+    NormalLayout,
+    CompactLayout,
+    SystemTheme,
+    LightTheme,
+    DarkTheme,
+    RightClickDeletes,
+    RightClickOpensMenu,
+    NoTooltipDelay,
+    ShortTooltipDelay,
+    LongTooltipDelay,
+    NoEngineMarkers,
+    MarkEngineCards,
+    GroupByColor,
+    GroupExtraDeck,
+    GroupByAttribute,
+    InfiniteScroll,
+    Pages,
+
+    /// Sizes in kB, the total is missing if the server did not send it
+    DownloadingCardData {
+        received: usize,
+        total: Option<usize>,
+    },
+    /// Sizes in kB of the compressed data
+    DecompressingCardData {
+        processed: usize,
+        total: usize,
+    },
+
+    Staples,
+    NewCards,
+    AllCards,
+    BrowsingStaples,
+    BrowsingNewCards,
+    BrowsingAllCards,
+    NameFilter,
+    DescriptionFilter,
+    OwnedOnly,
+    PartOnly(DeckPart),
+    RemoveFilter,
+    ClearAll,
+
+    Tributes,
+    /// Explanation of the tributes tag
+    TributesHint,
+    PendulumScale,
+    Pendulum,
+    /// Where a Pendulum monster of the Extra deck is built into
+    PendulumExtraDeck,
+    /// Where a Pendulum monster of the Main deck is built into, and where it goes after leaving
+    /// the field
+    PendulumMainDeck,
+    CardLimit(CardLimit),
+    CopyPassword,
+    Copied,
+    /// Prompt showing the password when it can not be copied
+    CardPassword,
+    SetLimit,
+    /// Copy limit overriding the ban list
+    Limit(u8),
+    /// Prompt for the copy limit of the named card
+    LimitPrompt(&'static str),
+    Lock,
+    Unlock,
+    Locked,
+    Tuner,
+    AddedTo(DeckPart),
+    /// Copy limit of a card outside of the deck
+    AtMostAllowed(u8),
+    /// Copies in the deck and the copy limit
+    OverLimit(u8, u8),
+    NotPlayableFrom(DeckPart),
+    /// Copies needed beyond the owned ones
+    NeedMore(u8),
+
+    Tools,
+    MoveUp,
+    MoveDown,
+    Errors,
+    SynchroMaterials,
+    ExtraDeckMaterials,
+    CardTypes,
+    ExtraDeckCardTypes,
+    ExtraDeckRules,
+    MonsterLevels,
+    MonsterAtk,
+    EffectTags,
+    MissingCards,
+    StapleSuggestions,
+    Snapshots,
+    CardData,
+}
+
+impl Text {
+    #[must_use]
+    pub fn localize(self, locale: Locale) -> String {
+        match locale {
+            Locale::English => self.english(),
+            Locale::German => self.german(),
+        }
+    }
+
+    #[allow(clippy::too_many_lines)] // one arm per text
+    fn english(self) -> String {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+
+        match self {
+            Self::New => "New".to_owned(),
+            Self::Import => "Import...".to_owned(),
+            Self::Merge => "Merge...".to_owned(),
+            Self::ImportPasswords => "Import Passwords...".to_owned(),
+            Self::DeckInfo => "Deck Info...".to_owned(),
+            Self::ExportFor(target) => format!("Export for {}...", target.name()),
+            Self::CopyLink => "Copy Link".to_owned(),
+            Self::ExportReport => "Export Report...".to_owned(),
+            Self::ExportImage => "Export Image...".to_owned(),
+            Self::ImageTitle => {
+                "Deck name for the image header, leave empty for no header".to_owned()
+            }
+            Self::EffectTag(tag) => match tag {
+                EffectTag::Destroy => "Destroys",
+                EffectTag::Negate => "Negates",
+                EffectTag::Draw => "Draws",
+                EffectTag::Search => "Searches",
+                EffectTag::SpecialSummonFromGraveyard => "Revives from GY",
+                EffectTag::Banish => "Banishes",
+                EffectTag::HandTrap => "Hand Trap",
+                EffectTag::BoardBreaker => "Board Breaker",
+                EffectTag::Extender => "Extender",
+            }
+            .to_owned(),
+            Self::TooManyCopies(count) => {
+                format!("Too many copies of {count} card{}", plural(count))
+            }
+            Self::MainOverrides(count) => format!(
+                "{count} Extra deck monster{} placed in the Main deck",
+                plural(count)
+            ),
+            Self::PartTooSmall(part, min) => format!("{part} deck contains less than {min} cards"),
+            Self::PartTooLarge(part, max) => format!("{part} deck contains more than {max} cards"),
+
+            Self::Language(locale) => locale.native_name().to_owned(),
+            Self::NormalLayout => "Normal Layout".to_owned(),
+            Self::CompactLayout => "Compact Layout".to_owned(),
+            Self::SystemTheme => "System Theme".to_owned(),
+            Self::LightTheme => "Light Theme".to_owned(),
+            Self::DarkTheme => "Dark Theme".to_owned(),
+            Self::RightClickDeletes => "Right-click deletes".to_owned(),
+            Self::RightClickOpensMenu => "Right-click opens menu".to_owned(),
+            Self::NoTooltipDelay => "No tooltip delay".to_owned(),
+            Self::ShortTooltipDelay => "Short tooltip delay".to_owned(),
+            Self::LongTooltipDelay => "Long tooltip delay".to_owned(),
+            Self::NoEngineMarkers => "No engine markers".to_owned(),
+            Self::MarkEngineCards => "Mark Tuners and Extenders".to_owned(),
+            Self::GroupByColor => "Group by color".to_owned(),
+            Self::GroupExtraDeck => "Group Extra deck monsters".to_owned(),
+            Self::GroupByAttribute => "Group by attribute".to_owned(),
+            Self::InfiniteScroll => "Infinite Scroll".to_owned(),
+            Self::Pages => "Pages".to_owned(),
+
+            Self::DownloadingCardData {
+                received,
+                total: Some(total),
+            } => format!("Downloading card data ({received} / {total} kB)..."),
+            Self::DownloadingCardData { received, .. } => {
+                format!("Downloading card data ({received} kB)...")
+            }
+            Self::DecompressingCardData { processed, total } => {
+                format!("Decompressing card data ({processed} / {total} kB)...")
+            }
+
+            Self::Staples => "Staples".to_owned(),
+            Self::NewCards => "New Cards".to_owned(),
+            Self::AllCards => "All Cards".to_owned(),
+            Self::BrowsingStaples => "Showing staple cards — type to search all".to_owned(),
+            Self::BrowsingNewCards => "Showing newest cards first — type to search all".to_owned(),
+            Self::BrowsingAllCards => "Showing all cards — type to search".to_owned(),
+            Self::NameFilter => "Name".to_owned(),
+            Self::DescriptionFilter => "Description".to_owned(),
+            Self::OwnedOnly => "Owned only".to_owned(),
+            Self::PartOnly(part) => format!("{part} deck only"),
+            Self::RemoveFilter => "Remove filter".to_owned(),
+            Self::ClearAll => "Clear All".to_owned(),
+
+            Self::Tributes => "Tributes".to_owned(),
+            Self::TributesHint => "Monsters to tribute for a Normal Summon".to_owned(),
+            Self::PendulumScale => "Pendulum Scale".to_owned(),
+            Self::Pendulum => "Pendulum".to_owned(),
+            Self::PendulumExtraDeck => "Built into the Extra deck".to_owned(),
+            Self::PendulumMainDeck => {
+                "Built into the Main deck, goes face-up to the Extra deck when it leaves the field"
+                    .to_owned()
+            }
+            Self::CardLimit(limit) => match limit {
+                CardLimit::Unlimited => "Unlimited",
+                CardLimit::SemiLimited => "Semi-Limited",
+                CardLimit::Limited => "Limited",
+                CardLimit::Forbidden => "Forbidden",
+            }
+            .to_owned(),
+            Self::CopyPassword => "Copy Password".to_owned(),
+            Self::Copied => "Copied".to_owned(),
+            Self::CardPassword => "Card password".to_owned(),
+            Self::SetLimit => "Set Limit".to_owned(),
+            Self::Limit(limit) => format!("Limit: {limit}"),
+            Self::LimitPrompt(name) => format!(
+                "Copies of \"{name}\" allowed in the deck, leave empty to follow the ban list"
+            ),
+            Self::Lock => "Lock".to_owned(),
+            Self::Unlock => "Unlock".to_owned(),
+            Self::Locked => "Locked".to_owned(),
+            Self::Tuner => "Tuner".to_owned(),
+            Self::AddedTo(part) => format!("Added to the {part} deck"),
+            Self::AtMostAllowed(allowed) => format!("At most {allowed} copies allowed"),
+            Self::OverLimit(count, allowed) => format!("{count} copies, at most {allowed} allowed"),
+            Self::NotPlayableFrom(part) => format!("Can not be played from the {part} deck"),
+            Self::NeedMore(missing) => format!("need {missing} more"),
+
+            Self::Tools => "Tools".to_owned(),
+            Self::MoveUp => "Move up".to_owned(),
+            Self::MoveDown => "Move down".to_owned(),
+            Self::Errors => "Errors".to_owned(),
+            Self::SynchroMaterials => "Synchro Materials".to_owned(),
+            Self::ExtraDeckMaterials => "Extra Deck Materials".to_owned(),
+            Self::CardTypes => "Card Types".to_owned(),
+            Self::ExtraDeckCardTypes => "Extra Deck Card Types".to_owned(),
+            Self::ExtraDeckRules => "Extra Deck Rules".to_owned(),
+            Self::MonsterLevels => "Monster Levels".to_owned(),
+            Self::MonsterAtk => "Monster ATK".to_owned(),
+            Self::EffectTags => "Effect Tags".to_owned(),
+            Self::MissingCards => "Missing Cards".to_owned(),
+            Self::StapleSuggestions => "Staple Suggestions".to_owned(),
+            Self::Snapshots => "Snapshots".to_owned(),
+            Self::CardData => "Card Data".to_owned(),
+        }
+    }
+
+    #[allow(clippy::too_many_lines)] // one arm per text
+    fn german(self) -> String {
+        match self {
+            Self::New => "Neu".to_owned(),
+            Self::Import => "Importieren...".to_owned(),
+            Self::Merge => "Zusammenführen...".to_owned(),
+            Self::ImportPasswords => "Passwörter importieren...".to_owned(),
+            Self::DeckInfo => "Deck-Infos...".to_owned(),
+            Self::ExportFor(target) => format!("Für {} exportieren...", target.name()),
+            Self::CopyLink => "Link kopieren".to_owned(),
+            Self::ExportReport => "Bericht exportieren...".to_owned(),
+            Self::ExportImage => "Bild exportieren...".to_owned(),
+            Self::ImageTitle => {
+                "Deckname für die Kopfzeile des Bildes, leer lassen für keine Kopfzeile".to_owned()
+            }
+            Self::EffectTag(tag) => match tag {
+                EffectTag::Destroy => "Zerstört",
+                EffectTag::Negate => "Annulliert",
+                EffectTag::Draw => "Zieht",
+                EffectTag::Search => "Sucht",
+                EffectTag::SpecialSummonFromGraveyard => "Belebt vom Friedhof",
+                EffectTag::Banish => "Verbannt",
+                EffectTag::HandTrap => "Hand-Trap",
+                EffectTag::BoardBreaker => "Board-Breaker",
+                EffectTag::Extender => "Extender",
+            }
+            .to_owned(),
+            Self::TooManyCopies(count) => format!(
+                "Zu viele Exemplare von {count} Karte{}",
+                if count == 1 { "" } else { "n" }
+            ),
+            Self::MainOverrides(count) => format!("{count} Extra-Deck-Monster im Main-Deck"),
+            Self::PartTooSmall(part, min) => {
+                format!("{part}-Deck enthält weniger als {min} Karten")
+            }
+            Self::PartTooLarge(part, max) => format!("{part}-Deck enthält mehr als {max} Karten"),
+
+            Self::Language(locale) => locale.native_name().to_owned(),
+            Self::NormalLayout => "Normales Layout".to_owned(),
+            Self::CompactLayout => "Kompaktes Layout".to_owned(),
+            Self::SystemTheme => "Design des Systems".to_owned(),
+            Self::LightTheme => "Helles Design".to_owned(),
+            Self::DarkTheme => "Dunkles Design".to_owned(),
+            Self::RightClickDeletes => "Rechtsklick entfernt".to_owned(),
+            Self::RightClickOpensMenu => "Rechtsklick öffnet das Menü".to_owned(),
+            Self::NoTooltipDelay => "Keine Verzögerung der Kartendetails".to_owned(),
+            Self::ShortTooltipDelay => "Kurze Verzögerung der Kartendetails".to_owned(),
+            Self::LongTooltipDelay => "Lange Verzögerung der Kartendetails".to_owned(),
+            Self::NoEngineMarkers => "Keine Engine-Markierungen".to_owned(),
+            Self::MarkEngineCards => "Empfänger und Extender markieren".to_owned(),
+            Self::GroupByColor => "Nach Farbe gruppieren".to_owned(),
+            Self::GroupExtraDeck => "Extra-Deck-Monster gruppieren".to_owned(),
+            Self::GroupByAttribute => "Nach Eigenschaft gruppieren".to_owned(),
+            Self::InfiniteScroll => "Endloses Scrollen".to_owned(),
+            Self::Pages => "Seiten".to_owned(),
+
+            Self::DownloadingCardData {
+                received,
+                total: Some(total),
+            } => format!("Kartendaten werden heruntergeladen ({received} / {total} kB)..."),
+            Self::DownloadingCardData { received, .. } => {
+                format!("Kartendaten werden heruntergeladen ({received} kB)...")
+            }
+            Self::DecompressingCardData { processed, total } => {
+                format!("Kartendaten werden entpackt ({processed} / {total} kB)...")
+            }
+
+            Self::Staples => "Staples".to_owned(),
+            Self::NewCards => "Neue Karten".to_owned(),
+            Self::AllCards => "Alle Karten".to_owned(),
+            Self::BrowsingStaples => {
+                "Staple-Karten werden angezeigt — tippen, um alle zu durchsuchen".to_owned()
+            }
+            Self::BrowsingNewCards => {
+                "Neueste Karten zuerst — tippen, um alle zu durchsuchen".to_owned()
+            }
+            Self::BrowsingAllCards => "Alle Karten werden angezeigt — tippen zum Suchen".to_owned(),
+            Self::NameFilter => "Name".to_owned(),
+            Self::DescriptionFilter => "Beschreibung".to_owned(),
+            Self::OwnedOnly => "Nur vorhandene".to_owned(),
+            Self::PartOnly(part) => format!("Nur {part}-Deck"),
+            Self::RemoveFilter => "Filter entfernen".to_owned(),
+            Self::ClearAll => "Alle entfernen".to_owned(),
+
+            Self::Tributes => "Tribute".to_owned(),
+            Self::TributesHint => {
+                "Als Tribut für eine Normalbeschwörung benötigte Monster".to_owned()
+            }
+            Self::PendulumScale => "Pendelbereich".to_owned(),
+            Self::Pendulum => "Pendel".to_owned(),
+            Self::PendulumExtraDeck => "Gehört ins Extra-Deck".to_owned(),
+            Self::PendulumMainDeck => {
+                "Gehört ins Main-Deck, kommt offen ins Extra-Deck, wenn es das Spielfeld verlässt"
+                    .to_owned()
+            }
+            Self::CardLimit(limit) => match limit {
+                CardLimit::Unlimited => "Unbegrenzt",
+                CardLimit::SemiLimited => "Semi-Limitiert",
+                CardLimit::Limited => "Limitiert",
+                CardLimit::Forbidden => "Verboten",
+            }
+            .to_owned(),
+            Self::CopyPassword => "Passwort kopieren".to_owned(),
+            Self::Copied => "Kopiert".to_owned(),
+            Self::CardPassword => "Kartenpasswort".to_owned(),
+            Self::SetLimit => "Limit festlegen".to_owned(),
+            Self::Limit(limit) => format!("Limit: {limit}"),
+            Self::LimitPrompt(name) => {
+                format!("Erlaubte Exemplare von „{name}“ im Deck, leer lassen für die Banliste")
+            }
+            Self::Lock => "Sperren".to_owned(),
+            Self::Unlock => "Entsperren".to_owned(),
+            Self::Locked => "Gesperrt".to_owned(),
+            Self::Tuner => "Empfänger".to_owned(),
+            Self::AddedTo(part) => format!("Wird zum {part}-Deck hinzugefügt"),
+            Self::AtMostAllowed(allowed) => format!("Höchstens {allowed} Exemplare erlaubt"),
+            Self::OverLimit(count, allowed) => {
+                format!("{count} Exemplare, höchstens {allowed} erlaubt")
+            }
+            Self::NotPlayableFrom(part) => {
+                format!("Kann nicht aus dem {part}-Deck gespielt werden")
+            }
+            Self::NeedMore(missing) => format!("{missing} fehlen"),
+
+            Self::Tools => "Werkzeuge".to_owned(),
+            Self::MoveUp => "Nach oben".to_owned(),
+            Self::MoveDown => "Nach unten".to_owned(),
+            Self::Errors => "Fehler".to_owned(),
+            Self::SynchroMaterials => "Synchromaterial".to_owned(),
+            Self::ExtraDeckMaterials => "Extra-Deck-Material".to_owned(),
+            Self::CardTypes => "Kartentypen".to_owned(),
+            Self::ExtraDeckCardTypes => "Kartentypen im Extra-Deck".to_owned(),
+            Self::ExtraDeckRules => "Regeln für das Extra-Deck".to_owned(),
+            Self::MonsterLevels => "Monsterstufen".to_owned(),
+            Self::MonsterAtk => "Monster-ATK".to_owned(),
+            Self::EffectTags => "Effekt-Tags".to_owned(),
+            Self::MissingCards => "Fehlende Karten".to_owned(),
+            Self::StapleSuggestions => "Staple-Vorschläge".to_owned(),
+            Self::Snapshots => "Schnappschüsse".to_owned(),
+            Self::CardData => "Kartendaten".to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plurals() {
+        assert_eq!(
+            Text::TooManyCopies(1).localize(Locale::English),
+            "Too many copies of 1 card"
+        );
+        assert_eq!(
+            Text::TooManyCopies(2).localize(Locale::German),
+            "Zu viele Exemplare von 2 Karten"
+        );
+        assert_eq!(
+            Text::MainOverrides(2).localize(Locale::English),
+            "2 Extra deck monsters placed in the Main deck"
+        );
+    }
+
+    #[test]
+    fn part_sizes() {
+        assert_eq!(
+            Text::PartTooSmall(DeckPart::Main, 40).localize(Locale::English),
+            "Main deck contains less than 40 cards"
+        );
+        assert_eq!(
+            Text::PartTooLarge(DeckPart::Side, 15).localize(Locale::German),
+            "Side-Deck enthält mehr als 15 Karten"
+        );
+    }
+
+    #[test]
+    fn loading_steps() {
+        assert_eq!(
+            Text::DownloadingCardData {
+                received: 512,
+                total: None
+            }
+            .localize(Locale::English),
+            "Downloading card data (512 kB)..."
+        );
+        assert_eq!(
+            Text::DecompressingCardData {
+                processed: 512,
+                total: 2048
+            }
+            .localize(Locale::German),
+            "Kartendaten werden entpackt (512 / 2048 kB)..."
+        );
+    }
+}
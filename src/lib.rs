@@ -1,6 +1,13 @@
+pub mod app_state;
+pub mod collection;
+pub mod copy_limits;
 pub mod deck;
+pub mod deck_meta;
 pub mod deck_order;
 pub mod error_handling;
+pub mod i18n;
+pub mod links;
+pub mod snapshots;
 pub mod text_encoding;
 pub mod ui;
 pub mod undo_redo;
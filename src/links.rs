@@ -0,0 +1,66 @@
+//! Links to external card databases.
+
+use std::fmt::Write;
+
+use common::card::Card;
+
+/// Punctuation which is kept as is in page titles, the unreserved characters and the
+/// sub-delimiters which the wiki does not encode either.
+const UNENCODED: &[u8] = b"-_.~!$'()*,:;@";
+
+/// Encode a card name for use as a wiki page title in an URL.
+fn encode_title(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+
+    for byte in name.bytes() {
+        match byte {
+            b' ' => result.push('_'),
+            byte if byte.is_ascii_alphanumeric() || UNENCODED.contains(&byte) => {
+                result.push(char::from(byte));
+            }
+            _ => write!(result, "%{byte:02X}").unwrap(/* Write for String should never fail */),
+        }
+    }
+
+    result
+}
+
+/// Link to the card page on [Yugipedia](https://yugipedia.com/).
+#[must_use]
+pub fn yugipedia(card: &Card) -> String {
+    format!("https://yugipedia.com/wiki/{}", encode_title(card.name))
+}
+
+/// Link to the rulings page for a card on [Yugipedia](https://yugipedia.com/).
+///
+/// Not every card has a rulings page.
+#[must_use]
+pub fn yugipedia_rulings(card: &Card) -> String {
+    format!(
+        "https://yugipedia.com/wiki/Card_Rulings:{}",
+        encode_title(card.name)
+    )
+}
+
+/// Link to the card page on [YGOPRODeck](https://ygoprodeck.com/).
+#[must_use]
+pub fn ygoprodeck(card: &Card) -> String {
+    format!("https://ygoprodeck.com/card/?search={}", card.password)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn title_encoding() {
+        assert_eq!(encode_title("Dark Magician"), "Dark_Magician");
+        assert_eq!(encode_title("Number 39: Utopia"), "Number_39:_Utopia");
+        assert_eq!(
+            encode_title("Ash Blossom & Joyous Spring"),
+            "Ash_Blossom_%26_Joyous_Spring"
+        );
+        assert_eq!(encode_title("#1/?"), "%231%2F%3F");
+        assert_eq!(encode_title("Ä"), "%C3%84");
+    }
+}
@@ -0,0 +1,168 @@
+//! Named copies of the deck, to try out changes and return to a previous state
+
+use std::fmt;
+
+use common::card_data::{CardData, Id};
+use leptos::logging;
+
+use crate::{deck::Deck, deck_order::deck_order, text_encoding::TextEncoding};
+
+/// Most cards shown in the thumbnail of a snapshot, filling a 3x3 grid.
+pub const THUMBNAIL_CARDS: usize = 9;
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub name: String,
+    pub deck: Deck,
+}
+
+impl Snapshot {
+    /// Cards shown in the thumbnail: the cover card of the deck if it has one, otherwise up to
+    /// [`THUMBNAIL_CARDS`] cards with the most copies.
+    #[must_use]
+    pub fn thumbnail(&self, cards: &CardData) -> Vec<Id> {
+        if let Some(cover) = self.deck.meta().cover {
+            return vec![cover];
+        }
+
+        let mut ids = self
+            .deck
+            .entries()
+            .map(|entry| entry.id())
+            .collect::<Vec<_>>();
+        ids.sort_unstable_by(|lhs, rhs| {
+            self.deck
+                .total_count(*rhs)
+                .cmp(&self.deck.total_count(*lhs))
+                .then_with(|| deck_order(&cards[*lhs], &cards[*rhs]))
+        });
+        ids.truncate(THUMBNAIL_CARDS);
+        ids
+    }
+}
+
+/// Saved snapshots, oldest first.
+#[derive(Debug, Default, Clone)]
+pub struct Snapshots(pub Vec<Snapshot>);
+
+impl Snapshots {
+    /// Save a copy of the deck without its undo history.
+    ///
+    /// Line breaks and tabs are removed from the name, as they are used by the text encoding.
+    pub fn add(&mut self, name: &str, deck: &Deck) {
+        let name = name.replace(['\n', '\r', '\t'], " ").trim().to_owned();
+        let mut deck = deck.clone();
+        deck.reset_history();
+        self.0.push(Snapshot { name, deck });
+    }
+}
+
+impl TextEncoding for Snapshots {
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        for (idx, snapshot) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writer.write_char('\n')?;
+            }
+            write!(writer, "{}\t", snapshot.name)?;
+            snapshot.deck.encode(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Snapshots which can not be decoded are skipped, so they do not take the others with them.
+    fn decode(text: &str) -> Option<Self> {
+        let snapshots = text
+            .lines()
+            .filter_map(|line| {
+                let snapshot = line.split_once('\t').and_then(|(name, deck)| {
+                    Some(Snapshot {
+                        name: name.to_owned(),
+                        deck: Deck::decode(deck)?,
+                    })
+                });
+                if snapshot.is_none() {
+                    logging::warn!("Skipping snapshot which could not be decoded: {line}");
+                }
+                snapshot
+            })
+            .collect();
+        Some(Self(snapshots))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        assert_part_eq, card::test_util::make_card, card_data::CardDataStorage, deck::PartType,
+    };
+    use leptos::provide_context;
+
+    use crate::deck_meta::DeckMeta;
+
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        const ID: Id = Id::new(0);
+
+        let cards = vec![make_card(1234)];
+        let card_data = CardData::from(CardDataStorage::new(cards, vec![]));
+        provide_context(card_data);
+
+        let mut deck = Deck::default();
+        deck.increment(ID, PartType::Playing, 2).unwrap();
+
+        let mut snapshots = Snapshots::default();
+        snapshots.add("Aggressive\tbuild", &deck);
+        snapshots.add("Empty", &Deck::default());
+
+        let mut decoded = Snapshots::decode(&snapshots.encode_string()).unwrap();
+        assert_eq!(decoded.0.len(), 2);
+        assert_eq!(decoded.0[0].name, "Aggressive build");
+        assert_part_eq!(&decoded.0[0].deck, PartType::Playing, &[(ID, 2)]);
+        assert_eq!(decoded.0[1].deck.entries().count(), 0);
+
+        // The undo history is not part of the snapshot
+        decoded.0[0].deck.undo();
+        assert_part_eq!(&decoded.0[0].deck, PartType::Playing, &[(ID, 2)]);
+
+        assert!(Snapshots::decode("").unwrap().0.is_empty());
+
+        // Malformed snapshots are skipped
+        let text = format!("no tab\n{}\nBroken\tv999", snapshots.encode_string());
+        let decoded = Snapshots::decode(&text).unwrap();
+        assert_eq!(decoded.0.len(), 2);
+        assert_eq!(decoded.0[0].name, "Aggressive build");
+    }
+
+    #[test]
+    fn thumbnail() {
+        let cards = (0..12).map(|password| {
+            let mut card = make_card(password);
+            card.name = format!("Card {password:02}");
+            card
+        });
+        let cards = CardData::from(CardDataStorage::new(cards.collect(), vec![]));
+
+        let mut deck = Deck::default();
+        for id in 0..12 {
+            let count = if id % 4 == 0 { 3 } else { 1 };
+            deck.increment(Id::new(id), PartType::Playing, count)
+                .unwrap();
+        }
+        let mut snapshot = Snapshot {
+            name: String::new(),
+            deck,
+        };
+        assert_eq!(
+            snapshot.thumbnail(&cards),
+            [0, 4, 8, 1, 2, 3, 5, 6, 7].map(Id::new)
+        );
+
+        snapshot.deck.set_meta(DeckMeta {
+            cover: Some(Id::new(11)),
+            ..DeckMeta::default()
+        });
+        assert_eq!(snapshot.thumbnail(&cards), [Id::new(11)]);
+    }
+}
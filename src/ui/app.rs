@@ -1,24 +1,307 @@
+use std::io::Write;
+
 use bincode::Options;
 use common::{
     card_data::{CardData, CardDataStorage},
     transfer,
 };
-use gloo_net::http::Request;
-use leptos::{component, create_local_resource, provide_context, view, IntoView, Suspense};
-use lzma_rs::xz_decompress;
+use gloo_net::http::{Request, Response};
+use leptos::{
+    component, create_effect, create_local_resource, create_rw_signal, ev, provide_context, view,
+    window_event_listener, IntoView, RwSignal, Show, SignalGet, SignalSet, Suspense,
+};
+use lzma_rs::decompress::Stream;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{js_sys, ReadableStreamDefaultReader};
 
-use crate::ui::{
-    card_search::CardSearch, card_view::CardTooltip, deck::Menu, deck_view::DeckView,
-    drawers::Drawers, tools::Tools,
+use crate::{
+    deck_order::Grouping,
+    i18n::{Locale, Text},
+    ui::{
+        card_search::CardSearch,
+        card_view::{CardTooltip, EngineMarkers, RightClick, SelectionActions, TooltipDelay},
+        deck::Menu,
+        deck_view::{ActivePart, DeckView},
+        drawers::Drawers,
+        settings::Setting,
+        tools::Tools,
+    },
 };
 
-async fn load_cards() -> CardData {
+/// Size of the cards and spacing of the layout.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Density {
+    #[default]
+    Normal,
+    /// Smaller cards, to fit more of them on large screens
+    Compact,
+}
+
+impl Setting for Density {
+    const STORAGE_KEY: &'static str = "density";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Normal, Self::Compact].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Compact => "compact",
+        }
+    }
+
+    fn name(self) -> Text {
+        match self {
+            Self::Normal => Text::NormalLayout,
+            Self::Compact => Text::CompactLayout,
+        }
+    }
+}
+
+/// Color scheme of the user interface, applied as `data-theme` on the root element.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// Follow `prefers-color-scheme`
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    fn apply(self) {
+        let Some(root) = leptos::document().document_element() else {
+            return;
+        };
+        let _ = match self {
+            Self::System => root.remove_attribute("data-theme"),
+            Self::Light => root.set_attribute("data-theme", "light"),
+            Self::Dark => root.set_attribute("data-theme", "dark"),
+        };
+    }
+}
+
+impl Setting for Theme {
+    const STORAGE_KEY: &'static str = "theme";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::System, Self::Light, Self::Dark].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+
+    fn name(self) -> Text {
+        match self {
+            Self::System => Text::SystemTheme,
+            Self::Light => Text::LightTheme,
+            Self::Dark => Text::DarkTheme,
+        }
+    }
+}
+
+/// Panel shown in the single column layout on narrow screens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Search,
+    Drawers,
+    Deck,
+    Extras,
+}
+
+impl Tab {
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Search, Self::Drawers, Self::Deck, Self::Extras].into_iter()
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Search => "Search",
+            Self::Drawers => "Drawers",
+            Self::Deck => "Deck",
+            Self::Extras => "Tools",
+        }
+    }
+
+    /// Value of the `data-tab` attribute, used by the stylesheet to show the panel
+    fn key(self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Drawers => "drawers",
+            Self::Deck => "deck",
+            Self::Extras => "extras",
+        }
+    }
+}
+
+/// Whether the viewport is too narrow for the multi column layout.
+fn is_narrow() -> bool {
+    // Keep in sync with the single column layout in `main.css`
+    const MAX_NARROW_WIDTH: f64 = 800.0;
+
+    leptos::window()
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .is_some_and(|width| width <= MAX_NARROW_WIDTH)
+}
+
+/// Tab bar and actions for the selected card, shown at the bottom on narrow screens.
+#[component]
+#[must_use]
+fn ActionBar(tab: RwSignal<Tab>) -> impl IntoView {
+    let tabs = Tab::iter()
+        .map(|value| {
+            view! {
+                <button class:active=move || tab.get() == value on:click=move |_| tab.set(value)>
+                    {value.name()}
+                </button>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <div class="action-bar">
+            <SelectionActions />
+            <div class="tabs">{tabs}</div>
+        </div>
+    }
+}
+
+/// Step of loading the card data, shown while the app is loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoadingStep {
+    Download {
+        received: usize,
+        /// Size of the card data, if the server sent it
+        total: Option<usize>,
+    },
+    /// Progress in compressed bytes
+    Decompress { processed: usize, total: usize },
+}
+
+impl LoadingStep {
+    fn text(self) -> Text {
+        match self {
+            Self::Download { received, total } => Text::DownloadingCardData {
+                received: received / 1024,
+                total: total.map(|total| total / 1024),
+            },
+            Self::Decompress { processed, total } => Text::DecompressingCardData {
+                processed: processed / 1024,
+                total: total / 1024,
+            },
+        }
+    }
+
+    /// Value for a `<progress>` element, `None` if the progress is unknown.
+    #[allow(clippy::cast_precision_loss)] // the card data is far below 2^52 bytes
+    fn fraction(self) -> Option<f64> {
+        match self {
+            Self::Download {
+                received,
+                total: Some(total),
+            } if total > 0 => Some((received as f64 / total as f64).min(1.0)),
+            Self::Decompress { processed, total } if total > 0 => {
+                Some(processed as f64 / total as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Read the response body in chunks, reporting the number of received bytes after each one.
+///
+/// The buffer is reserved for the expected `size`, so it is not reallocated, which would hold the
+/// data twice, while the chunks are appended.
+async fn read_body(
+    response: &Response,
+    size: Option<usize>,
+    mut on_progress: impl FnMut(usize),
+) -> Vec<u8> {
+    let Some(body) = response.body() else {
+        return response.binary().await.unwrap();
+    };
+    let reader = body
+        .get_reader()
+        .unchecked_into::<ReadableStreamDefaultReader>();
+
+    let mut bytes = Vec::with_capacity(size.unwrap_or_default());
+    loop {
+        let chunk = JsFuture::from(reader.read()).await.unwrap();
+        if js_sys::Reflect::get(&chunk, &"done".into())
+            .unwrap()
+            .is_truthy()
+        {
+            break;
+        }
+        let value = js_sys::Reflect::get(&chunk, &"value".into()).unwrap();
+        let value = js_sys::Uint8Array::new(&value);
+        let start = bytes.len();
+        bytes.resize(start + value.length() as usize, 0);
+        value.copy_to(&mut bytes[start..]);
+        on_progress(bytes.len());
+    }
+    bytes
+}
+
+/// Wait until the browser has rendered the current state of the page.
+async fn next_frame() {
+    // The first callback runs before the next render, the second one after it
+    for _ in 0..2 {
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            let _ = leptos::window().request_animation_frame(&resolve);
+        });
+        let _ = JsFuture::from(promise).await;
+    }
+}
+
+/// Compressed bytes to decompress between two progress updates
+const DECOMPRESS_SLICE: usize = 512 * 1024;
+
+/// Decompress the card data in slices, reporting the number of processed bytes before each one.
+///
+/// Decompressing blocks the page, so the browser is given the chance to render the progress
+/// between the slices.
+async fn decompress(bytes: &[u8], mut on_progress: impl FnMut(usize)) -> Vec<u8> {
+    let mut stream = Stream::new(Vec::new());
+    for (idx, slice) in bytes.chunks(DECOMPRESS_SLICE).enumerate() {
+        on_progress(idx * DECOMPRESS_SLICE);
+        next_frame().await;
+        stream.write_all(slice).unwrap();
+    }
+    stream.finish().unwrap()
+}
+
+async fn load_cards(step: RwSignal<LoadingStep>) -> CardData {
     let request = Request::get(transfer::DATA_FILENAME);
     let response = request.send().await.unwrap();
-    let bytes = response.binary().await.unwrap();
+    let total = response
+        .headers()
+        .get("Content-Length")
+        .and_then(|length| length.parse().ok());
+    let bytes = read_body(&response, total, |received| {
+        step.set(LoadingStep::Download { received, total });
+    })
+    .await;
 
-    let mut decompressed = Vec::new();
-    xz_decompress(&mut bytes.as_slice(), &mut decompressed).unwrap();
+    let decompressed = decompress(&bytes, |processed| {
+        step.set(LoadingStep::Decompress {
+            processed,
+            total: bytes.len(),
+        });
+    })
+    .await;
+    // lzma-rs can only decompress into a writer, while bincode reads from a reader, so the
+    // decompressed data is needed as a whole. Free the compressed data before deserializing.
+    drop(bytes);
     let cards: CardDataStorage = transfer::bincode_options()
         .deserialize(&decompressed)
         .unwrap();
@@ -28,17 +311,51 @@ async fn load_cards() -> CardData {
 #[component]
 #[must_use]
 pub fn App() -> impl IntoView {
-    let cards = create_local_resource(|| (), |()| load_cards());
+    let step = create_rw_signal(LoadingStep::Download {
+        received: 0,
+        total: None,
+    });
+    let cards = create_local_resource(|| (), move |()| load_cards(step));
 
-    let fallback = move || "Loading...";
+    // The locale is only installed as context once the card data is loaded
+    let locale = Locale::load();
+    let fallback = move || {
+        view! {
+            <div class="loading">
+                <p>{move || step.get().text().localize(locale)}</p>
+                <progress value=move || step.get().fraction()></progress>
+            </div>
+        }
+    };
     let app = move || {
         cards.map(|cards| {
             provide_context::<CardData>(*cards);
+            Locale::install_as_context();
+            Grouping::install_as_context();
             crate::ui::deck::install_as_context();
+            crate::ui::collection::install_as_context();
+            RightClick::install_as_context();
+            TooltipDelay::install_as_context();
+            EngineMarkers::install_as_context();
+            ActivePart::install_as_context();
+            let density = Density::stored();
+            provide_context(density);
+            let theme = Theme::stored();
+            create_effect(move |_| theme.get().apply());
+            provide_context(theme);
+
+            let narrow = create_rw_signal(is_narrow());
+            window_event_listener(ev::resize, move |_| narrow.set(is_narrow()));
+            let tab = create_rw_signal(Tab::Search);
 
             view! {
                 <CardTooltip />
-                <div class="deck-builder">
+                <div
+                    class="deck-builder"
+                    class:compact=move || density.get() == Density::Compact
+                    class:narrow=move || narrow.get()
+                    data-tab=move || tab.get().key()
+                >
                     <CardSearch />
                     <Drawers />
                     <DeckView />
@@ -46,6 +363,9 @@ pub fn App() -> impl IntoView {
                         <Menu />
                         <Tools />
                     </div>
+                    <Show when=move || narrow.get()>
+                        <ActionBar tab=tab />
+                    </Show>
                 </div>
             }
         })
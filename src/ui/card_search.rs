@@ -1,41 +1,256 @@
-use common::{card::Card, card_data::CardData};
+use std::{cmp::Reverse, rc::Rc, time::Duration};
+
+use common::{
+    card::{Card, EffectTag, EffectTags},
+    card_data::{CardData, Id},
+    deck::PartType,
+    deck_part::DeckPart,
+    search,
+    validation::CopyLimits,
+};
 use leptos::{
-    component, create_memo, create_node_ref, create_signal, expect_context, html, provide_context,
-    view, Callable, Callback, For, IntoView, RwSignal, SignalGet, SignalGetUntracked, SignalSet,
-    SignalWith, SignalWithUntracked,
+    component, create_effect, create_memo, create_node_ref, create_rw_signal, create_signal,
+    event_target_value, expect_context, html, leptos_dom::helpers::TimeoutHandle, provide_context,
+    set_timeout_with_handle, store_value, view, Callable, Callback, For, IntoView, Memo, NodeRef,
+    RwSignal, Show, Signal, SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, SignalWith,
+    SignalWithUntracked,
 };
 use wasm_bindgen::{closure::Closure, JsCast};
-use web_sys::js_sys;
+use web_sys::{js_sys, UrlSearchParams};
 
-use crate::ui::card_view::CardView;
+use crate::{
+    collection::Collection,
+    deck::Deck,
+    i18n::{Locale, Text},
+    print_error,
+    ui::{
+        card_view::CardView,
+        settings::{Setting, SettingSelect},
+    },
+};
 
 #[derive(Debug, Default, Clone, Copy)]
 struct CardFilter {
+    /// Name as typed by the user, it is folded for the query
     name: RwSignal<String>,
+    /// Description as typed by the user, it is folded for the query
     text: RwSignal<String>,
+    tags: RwSignal<EffectTags>,
+    /// Only show cards with owned copies in the collection
+    owned_only: RwSignal<bool>,
+    /// Only show cards which can be placed in the Main or the Extra deck
+    part: RwSignal<Option<DeckPart>>,
 }
 
 impl CardFilter {
     fn is_empty(&self) -> bool {
-        self.name.with(String::is_empty) && self.text.with(String::is_empty)
+        self.name.with(String::is_empty)
+            && self.text.with(String::is_empty)
+            && self.tags.with(EffectTags::is_empty)
+            && !self.owned_only.get()
+            && self.part.with(Option::is_none)
     }
 
-    fn matches(&self, card: &Card) -> bool {
-        if self
-            .name
-            .with(|name| !name.is_empty() && !card.name.to_ascii_lowercase().contains(name))
-        {
-            return false;
+    fn clear(&self) {
+        self.name.set(String::new());
+        self.text.set(String::new());
+        self.tags.set(EffectTags::default());
+        self.owned_only.set(false);
+        self.part.set(None);
+    }
+
+    /// Query for the text and effect filters, the owned filter is applied separately.
+    fn query(&self) -> search::Query {
+        search::Query {
+            name: search::fold(&self.name.get()),
+            text: search::fold(&self.text.get()),
+            tags: self.tags.get(),
+            part: self.part.get(),
         }
+    }
 
-        if self
-            .text
-            .with(|text| !text.is_empty() && !card.search_text.contains(text))
-        {
-            return false;
+    fn matches(&self, query: &search::Query, card: &Card, collection: &Collection) -> bool {
+        query.matches(card) && (!self.owned_only.get() || collection.owned(card.password) > 0)
+    }
+
+    /// Link to the app with this filter, see [`CardFilter::load_from_url`].
+    fn to_url(self) -> String {
+        let params = UrlSearchParams::new().unwrap();
+        self.name.with(|name| {
+            if !name.is_empty() {
+                params.append("name", name);
+            }
+        });
+        self.text.with(|text| {
+            if !text.is_empty() {
+                params.append("text", text);
+            }
+        });
+        let tags = EffectTag::iter()
+            .filter(|tag| self.tags.with(|tags| tags.has(*tag)))
+            .map(tag_key)
+            .collect::<Vec<_>>();
+        if !tags.is_empty() {
+            params.append("tags", &tags.join(","));
+        }
+        if self.owned_only.get() {
+            params.append("owned", "1");
+        }
+        if let Some(part) = self.part.get() {
+            params.append("part", part_key(part));
+        }
+
+        let location = leptos::window().location();
+        format!(
+            "{}{}?{}",
+            location.origin().unwrap_or_default(),
+            location.pathname().unwrap_or_default(),
+            String::from(params.to_string())
+        )
+    }
+
+    /// Prefill the filter from the query of the current address, ignoring unknown values.
+    fn load_from_url(&self) {
+        let Some(params) = leptos::window()
+            .location()
+            .search()
+            .ok()
+            .and_then(|search| UrlSearchParams::new_with_str(&search).ok())
+        else {
+            return;
+        };
+
+        if let Some(name) = params.get("name") {
+            self.name.set(name);
+        }
+        if let Some(text) = params.get("text") {
+            self.text.set(text);
+        }
+        if let Some(keys) = params.get("tags") {
+            let mut tags = EffectTags::default();
+            for key in keys.split(',') {
+                if let Some(tag) = EffectTag::iter().find(|tag| tag_key(*tag) == key) {
+                    tags.add(tag);
+                }
+            }
+            self.tags.set(tags);
+        }
+        self.owned_only
+            .set(params.get("owned").as_deref() == Some("1"));
+        if let Some(key) = params.get("part") {
+            self.part
+                .set(PART_FILTERS.into_iter().find(|part| part_key(*part) == key));
+        }
+    }
+}
+
+/// Deck parts offered by the part filter, the Side deck can contain every card.
+const PART_FILTERS: [DeckPart; 2] = [DeckPart::Main, DeckPart::Extra];
+
+/// Stable name of a deck part in search links.
+fn part_key(part: DeckPart) -> &'static str {
+    match part {
+        DeckPart::Main => "main",
+        DeckPart::Extra => "extra",
+        DeckPart::Side => "side",
+    }
+}
+
+/// Stable name of an effect tag in search links.
+fn tag_key(tag: EffectTag) -> &'static str {
+    match tag {
+        EffectTag::Destroy => "destroy",
+        EffectTag::Negate => "negate",
+        EffectTag::Draw => "draw",
+        EffectTag::Search => "search",
+        EffectTag::SpecialSummonFromGraveyard => "revive",
+        EffectTag::Banish => "banish",
+        EffectTag::HandTrap => "hand-trap",
+        EffectTag::BoardBreaker => "board-breaker",
+        EffectTag::Extender => "extender",
+    }
+}
+
+/// Cards to show while no filter is active.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum BrowseMode {
+    #[default]
+    Staples,
+    New,
+    All,
+}
+
+impl BrowseMode {
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Staples, Self::New, Self::All].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Staples => "staples",
+            Self::New => "new",
+            Self::All => "all",
+        }
+    }
+
+    fn name(self) -> Text {
+        match self {
+            Self::Staples => Text::Staples,
+            Self::New => Text::NewCards,
+            Self::All => Text::AllCards,
+        }
+    }
+
+    fn description(self) -> Text {
+        match self {
+            Self::Staples => Text::BrowsingStaples,
+            Self::New => Text::BrowsingNewCards,
+            Self::All => Text::BrowsingAllCards,
+        }
+    }
+
+    fn cards(self, cards: &CardData) -> Vec<Id> {
+        match self {
+            Self::Staples => cards.staples().collect(),
+            Self::New => {
+                let mut result = cards.entries().collect::<Vec<_>>();
+                result.sort_by_key(|(_, card)| Reverse(card.release_date));
+                result.into_iter().map(|(id, _)| id).collect()
+            }
+            Self::All => cards.entries().map(|(id, _)| id).collect(),
+        }
+    }
+}
+
+/// How search results are split into pages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum PageMode {
+    /// Load more results when scrolling down
+    #[default]
+    Infinite,
+    /// Show one page at a time
+    Paged,
+}
+
+impl Setting for PageMode {
+    const STORAGE_KEY: &'static str = "search_page_mode";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Infinite, Self::Paged].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Infinite => "infinite",
+            Self::Paged => "paged",
         }
+    }
 
-        true
+    fn name(self) -> Text {
+        match self {
+            Self::Infinite => Text::InfiniteScroll,
+            Self::Paged => Text::Pages,
+        }
     }
 }
 
@@ -46,54 +261,423 @@ struct ScrollReset {
 
 #[component]
 #[must_use]
-pub fn FilterInput(
-    placeholder: &'static str,
-    map: fn(String) -> String,
-    filter: RwSignal<String>,
-) -> impl IntoView {
-    let node_ref = create_node_ref();
+pub fn FilterInput(placeholder: &'static str, filter: RwSignal<String>) -> impl IntoView {
+    // Delay before applying typed text, to avoid filtering on every keystroke
+    const DEBOUNCE: Duration = Duration::from_millis(150);
+
+    let node_ref = create_node_ref::<html::Input>();
     let reset = expect_context::<ScrollReset>();
+    let pending = store_value(None::<TimeoutHandle>);
+
+    // Clear the input when the filter is reset from elsewhere
+    create_effect(move |_| {
+        if filter.with(String::is_empty) {
+            if let Some(input) = node_ref.get_untracked() {
+                input.set_value("");
+            }
+        }
+    });
 
     view! {
         <input
             type="text"
             placeholder=placeholder
+            value=filter.get_untracked()
             ref=node_ref
             on:input=move |_| {
-                let input = node_ref.get().unwrap();
-                filter.set(map(input.value()));
-                reset.callback.call(());
+                if let Some(handle) = pending.get_value() {
+                    handle.clear();
+                }
+                let update = move || {
+                    pending.set_value(None);
+                    let input = node_ref.get_untracked().unwrap();
+                    filter.set(input.value());
+                    reset.callback.call(());
+                };
+                pending.set_value(set_timeout_with_handle(update, DEBOUNCE).ok());
             }
         />
     }
 }
 
+#[component]
+#[must_use]
+pub fn EffectTagFilter(tags: RwSignal<EffectTags>) -> impl IntoView {
+    let reset = expect_context::<ScrollReset>();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    let buttons = EffectTag::iter()
+        .map(|tag| {
+            let toggle = move |_| {
+                tags.update(|tags| {
+                    if tags.has(tag) {
+                        tags.remove(tag);
+                    } else {
+                        tags.add(tag);
+                    }
+                });
+                reset.callback.call(());
+            };
+
+            view! {
+                <button class:active=move || tags.with(|tags| tags.has(tag)) on:click=toggle>
+                    {move || Text::EffectTag(tag).localize(locale.get())}
+                </button>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! { <div class="effect-tags">{buttons}</div> }
+}
+
+/// Removable chips for the active facets of the filter.
+#[component]
+#[must_use]
+fn FilterChips(filter: CardFilter) -> impl IntoView {
+    let reset = expect_context::<ScrollReset>();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    let chip = move |label: String, clear: Box<dyn Fn()>| {
+        view! {
+            <button
+                class="chip"
+                title=move || Text::RemoveFilter.localize(locale.get())
+                on:click=move |_| {
+                    clear();
+                    reset.callback.call(());
+                }
+            >
+                {label}
+                " ✕"
+            </button>
+        }
+    };
+
+    move || {
+        let locale = locale.get();
+        let mut chips = Vec::new();
+
+        let name = filter.name.get();
+        if !name.is_empty() {
+            chips.push(chip(
+                format!("{}: {name}", Text::NameFilter.localize(locale)),
+                Box::new(move || filter.name.set(String::new())),
+            ));
+        }
+
+        let text = filter.text.get();
+        if !text.is_empty() {
+            chips.push(chip(
+                format!("{}: {text}", Text::DescriptionFilter.localize(locale)),
+                Box::new(move || filter.text.set(String::new())),
+            ));
+        }
+
+        for tag in EffectTag::iter().filter(|tag| filter.tags.with(|tags| tags.has(*tag))) {
+            chips.push(chip(
+                Text::EffectTag(tag).localize(locale),
+                Box::new(move || filter.tags.update(|tags| tags.remove(tag))),
+            ));
+        }
+
+        if filter.owned_only.get() {
+            chips.push(chip(
+                Text::OwnedOnly.localize(locale),
+                Box::new(move || filter.owned_only.set(false)),
+            ));
+        }
+
+        if let Some(part) = filter.part.get() {
+            chips.push(chip(
+                Text::PartOnly(part).localize(locale),
+                Box::new(move || filter.part.set(None)),
+            ));
+        }
+
+        chips
+    }
+}
+
+#[component]
+#[must_use]
+pub fn PartFilter(part: RwSignal<Option<DeckPart>>) -> impl IntoView {
+    let reset = expect_context::<ScrollReset>();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    let select = move |ev: web_sys::Event| {
+        let value = event_target_value(&ev);
+        part.set(
+            PART_FILTERS
+                .into_iter()
+                .find(|part| part_key(*part) == value),
+        );
+        reset.callback.call(());
+    };
+    let options = PART_FILTERS
+        .into_iter()
+        .map(|option| {
+            view! {
+                <option value=part_key(option) selected=move || part.get() == Some(option)>
+                    {move || Text::PartOnly(option).localize(locale.get())}
+                </option>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <select class="part-filter" on:change=select>
+            <option value="" selected=move || part.get().is_none()>
+                "Any deck part"
+            </option>
+            {options}
+        </select>
+    }
+}
+
+#[component]
+#[must_use]
+pub fn OwnedFilter(owned_only: RwSignal<bool>) -> impl IntoView {
+    let reset = expect_context::<ScrollReset>();
+    let collection = expect_context::<RwSignal<Collection>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+    // Stay enabled while checked, so the filter can be turned off again
+    let disabled = move || !owned_only.get() && collection.with(Collection::is_empty);
+
+    view! {
+        <label
+            class="owned-filter"
+            title=move || disabled().then_some("Add owned copies in the card tooltip first")
+        >
+            <input
+                type="checkbox"
+                prop:checked=move || owned_only.get()
+                disabled=disabled
+                on:change=move |_| {
+                    owned_only.update(|owned_only| *owned_only = !*owned_only);
+                    reset.callback.call(());
+                }
+            />
+            {move || Text::OwnedOnly.localize(locale.get())}
+        </label>
+    }
+}
+
+/// Inputs for the search filters, with the active filters or the browse mode below.
+#[component]
+#[must_use]
+fn FilterPanel(
+    filter: CardFilter,
+    browse_mode: RwSignal<BrowseMode>,
+    filtered_cards: Memo<Vec<Id>>,
+    clear_filters: Callback<()>,
+) -> impl IntoView {
+    let reset = expect_context::<ScrollReset>();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    let select_browse_mode = move |ev: web_sys::Event| {
+        let value = event_target_value(&ev);
+        if let Some(mode) = BrowseMode::iter().find(|mode| mode.key() == value) {
+            browse_mode.set(mode);
+            reset.callback.call(());
+        }
+    };
+    let browse_mode_options = move || {
+        BrowseMode::iter()
+            .map(|mode| {
+                view! {
+                    <option value=mode.key() selected=move || browse_mode.get() == mode>
+                        {move || mode.name().localize(locale.get())}
+                    </option>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The dialog shows the link selected, ready to be copied
+    let share = move |_| {
+        let _ = gloo_dialogs::prompt("Link to this search", Some(&filter.to_url()));
+    };
+
+    view! {
+        <div class="card-search-params">
+            <FilterInput placeholder="Name" filter=filter.name />
+            <FilterInput placeholder="Description" filter=filter.text />
+            <EffectTagFilter tags=filter.tags />
+            <PartFilter part=filter.part />
+            <OwnedFilter owned_only=filter.owned_only />
+            <Show
+                when=move || filter.is_empty()
+                fallback=move || {
+                    view! {
+                        <div class="filter-state">
+                            <span class="result-count">
+                                {move || {
+                                    let count = filtered_cards.with(Vec::len);
+                                    format!("{count} card{}", if count == 1 { "" } else { "s" })
+                                }}
+                            </span>
+                            <FilterChips filter=filter />
+                            <button class="chip" on:click=move |_| clear_filters.call(())>
+                                {move || Text::ClearAll.localize(locale.get())}
+                            </button>
+                            <button class="share" on:click=share>
+                                "Share..."
+                            </button>
+                        </div>
+                    }
+                }
+            >
+                <div class="browse-mode">
+                    <span>{move || browse_mode.get().description().localize(locale.get())}</span>
+                    <select on:change=select_browse_mode>{browse_mode_options}</select>
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+/// The scrollable list of search results.
+#[component]
+#[must_use]
+fn ResultList(
+    ids: Signal<Vec<Id>>,
+    no_matches: Signal<bool>,
+    node_ref: NodeRef<html::Div>,
+    /// Called when the list is scrolled or resized
+    adjust_pages: Callback<()>,
+    clear_filters: Callback<()>,
+) -> impl IntoView {
+    let cards = expect_context::<CardData>();
+
+    node_ref.on_load(move |scroll_area| {
+        let callback = move |_, _| adjust_pages.call(());
+        let callback = Closure::<dyn Fn(js_sys::Array, web_sys::ResizeObserver)>::new(callback)
+            .into_js_value();
+        let observer = web_sys::ResizeObserver::new(callback.as_ref().unchecked_ref()).unwrap();
+        observer.observe(&scroll_area);
+    });
+
+    // Middle-clicking a result adds it to the Side deck
+    let deck = expect_context::<RwSignal<Deck>>();
+    let limits = expect_context::<RwSignal<CopyLimits>>();
+    let add_to_side = Rc::new(move |id: Id| {
+        let mut result = Ok(());
+        let cap = limits.with_untracked(|limits| limits.cap(id, &cards));
+        deck.update(|deck| result = deck.increment_up_to(id, PartType::Side, 1, cap));
+        if let Err(err) = result {
+            print_error!("Could not add \"{}\": {err}", cards[id].name);
+        }
+    });
+
+    view! {
+        <div
+            class="card-list"
+            role="group"
+            aria-label="Search results"
+            ref=node_ref
+            on:scroll=move |_| adjust_pages.call(())
+        >
+            <For
+                each=move || ids.get()
+                key=|id| *id
+                children=move |id| {
+                    let add_to_side = add_to_side.clone();
+                    view! { <CardView id=id on_middle_click=add_to_side show_target=true /> }
+                }
+            />
+            <Show when=move || no_matches.get()>
+                <div class="empty">
+                    <p>"No cards match your filters"</p>
+                    <button on:click=move |_| clear_filters.call(())>"Clear Filters"</button>
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+/// Controls for switching pages and the page mode.
+#[component]
+#[must_use]
+fn Pagination(
+    page_mode: RwSignal<PageMode>,
+    page: RwSignal<usize>,
+    page_count: Signal<usize>,
+    scroll_area_ref: NodeRef<html::Div>,
+) -> impl IntoView {
+    let reset = expect_context::<ScrollReset>();
+
+    let change_page = move |delta: isize| {
+        let new_page = page.get_untracked().saturating_add_signed(delta);
+        page.set(new_page.min(page_count.get_untracked() - 1));
+        scroll_area_ref.get_untracked().unwrap().set_scroll_top(0);
+    };
+
+    view! {
+        <div class="pagination">
+            <Show when=move || page_mode.get() == PageMode::Paged>
+                <button on:click=move |_| change_page(-1) disabled=move || page.get() == 0>
+                    "<"
+                </button>
+                <span>{move || format!("Page {} of {}", page.get() + 1, page_count.get())}</span>
+                <button
+                    on:click=move |_| change_page(1)
+                    disabled=move || page.get() + 1 >= page_count.get()
+                >
+                    ">"
+                </button>
+            </Show>
+            <SettingSelect
+                setting=page_mode
+                on_change=Callback::new(move |_| reset.callback.call(()))
+            />
+        </div>
+    }
+}
+
 #[component]
 #[must_use]
 pub fn CardSearch() -> impl IntoView {
     const PAGE_SIZE: usize = 50;
 
     let cards = expect_context::<CardData>();
+    let collection = expect_context::<RwSignal<Collection>>();
     let filter = CardFilter::default();
+    filter.load_from_url();
+    let browse_mode = create_rw_signal(BrowseMode::default());
     let filtered_cards = create_memo(move |_| {
         if filter.is_empty() {
-            cards.staples().collect::<Vec<_>>()
+            browse_mode.get().cards(&cards)
         } else {
-            cards
-                .entries()
-                .filter(move |(_, card)| filter.matches(card))
-                .map(|(id, _)| id)
-                .collect::<Vec<_>>()
+            let query = filter.query();
+            collection.with(|collection| {
+                cards
+                    .entries()
+                    .filter(|(_, card)| filter.matches(&query, card, collection))
+                    .map(|(id, _)| id)
+                    .collect::<Vec<_>>()
+            })
         }
     });
 
+    let page_mode = PageMode::stored();
+    // Number of loaded pages for infinite scrolling
     let (pages, set_pages) = create_signal(1);
+    // Index of the current page for paged mode
+    let page = create_rw_signal(0);
+    let page_count = move || filtered_cards.with(Vec::len).div_ceil(PAGE_SIZE).max(1);
+
     let paginated_cards = move || {
+        let range = match page_mode.get() {
+            PageMode::Infinite => 0..pages.get() * PAGE_SIZE,
+            PageMode::Paged => page.get() * PAGE_SIZE..(page.get() + 1) * PAGE_SIZE,
+        };
+
         filtered_cards.with(|cards| {
             cards
                 .iter()
                 .copied()
-                .take(pages.get() * PAGE_SIZE)
+                .skip(range.start)
+                .take(range.len())
                 .collect::<Vec<_>>()
         })
     };
@@ -102,6 +686,10 @@ pub fn CardSearch() -> impl IntoView {
 
     // Increase page count until the scroll buffer is sufficiently filled
     let adjust_pages = move || {
+        if page_mode.get_untracked() != PageMode::Infinite {
+            return;
+        }
+
         let scroll_area = scroll_area_ref.get_untracked().unwrap();
         let card_count = filtered_cards.with_untracked(Vec::len);
         let mut pages = pages.get_untracked();
@@ -118,45 +706,66 @@ pub fn CardSearch() -> impl IntoView {
     };
 
     // Provide a callback to reset scrolling when the search text changes
-    provide_context(ScrollReset {
+    let reset = ScrollReset {
         callback: Callback::new(move |()| {
             let scroll_area = scroll_area_ref.get_untracked().unwrap();
             scroll_area.set_scroll_top(0);
 
             set_pages.set(0);
+            page.set(0);
             adjust_pages();
         }),
-    });
+    };
+    provide_context(reset);
 
-    // Adjust page count on resize
-    scroll_area_ref.on_load(move |scroll_area| {
-        let callback = move |_, _| adjust_pages();
-        let callback = Closure::<dyn Fn(js_sys::Array, web_sys::ResizeObserver)>::new(callback)
-            .into_js_value();
-        let observer = web_sys::ResizeObserver::new(callback.as_ref().unchecked_ref()).unwrap();
-        observer.observe(&scroll_area);
+    // Bail out of an over-constrained search, back to the default browsing view
+    let clear_filters = Callback::new(move |()| {
+        filter.clear();
+        browse_mode.set(BrowseMode::default());
+        reset.callback.call(());
     });
 
     view! {
         <div class="card-search">
-            <div class="card-search-params">
-                <FilterInput placeholder="Name" map=|s| s.to_ascii_lowercase() filter=filter.name />
-                <FilterInput
-                    placeholder="Description"
-                    map=|s| s.to_ascii_lowercase()
-                    filter=filter.text
-                />
-            </div>
-
-            <div class="card-list" ref=scroll_area_ref on:scroll=move |_| adjust_pages()>
-                <For
-                    each=paginated_cards
-                    key=|id| *id
-                    children=move |id| {
-                        view! { <CardView id=id /> }
-                    }
-                />
-            </div>
+            <FilterPanel
+                filter=filter
+                browse_mode=browse_mode
+                filtered_cards=filtered_cards
+                clear_filters=clear_filters
+            />
+            <ResultList
+                ids=Signal::derive(paginated_cards)
+                no_matches=Signal::derive(move || {
+                    !filter.is_empty() && filtered_cards.with(Vec::is_empty)
+                })
+                node_ref=scroll_area_ref
+                adjust_pages=Callback::new(move |()| adjust_pages())
+                clear_filters=clear_filters
+            />
+
+            <Pagination
+                page_mode=page_mode
+                page=page
+                page_count=Signal::derive(page_count)
+                scroll_area_ref=scroll_area_ref
+            />
         </div>
     }
 }
+
+#[cfg(test)]
+mod test {
+    use common::card::EffectTag;
+    use itertools::Itertools;
+
+    use super::tag_key;
+
+    #[test]
+    fn tag_keys() {
+        // The keys are part of shared links, so they must stay the same
+        assert_eq!(tag_key(EffectTag::Destroy), "destroy");
+        assert_eq!(tag_key(EffectTag::SpecialSummonFromGraveyard), "revive");
+        assert_eq!(tag_key(EffectTag::HandTrap), "hand-trap");
+        assert!(EffectTag::iter().map(tag_key).all_unique());
+    }
+}
@@ -1,156 +1,318 @@
-use std::rc::Rc;
+use std::{rc::Rc, time::Duration};
 
 use common::{
     card::{
-        Attribute, Card, CardType, LinkMarker, MonsterEffect, MonsterStats, MonsterType, Race,
-        SpanKind, SpellType, TextBlock, TextPart, TrapType,
+        tributes, Card, CardPassword, CardType, EffectTag, Header, LinkMarker, MonsterEffect,
+        MonsterStats, MonsterType, SpanKind, TextBlock, TextPart,
     },
     card_data::{CardData, Id},
-    transfer::{IMAGE_DIRECTORY, IMAGE_FILE_ENDING},
+    deck::PartType,
+    deck_part::DeckPart,
+    transfer::{IMAGE_DIRECTORY, IMAGE_FILE_ENDING, IMAGE_SIZE},
+    validation::{CopyLimits, DeckError},
 };
 use itertools::intersperse_with;
 use leptos::{
-    component, create_node_ref, create_signal, expect_context,
+    component, create_node_ref, create_rw_signal, ev, event_target, event_target_value,
+    expect_context,
     html::{self, Div},
-    provide_context, svg, view, IntoView, NodeRef, Show, SignalGet, SignalSet, View, WriteSignal,
+    leptos_dom::helpers::TimeoutHandle,
+    provide_context, request_animation_frame, set_timeout, set_timeout_with_handle, spawn_local,
+    store_value, svg, view, window_event_listener, IntoView, Memo, NodeRef, RwSignal, Show, Signal,
+    SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, SignalWithUntracked,
+    StoredValue, View,
 };
-use web_sys::MouseEvent;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{js_sys, DomRect, HtmlElement, HtmlImageElement, KeyboardEvent, MouseEvent};
 
-use crate::ui::drag_drop::start_drag;
+use crate::{
+    collection::Collection,
+    deck::Deck,
+    i18n::{Locale, Text},
+    links, print_error,
+    ui::{
+        collection::OwnedCopies,
+        deck_view::ActivePart,
+        drag_drop::{set_drag_copies, start_drag},
+        settings::Setting,
+    },
+};
+
+/// Shown instead of card images which fail to load, e.g. when missing on the server
+const PLACEHOLDER_IMAGE: &str = "data:image/svg+xml,\
+    %3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 96 96'%3E\
+    %3Crect width='96' height='96' fill='gray'/%3E\
+    %3Ctext x='48' y='64' font-size='48' font-family='sans-serif' text-anchor='middle' \
+    fill='white'%3E%3F%3C/text%3E%3C/svg%3E";
+
+/// Location of the image of a card.
+#[must_use]
+pub fn image_url(password: CardPassword) -> String {
+    format!("{IMAGE_DIRECTORY}/{password}.{IMAGE_FILE_ENDING}")
+}
 
 #[derive(Clone, Copy)]
 struct TooltipData {
+    id: Id,
     card: &'static Card,
     node: NodeRef<Div>,
 }
 
-fn map_race(race: Race) -> &'static str {
-    match race {
-        Race::Aqua => "Aqua",
-        Race::Beast => "Beast",
-        Race::BeastWarrior => "Beast-Warrior",
-        Race::CreatorGod => "Creator God",
-        Race::Cyberse => "Cyberse",
-        Race::Dinosaur => "Dinosaur",
-        Race::DivineBeast => "Divine-Beast",
-        Race::Dragon => "Dragon",
-        Race::Fairy => "Fairy",
-        Race::Fiend => "Fiend",
-        Race::Fish => "Fish",
-        Race::Illusion => "Illusion",
-        Race::Insect => "Insect",
-        Race::Machine => "Machine",
-        Race::Plant => "Plant",
-        Race::Psychic => "Psychic",
-        Race::Pyro => "Pyro",
-        Race::Reptile => "Reptile",
-        Race::Rock => "Rock",
-        Race::SeaSerpent => "Sea Serpent",
-        Race::Spellcaster => "Spellcaster",
-        Race::Thunder => "Thunder",
-        Race::Warrior => "Warrior",
-        Race::WingedBeast => "Winged Beast",
-        Race::Wyrm => "Wyrm",
-        Race::Zombie => "Zombie",
-    }
+#[derive(Clone, Copy)]
+struct Tooltip {
+    data: RwSignal<Option<TooltipData>>,
+    /// Pinned tooltips stay open when the mouse leaves the card, so their content can be used.
+    pinned: RwSignal<bool>,
+    /// Element focused before pinning, which gets the focus back when the tooltip is closed
+    return_focus: StoredValue<Option<HtmlElement>>,
+    /// Tooltip waiting for the hover delay to pass
+    pending: StoredValue<Option<TimeoutHandle>>,
+    delay: RwSignal<TooltipDelay>,
 }
 
-fn get_tags(card: &Card) -> Vec<View> {
-    let mut tags = Vec::new();
+impl Tooltip {
+    fn new() -> Self {
+        Self {
+            data: create_rw_signal(None),
+            pinned: create_rw_signal(false),
+            return_focus: store_value(None),
+            pending: store_value(None),
+            delay: expect_context(),
+        }
+    }
 
-    match &card.card_type {
-        CardType::Monster {
-            race,
-            attribute,
-            stats,
-            effect,
-            is_tuner,
-        } => {
-            let (monster, level) = match stats {
-                MonsterStats::Normal {
-                    level,
-                    monster_type,
-                    ..
-                } => {
-                    let monster_type = match monster_type {
-                        None => "Monster",
-                        Some(MonsterType::Fusion) => "Fusion Monster",
-                        Some(MonsterType::Ritual) => "Ritual Monster",
-                        Some(MonsterType::Synchro) => "Synchro Monster",
-                        Some(MonsterType::Xyz) => "Xyz Monster",
-                    };
-                    (monster_type, level)
-                }
-                MonsterStats::Link { link_value, .. } => ("Link Monster", link_value),
-            };
-            tags.push(view! { <li>{monster} <span class="level">{*level}</span></li> });
+    fn cancel_pending(self) {
+        if let Some(handle) = self.pending.get_value() {
+            self.pending.set_value(None);
+            handle.clear();
+        }
+    }
 
-            if let MonsterStats::Normal {
-                pendulum_scale: Some(scale),
-                ..
-            } = stats
-            {
-                tags.push(view! { <li>"Pendulum" <span class="level">{*scale}</span></li> });
-            }
+    /// Show the tooltip after the hover delay, so moving the mouse across cards does not render
+    /// the tooltip of every card on the way.
+    fn show(self, data: TooltipData) {
+        self.cancel_pending();
+        if self.pinned.get_untracked() {
+            return;
+        }
 
-            let effect = match effect {
-                MonsterEffect::Normal => None,
-                MonsterEffect::Effect => Some("Effect"),
-                MonsterEffect::Spirit => Some("Spirit Effect"),
-                MonsterEffect::Toon => Some("Toon Effect"),
-                MonsterEffect::Union => Some("Union Effect"),
-                MonsterEffect::Gemini => Some("Gemini Effect"),
-                MonsterEffect::Flip => Some("Flip Effect"),
-            };
-            if let Some(effect) = effect {
-                tags.push(html::li().child(effect));
-            }
+        let delay = self.delay.get_untracked().duration();
+        if delay.is_zero() {
+            self.data.set(Some(data));
+            return;
+        }
 
-            if *is_tuner {
-                tags.push(html::li().child("Tuner"));
+        let show = move || {
+            self.pending.set_value(None);
+            if !self.pinned.get_untracked() {
+                self.data.set(Some(data));
             }
+        };
+        self.pending
+            .set_value(set_timeout_with_handle(show, delay).ok());
+    }
 
-            let attribute = match attribute {
-                Attribute::Dark => "Dark",
-                Attribute::Earth => "Earth",
-                Attribute::Fire => "Fire",
-                Attribute::Light => "Light",
-                Attribute::Water => "Water",
-                Attribute::Wind => "Wind",
-                Attribute::Divine => "Divine",
-            };
-            tags.push(html::li().child(attribute));
+    fn hide(self) {
+        self.cancel_pending();
+        if !self.pinned.get_untracked() {
+            self.data.set(None);
+        }
+    }
 
-            tags.push(html::li().child(map_race(*race)));
+    fn pin(self, data: TooltipData) {
+        self.cancel_pending();
+        if !self.pinned.get_untracked() {
+            let focused = leptos::document()
+                .active_element()
+                .and_then(|element| element.dyn_into::<HtmlElement>().ok());
+            self.return_focus.set_value(focused);
         }
-        CardType::Trap(trap_type) => {
-            let tag = match trap_type {
-                TrapType::Normal => "Trap",
-                TrapType::Counter => "Counter Trap",
-                TrapType::Continuous => "Continuous Trap",
-            };
-            tags.push(html::li().child(tag));
+
+        // Pin first, so the new tooltip takes the focus when it is rendered
+        self.pinned.set(true);
+        self.data.set(Some(data));
+    }
+
+    fn close(self) {
+        self.cancel_pending();
+        self.pinned.set(false);
+        self.data.set(None);
+
+        if let Some(element) = self.return_focus.get_value() {
+            self.return_focus.set_value(None);
+            let _ = element.focus();
         }
-        CardType::Spell(spell_type) => {
-            let tag = match spell_type {
-                SpellType::Normal => "Spell",
-                SpellType::Field => "Field Spell",
-                SpellType::Equip => "Equip Spell",
-                SpellType::Continuous => "Continuous Spell",
-                SpellType::QuickPlay => "Quick-Play Spell",
-                SpellType::Ritual => "Ritual Spell",
-            };
-            tags.push(html::li().child(tag));
+    }
+}
+
+/// Action of a right click on cards in the deck.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RightClick {
+    /// Remove a copy of the card
+    #[default]
+    Delete,
+    /// Open the context menu of the browser
+    Menu,
+}
+
+impl Setting for RightClick {
+    const STORAGE_KEY: &'static str = "right_click";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Delete, Self::Menu].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Menu => "menu",
         }
     }
 
-    let limit = match card.limit {
-        common::card::CardLimit::Unlimited => "Unlimited",
-        common::card::CardLimit::SemiLimited => "Semi-Limited",
-        common::card::CardLimit::Limited => "Limited",
-        common::card::CardLimit::Forbidden => "Forbidden",
-    };
-    tags.push(html::li().child(limit));
+    fn name(self) -> Text {
+        match self {
+            Self::Delete => Text::RightClickDeletes,
+            Self::Menu => Text::RightClickOpensMenu,
+        }
+    }
+}
+
+/// Time the mouse has to rest on a card before its tooltip is shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TooltipDelay {
+    Off,
+    #[default]
+    Short,
+    Long,
+}
+
+impl TooltipDelay {
+    fn duration(self) -> Duration {
+        Duration::from_millis(match self {
+            Self::Off => 0,
+            Self::Short => 150,
+            Self::Long => 500,
+        })
+    }
+}
+
+impl Setting for TooltipDelay {
+    const STORAGE_KEY: &'static str = "tooltip_delay";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Off, Self::Short, Self::Long].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Short => "short",
+            Self::Long => "long",
+        }
+    }
+
+    fn name(self) -> Text {
+        match self {
+            Self::Off => Text::NoTooltipDelay,
+            Self::Short => Text::ShortTooltipDelay,
+            Self::Long => Text::LongTooltipDelay,
+        }
+    }
+}
+
+/// Markers on the cards in the deck, for a quick read of the engine pieces.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EngineMarkers {
+    #[default]
+    Off,
+    /// Mark Tuners and cards tagged as [`EffectTag::Extender`]
+    On,
+}
+
+impl Setting for EngineMarkers {
+    const STORAGE_KEY: &'static str = "engine_markers";
+
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self::Off, Self::On].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::On => "on",
+        }
+    }
+
+    fn name(self) -> Text {
+        match self {
+            Self::Off => Text::NoEngineMarkers,
+            Self::On => Text::MarkEngineCards,
+        }
+    }
+}
+
+/// Short labels of the engine markers for a card, with their description.
+fn engine_markers(card: &Card) -> Vec<(&'static str, Text)> {
+    let mut markers = vec![];
+    if card.card_type.is_tuner() {
+        markers.push(("T", Text::Tuner));
+    }
+    if card.effect_tags.has(EffectTag::Extender) {
+        markers.push(("E", Text::EffectTag(EffectTag::Extender)));
+    }
+    markers
+}
+
+/// Explanation of where a Pendulum monster goes, as its deck part differs from where it ends up
+/// during a duel.
+fn pendulum_note(card: &Card) -> Option<Text> {
+    if !card.card_type.is_pendulum() {
+        return None;
+    }
+
+    Some(if card.card_type.is_extra_deck_monster() {
+        Text::PendulumExtraDeck
+    } else {
+        Text::PendulumMainDeck
+    })
+}
+
+/// Tags for the information which is not part of the [`Card::summary`].
+fn get_tags(card: &Card, locale: Locale) -> Vec<View> {
+    let mut tags = Vec::new();
+
+    if let CardType::Monster {
+        stats:
+            MonsterStats::Normal {
+                level,
+                monster_type,
+                pendulum_scale,
+                ..
+            },
+        ..
+    } = &card.card_type
+    {
+        if monster_type.is_none() {
+            tags.push(view! {
+                <li title=Text::TributesHint.localize(locale)>
+                    {Text::Tributes.localize(locale)}
+                    <span class="level">{tributes(*level)}</span>
+                </li>
+            });
+        }
+
+        if let Some(scale) = pendulum_scale {
+            tags.push(view! {
+                <li title=pendulum_note(card).map(|note| note.localize(locale))>
+                    {Text::PendulumScale.localize(locale)}
+                    <span class="level">{*scale}</span>
+                </li>
+            });
+        }
+    }
+
+    tags.push(html::li().child(Text::CardLimit(card.limit).localize(locale)));
 
     intersperse_with(tags.into_iter().map(IntoView::into_view), || {
         html::li().child("•").into_view()
@@ -172,60 +334,40 @@ fn link_marker_path(link_marker: LinkMarker) -> &'static str {
     }
 }
 
+/// Link markers of Link monsters, the other stats are part of the [`Card::summary`].
 #[component]
 #[must_use]
-fn Stats(card_type: &'static CardType) -> impl IntoView {
-    if let CardType::Monster { stats, .. } = &card_type {
-        let (atk, def, link) = match stats {
-            MonsterStats::Normal { atk, def, .. } => (atk, Some(def), None),
-            MonsterStats::Link {
-                atk, link_markers, ..
-            } => (atk, None, Some(link_markers)),
-        };
-
-        let atk = view! {
-            <span class="label">"ATK"</span>
-            <span class="data">{atk.to_string()}</span>
-        };
-
-        let def = def.map(|def| {
-            view! {
-                <span class="label">"DEF"</span>
-                <span class="data">{def.to_string()}</span>
-            }
-        });
-
-        let link = link.map(|link| {
-            let markers = LinkMarker::iter()
-                .map(|marker| {
-                    svg::polygon()
-                        .attr("points", link_marker_path(marker))
-                        .attr("fill-opacity", if link.has(marker) { "1" } else { "0.2" })
-                })
-                .collect::<Vec<_>>();
-            view! {
-                <span class="label">"LINKS"</span>
-                <svg class="link" viewBox="0 0 6 6" width="1em" height="1em">
-                    {markers}
-                </svg>
-            }
-        });
+fn LinkArrows(card_type: &'static CardType) -> impl IntoView {
+    let CardType::Monster {
+        stats: MonsterStats::Link { link_markers, .. },
+        ..
+    } = card_type
+    else {
+        return None;
+    };
 
-        Some(
-            html::div()
-                .class("stats", true)
-                .child((atk, def, link))
-                .into_view(),
-        )
-    } else {
-        None
-    }
+    let markers = LinkMarker::iter()
+        .map(|marker| {
+            svg::polygon()
+                .attr("points", link_marker_path(marker))
+                .attr(
+                    "fill-opacity",
+                    if link_markers.has(marker) { "1" } else { "0.2" },
+                )
+        })
+        .collect::<Vec<_>>();
+    Some(view! {
+        <div class="stats">
+            <span class="label">"LINKS"</span>
+            <svg class="link" viewBox="0 0 6 6" width="1em" height="1em">
+                {markers}
+            </svg>
+        </div>
+    })
 }
 
-#[component]
-#[must_use]
-fn DescriptionParts(parts: &'static [TextPart<&'static str>]) -> impl IntoView {
-    let mut div = html::div();
+fn text_blocks(parts: &'static [TextPart<&'static str>]) -> Vec<View> {
+    let mut result = Vec::new();
     let mut current_list = None;
     let mut current_block = None;
 
@@ -233,61 +375,630 @@ fn DescriptionParts(parts: &'static [TextPart<&'static str>]) -> impl IntoView {
         match part {
             TextPart::Block(block) => match block {
                 TextBlock::Paragraph => current_block = Some(html::p().into_any()),
-                TextBlock::List => current_list = Some(html::ul()),
+                TextBlock::List => current_list = Some(html::ul().into_any()),
+                TextBlock::OrderedList => current_list = Some(html::ol().into_any()),
                 TextBlock::ListEntry => current_block = Some(html::li().into_any()),
             },
             TextPart::EndBlock(block) => match block {
-                TextBlock::Paragraph => div = div.child(current_block.take().unwrap()),
-                TextBlock::List => div = div.child(current_list.take().unwrap()),
+                TextBlock::Paragraph => result.push(current_block.take().unwrap().into_view()),
+                TextBlock::List | TextBlock::OrderedList => {
+                    result.push(current_list.take().unwrap().into_view());
+                }
                 TextBlock::ListEntry => {
                     current_list = Some(current_list.unwrap().child(current_block.take().unwrap()));
                 }
             },
-            TextPart::Header(header) => {
-                let text = match header {
-                    common::card::Header::PendulumEffect => "Pendulum Effect",
-                    common::card::Header::MonsterEffect => "Monster Effect",
+            // Handled by `DescriptionParts`
+            TextPart::Header(_) => {}
+            TextPart::Separator => result.push(html::div().class("separator", true).into_view()),
+            TextPart::Span(kind, text) => {
+                let span = match kind {
+                    SpanKind::Normal => (*text).into_view(),
+                    SpanKind::CardName => html::span()
+                        .class("card-name", true)
+                        .child(*text)
+                        .into_view(),
+                    SpanKind::Keyword => {
+                        html::span().class("keyword", true).child(*text).into_view()
+                    }
+                    SpanKind::Bold => html::strong().child(*text).into_view(),
                 };
-                div = div.child(html::h2().child(text));
+                current_block = Some(current_block.unwrap().child(span));
             }
-            TextPart::Span(kind, text) => match kind {
-                SpanKind::Normal => {
-                    current_block = Some(current_block.unwrap().child(*text));
+        };
+    }
+
+    result
+}
+
+#[component]
+#[must_use]
+fn DescriptionParts(parts: &'static [TextPart<&'static str>]) -> impl IntoView {
+    let headers = parts.iter().filter_map(|part| match part {
+        TextPart::Header(header) => Some(*header),
+        _ => None,
+    });
+    let mut sections = parts.split(|part| matches!(part, TextPart::Header(_)));
+    let preamble = sections.next().unwrap(/* split always yields at least one element */);
+
+    let sections = headers
+        .zip(sections)
+        .map(|(header, parts)| {
+            let (class, text) = match header {
+                Header::PendulumEffect => ("pendulum-effect", "Pendulum Effect"),
+                Header::MonsterEffect => ("monster-effect", "Monster Effect"),
+            };
+
+            view! {
+                <section class=class>
+                    <h2>{text}</h2>
+                    {text_blocks(parts)}
+                </section>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <div class="description">
+            {text_blocks(preamble)}
+            {sections}
+        </div>
+    }
+}
+
+/// Add the card of the current tooltip to the deck by pressing `1` (Main or Extra deck), `2`
+/// (Side deck) or `a` (last active part), lock or unlock it by pressing `l`, and dismiss it by
+/// pressing `Escape`.
+fn install_deck_shortcuts(tooltip: Tooltip) {
+    let cards = expect_context::<CardData>();
+    let deck = expect_context::<RwSignal<Deck>>();
+    let limits = expect_context::<RwSignal<CopyLimits>>();
+    let active = expect_context::<ActivePart>().0;
+
+    window_event_listener(ev::keydown, move |ev| {
+        if ev.ctrl_key() || ev.meta_key() || ev.alt_key() || ev.repeat() {
+            return;
+        }
+
+        // Do not interfere with typing
+        let target = event_target::<web_sys::Element>(&ev);
+        if matches!(target.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") {
+            return;
+        }
+
+        let part_type = match ev.key().as_str() {
+            "Escape" => {
+                if tooltip.pinned.get_untracked() {
+                    tooltip.close();
+                } else {
+                    tooltip.data.set(None);
                 }
-            },
+                return;
+            }
+            "1" => PartType::Playing,
+            "2" => PartType::Side,
+            "a" => active.get_untracked().into(),
+            "l" => {
+                if let Some(data) = tooltip.data.get_untracked() {
+                    deck.update(|deck| deck.toggle_locked(data.id));
+                }
+                return;
+            }
+            _ => return,
         };
+
+        if let Some(data) = tooltip.data.get_untracked() {
+            let mut result = Ok(());
+            let cap = limits.with_untracked(|limits| limits.cap(data.id, &cards));
+            deck.update(|deck| result = deck.increment_up_to(data.id, part_type, 1, cap));
+            if let Err(err) = result {
+                print_error!("Could not add \"{}\": {err}", data.card.name);
+            }
+        }
+    });
+}
+
+/// Space between a card and its tooltip, and between the tooltip and the viewport edges, in pixels
+const TOOLTIP_GAP: f64 = 8.0;
+
+/// How long the confirmation is shown after copying a card password
+const COPIED_DURATION: Duration = Duration::from_secs(2);
+
+/// Position of the tooltip, preferably to the right of the card.
+///
+/// If the tooltip does not fit there, it is flipped to the left of the card. If it does not fit on
+/// either side, or would extend past the bottom of the viewport, it is clamped to the viewport.
+fn place_tooltip(card: &DomRect, tooltip: &DomRect) -> (f64, f64) {
+    let root = leptos::document().document_element().unwrap();
+    let viewport_width = f64::from(root.client_width());
+    let viewport_height = f64::from(root.client_height());
+    let width = tooltip.width();
+
+    let right = card.right() + TOOLTIP_GAP;
+    let flipped = card.left() - TOOLTIP_GAP - width;
+    let left = if right + width + TOOLTIP_GAP <= viewport_width {
+        right
+    } else if flipped >= TOOLTIP_GAP {
+        flipped
+    } else {
+        (viewport_width - TOOLTIP_GAP - width).max(TOOLTIP_GAP)
+    };
+
+    let top = card
+        .top()
+        .min(viewport_height - TOOLTIP_GAP - tooltip.height())
+        .max(TOOLTIP_GAP);
+
+    (left, top)
+}
+
+/// Keep the focus inside of pinned tooltips when tabbing.
+fn trap_focus(ev: &KeyboardEvent, container: &HtmlElement) {
+    if ev.key() != "Tab" {
+        return;
+    }
+
+    let Ok(focusable) = container.query_selector_all("button, select, a[href]") else {
+        return;
+    };
+    if focusable.length() == 0 {
+        return;
+    }
+    let first = focusable.get(0);
+    let last = focusable.get(focusable.length() - 1);
+
+    let active = leptos::document().active_element().map(web_sys::Node::from);
+    let on_container = active.as_ref() == Some(container.as_ref());
+    let target = if ev.shift_key() && (on_container || active == first) {
+        last
+    } else if !ev.shift_key() && (on_container || active == last) {
+        first
+    } else {
+        return;
+    };
+
+    if let Some(element) = target.and_then(|node| node.dyn_into::<HtmlElement>().ok()) {
+        let _ = element.focus();
+        ev.prevent_default();
     }
+}
 
-    div
+/// Buttons of pinned tooltips, to close them and to act on the card.
+#[component]
+fn TooltipControls(data: TooltipData) -> impl IntoView {
+    let tooltip = expect_context::<Tooltip>();
+    let deck = expect_context::<RwSignal<Deck>>();
+    let limits = expect_context::<RwSignal<CopyLimits>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    let copied = create_rw_signal(false);
+    let copy_password = move |_| {
+        let password = data.card.password.to_string();
+        spawn_local(async move {
+            if write_clipboard(&password).await {
+                copied.set(true);
+                set_timeout(
+                    move || {
+                        // The tooltip may be closed by now
+                        let _ = copied.try_set(false);
+                    },
+                    COPIED_DURATION,
+                );
+            } else {
+                let _ = gloo_dialogs::prompt(
+                    &Text::CardPassword.localize(locale.get_untracked()),
+                    Some(&password),
+                );
+            }
+        });
+    };
+
+    let password = data.card.password;
+    let limit_override = move || limits.with(|limits| limits.override_for(password));
+    let set_limit = move |_| {
+        let current = limit_override().map(|limit| limit.to_string());
+        let Some(input) = gloo_dialogs::prompt(
+            &Text::LimitPrompt(data.card.name).localize(locale.get_untracked()),
+            Some(current.as_deref().unwrap_or_default()),
+        ) else {
+            return;
+        };
+
+        let input = input.trim();
+        let limit = if input.is_empty() {
+            None
+        } else if let Ok(limit) = input.parse() {
+            Some(limit)
+        } else {
+            print_error!("\"{input}\" is not a valid number of copies");
+            return;
+        };
+        limits.update(|limits| limits.set_override(password, limit));
+    };
+
+    view! {
+        <button class="close" aria-label="Close" on:click=move |_| tooltip.close()>
+            "X"
+        </button>
+        <button class="copy" on:click=copy_password>
+            {move || {
+                let text = if copied.get() { Text::Copied } else { Text::CopyPassword };
+                text.localize(locale.get())
+            }}
+        </button>
+        <button class="limit" on:click=set_limit>
+            {move || limit_override().map_or(Text::SetLimit, Text::Limit).localize(locale.get())}
+        </button>
+        <Show when=move || deck.with(|deck| deck.total_count(data.id) > 0)>
+            <button class="lock" on:click=move |_| deck.update(|deck| deck.toggle_locked(data.id))>
+                {move || {
+                    let text = if deck.with(|deck| deck.locked_count(data.id) > 0) {
+                        Text::Unlock
+                    } else {
+                        Text::Lock
+                    };
+                    text.localize(locale.get())
+                }}
+            </button>
+            <PrintingSelect id=data.id />
+        </Show>
+    }
+}
+
+/// Select for the printing of a card in the deck, which is used on export.
+#[component]
+fn PrintingSelect(id: Id) -> impl IntoView {
+    let cards = expect_context::<CardData>();
+    let deck = expect_context::<RwSignal<Deck>>();
+
+    let main = cards.password_for_id(id);
+    let printings = cards.printings(id);
+    let has_alternatives = printings.len() > 1;
+    let select = move |ev: web_sys::Event| {
+        let password = event_target_value(&ev)
+            .parse()
+            .ok()
+            .filter(|password| *password != main);
+        deck.update(|deck| deck.set_printing(id, password));
+    };
+    let options = printings
+        .into_iter()
+        .map(|password| {
+            let label = if password == main {
+                format!("Main printing ({password})")
+            } else {
+                format!("Printing {password}")
+            };
+            let selected = move || deck.with(|deck| deck.printing(id).unwrap_or(main)) == password;
+            view! {
+                <option value=password.to_string() selected=selected>
+                    {label}
+                </option>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    has_alternatives.then(|| {
+        view! {
+            <select class="printing" aria-label="Printing used on export" on:change=select>
+                {options}
+            </select>
+        }
+    })
+}
+
+/// Links to the card in external databases.
+#[component]
+fn CardLinks(card: &'static Card) -> impl IntoView {
+    view! {
+        <ul class="links">
+            <li>
+                <a href=links::yugipedia(card) target="_blank" rel="noopener">
+                    "Yugipedia"
+                </a>
+            </li>
+            <li>
+                <a href=links::ygoprodeck(card) target="_blank" rel="noopener">
+                    "YGOPRODeck"
+                </a>
+            </li>
+            <li>
+                <a href=links::yugipedia_rulings(card) target="_blank" rel="noopener">
+                    "Rulings"
+                </a>
+            </li>
+        </ul>
+    }
 }
 
 #[component]
 #[must_use]
 pub fn CardTooltip() -> impl IntoView {
-    let (tooltip_data, set_tooltip_data) = create_signal(None);
-    provide_context(set_tooltip_data);
+    let tooltip = Tooltip::new();
+    provide_context(tooltip);
+    install_deck_shortcuts(tooltip);
+    let locale = expect_context::<RwSignal<Locale>>();
 
     let popup = move || {
-        tooltip_data.get().map(|data: TooltipData| {
-            let rect = data.node.get().unwrap().get_bounding_client_rect();
-            let left = rect.right();
-            let top = rect.top();
+        tooltip.data.get().map(|data: TooltipData| {
+            let card_rect = data.node.get().unwrap().get_bounding_client_rect();
+            // Hidden until its size is known and it can be placed within the viewport
+            let position = create_rw_signal(None::<(f64, f64)>);
+            let left = card_rect.right() + TOOLTIP_GAP;
+            let top = card_rect.top();
+
+            let node_ref = create_node_ref::<Div>();
+            node_ref.on_load(move |node| {
+                request_animation_frame(move || {
+                    let rect = node.get_bounding_client_rect();
+                    position.set(Some(place_tooltip(&card_rect, &rect)));
+
+                    if tooltip.pinned.get_untracked() {
+                        let _ = node.focus();
+                    }
+                });
+            });
+
             view! {
                 <div
                     class="card-tooltip"
-                    style=("--left", format!("{left}px"))
-                    style:top=format!("{top}px")
+                    ref=node_ref
+                    tabindex="-1"
+                    on:keydown=move |ev| {
+                        if tooltip.pinned.get_untracked() {
+                            trap_focus(&ev, &node_ref.get_untracked().unwrap());
+                        }
+                    }
+                    role="dialog"
+                    aria-label=data.card.name
+                    class:pinned=move || tooltip.pinned.get()
+                    style:left=move || format!("{}px", position.get().map_or(left, |(left, _)| left))
+                    style:top=move || format!("{}px", position.get().map_or(top, |(_, top)| top))
+                    style:visibility=move || position.get().is_none().then_some("hidden")
                 >
+                    <Show when=move || tooltip.pinned.get()>
+                        <TooltipControls data=data />
+                    </Show>
                     <h1>{data.card.name}</h1>
-                    <ul class="tags">{get_tags(data.card)}</ul>
-                    <Stats card_type=&data.card.card_type />
+                    <p class="summary">{data.card.summary()}</p>
+                    <ul class="tags">{move || get_tags(data.card, locale.get())}</ul>
+                    <LinkArrows card_type=&data.card.card_type />
+                    <OwnedCopies card=data.card />
                     <DescriptionParts parts=data.card.description />
+                    <CardLinks card=data.card />
                 </div>
             }
         })
     };
 
-    view! { <Show when=move || tooltip_data.get().is_some()>{popup}</Show> }
+    view! { <Show when=move || tooltip.data.get().is_some()>{popup}</Show> }
+}
+
+/// Write `text` to the clipboard, returning whether it succeeded.
+pub async fn write_clipboard(text: &str) -> bool {
+    // `navigator.clipboard` is missing outside of secure contexts, so it is looked up dynamically
+    let navigator = leptos::window().navigator();
+    let Ok(clipboard) = js_sys::Reflect::get(&navigator, &"clipboard".into()) else {
+        return false;
+    };
+    let Ok(write_text) = js_sys::Reflect::get(&clipboard, &"writeText".into())
+        .and_then(JsCast::dyn_into::<js_sys::Function>)
+    else {
+        return false;
+    };
+    let Ok(promise) = write_text.call1(&clipboard, &text.into()) else {
+        return false;
+    };
+
+    JsFuture::from(js_sys::Promise::from(promise)).await.is_ok()
+}
+
+/// Name of the pinned card and buttons to add or remove it, for layouts without drag and drop.
+#[component]
+#[must_use]
+pub fn SelectionActions() -> impl IntoView {
+    let cards = expect_context::<CardData>();
+    let tooltip = expect_context::<Tooltip>();
+    let deck = expect_context::<RwSignal<Deck>>();
+    let limits = expect_context::<RwSignal<CopyLimits>>();
+
+    let selected = move || tooltip.pinned.get().then(|| tooltip.data.get()).flatten();
+
+    let add = move |part_type| {
+        if let Some(data) = selected() {
+            let mut result = Ok(());
+            let cap = limits.with_untracked(|limits| limits.cap(data.id, &cards));
+            deck.update(|deck| result = deck.increment_up_to(data.id, part_type, 1, cap));
+            if let Err(err) = result {
+                print_error!("Could not add \"{}\": {err}", data.card.name);
+            }
+        }
+    };
+    let remove = move |_| {
+        if let Some(data) = selected() {
+            deck.update(|deck| {
+                let part_type = if deck
+                    .entries()
+                    .any(|entry| entry.id() == data.id && entry.count(PartType::Playing) > 0)
+                {
+                    PartType::Playing
+                } else {
+                    PartType::Side
+                };
+                deck.decrement(data.id, part_type, 1);
+            });
+        }
+    };
+
+    view! {
+        <div class="selection">
+            <span class="name">
+                {move || selected().map_or("Tap a card to select it", |data| data.card.name)}
+            </span>
+            <button on:click=move |_| add(PartType::Playing) disabled=move || selected().is_none()>
+                "Add"
+            </button>
+            <button on:click=move |_| add(PartType::Side) disabled=move || selected().is_none()>
+                "Side"
+            </button>
+            <button
+                on:click=remove
+                disabled=move || {
+                    !selected().is_some_and(|data| deck.with(|deck| deck.total_count(data.id) > 0))
+                }
+            >
+                "Remove"
+            </button>
+        </div>
+    }
+}
+
+/// Badge with the deck part a card outside of the deck is added to.
+fn target_badge(card: &Card, locale: Locale) -> impl IntoView {
+    let part = [DeckPart::Main, DeckPart::Extra]
+        .into_iter()
+        .find(|part| part.can_contain(card))
+        .unwrap_or(DeckPart::Side);
+    let added = Text::AddedTo(part).localize(locale);
+    let title = match pendulum_note(card) {
+        Some(hint) => format!(
+            "{added}\n\n{}: {}",
+            Text::Pendulum.localize(locale),
+            hint.localize(locale)
+        ),
+        None => added,
+    };
+    let pendulum = if card.card_type.is_pendulum() {
+        " (P)"
+    } else {
+        ""
+    };
+    html::div()
+        .class("target", true)
+        .class("backdrop", true)
+        .attr("title", title)
+        .child(format!("→ {part}{pendulum}"))
+}
+
+/// The overlays of a [`CardView`], like the copy count and the errors of the card.
+#[component]
+#[must_use]
+fn CardBadges(
+    id: Id,
+    count: u8,
+    total: Option<Signal<u8>>,
+    locked: Option<Signal<bool>>,
+    show_target: bool,
+    show_engine: bool,
+) -> impl IntoView {
+    let cards = expect_context::<CardData>();
+    let card = cards.get(id);
+    let password = card.password;
+    let collection = expect_context::<RwSignal<Collection>>();
+    let limits = expect_context::<RwSignal<CopyLimits>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    // Copies needed beyond the owned ones, if collection tracking is enabled
+    let missing = move || {
+        let total = total?.get();
+        collection
+            .with(|collection| {
+                collection
+                    .enabled
+                    .then(|| total.saturating_sub(collection.owned(password)))
+            })
+            .filter(|missing| *missing > 0)
+    };
+
+    // Legality errors concerning this card. Cards shown outside of the deck only check their
+    // own count against the limit.
+    let deck_errors = expect_context::<Memo<Vec<DeckError>>>();
+    let errors = move || {
+        let locale = locale.get();
+        if total.is_none() {
+            let allowed = limits.with(|limits| limits.allowed(id, &cards));
+            return (count > allowed)
+                .then(|| Text::AtMostAllowed(allowed).localize(locale))
+                .into_iter()
+                .collect::<Vec<_>>();
+        }
+
+        deck_errors.with(|errors| {
+            errors
+                .iter()
+                .filter_map(|error| match *error {
+                    DeckError::OverLimit {
+                        id: error_id,
+                        count,
+                        allowed,
+                    } if error_id == id => Some(Text::OverLimit(count, allowed).localize(locale)),
+                    DeckError::IllegalPartPlacement { id: error_id, part } if error_id == id => {
+                        Some(Text::NotPlayableFrom(part).localize(locale))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let engine_markers_setting = expect_context::<RwSignal<EngineMarkers>>();
+    let markers = move || {
+        (show_engine && engine_markers_setting.get() == EngineMarkers::On)
+            .then(|| engine_markers(card))
+            .filter(|markers| !markers.is_empty())
+    };
+
+    view! {
+        {(count > 1)
+            .then(|| html::div().class("count", true).class("backdrop", true).child(count))}
+        {show_target.then_some(move || target_badge(card, locale.get()))}
+        {move || {
+            let errors = errors();
+            (!errors.is_empty())
+                .then(|| {
+                    html::div()
+                        .class("error", true)
+                        .class("backdrop", true)
+                        .attr("title", errors.join("\n"))
+                        .child("!")
+                })
+        }}
+        {move || {
+            markers()
+                .map(|markers| {
+                    let title = markers
+                        .iter()
+                        .map(|(_, description)| description.localize(locale.get()))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let labels = markers.iter().map(|(label, _)| *label).collect::<String>();
+                    html::div()
+                        .class("engine", true)
+                        .class("backdrop", true)
+                        .attr("title", title)
+                        .child(labels)
+                })
+        }}
+        {move || {
+            locked
+                .is_some_and(|locked| locked.get())
+                .then(|| {
+                    html::div()
+                        .class("lock", true)
+                        .attr("title", Text::Locked.localize(locale.get()))
+                        .child("🔒")
+                })
+        }}
+        {move || {
+            missing()
+                .map(|missing| {
+                    html::div()
+                        .class("missing", true)
+                        .class("backdrop", true)
+                        .child(Text::NeedMore(missing).localize(locale.get()))
+                })
+        }}
+    }
 }
 
 #[component]
@@ -295,45 +1006,136 @@ pub fn CardTooltip() -> impl IntoView {
 pub fn CardView(
     id: Id,
     #[prop(default = 1)] count: u8,
+    /// Copies across all deck parts, used for the limit and collection checks instead of `count`
+    #[prop(optional, into)]
+    total: Option<Signal<u8>>,
+    /// Whether the card is locked in the deck, see [`Deck::toggle_locked`]
+    #[prop(optional, into)]
+    locked: Option<Signal<bool>>,
     #[prop(optional)] on_delete: Option<Rc<dyn Fn(Id)>>,
+    /// Called when the card is clicked with the middle mouse button
+    #[prop(optional)]
+    on_middle_click: Option<Rc<dyn Fn(Id)>>,
+    /// Show the deck part the card is added to on hover, for cards outside of the deck
+    #[prop(optional)]
+    show_target: bool,
+    /// Copies added when the card is dropped into the deck, see [`set_drag_copies`]
+    #[prop(optional)]
+    drag_copies: Option<u8>,
+    /// Mark Tuners and Extenders if enabled, see [`EngineMarkers`]
+    #[prop(optional)]
+    show_engine: bool,
 ) -> impl IntoView {
-    let card = expect_context::<CardData>().get(id);
-    let password = card.password;
+    let cards = expect_context::<CardData>();
+    let card = cards.get(id);
+    let image = cards.image_password(id);
+
+    let right_click = expect_context::<RwSignal<RightClick>>();
+    let deletes = move || right_click.get() == RightClick::Delete;
 
-    let on_click: Box<dyn FnMut(MouseEvent)> = if let Some(on_delete) = on_delete {
-        Box::new(move |ev: MouseEvent| {
-            if ev.button() == 2 {
+    let tooltip = expect_context::<Tooltip>();
+    let node = create_node_ref();
+
+    // Keyboard alternative to dragging: focused cards can be added using the deck shortcuts,
+    // pinned with Enter and removed with Delete.
+    let on_delete_key = on_delete.clone();
+    let on_key = move |ev: KeyboardEvent| match ev.key().as_str() {
+        "Enter" | " " => {
+            tooltip.pin(TooltipData { id, card, node });
+            ev.prevent_default();
+        }
+        "Delete" | "Backspace" => {
+            if let Some(on_delete) = &on_delete_key {
                 on_delete(id);
                 ev.prevent_default();
             }
-        })
+        }
+        _ => {}
+    };
+    let label = if count > 1 {
+        format!("{count} × {}, {}", card.name, card.summary())
     } else {
-        Box::new(move |_ev: MouseEvent| {})
+        format!("{}, {}", card.name, card.summary())
+    };
+
+    let has_middle_click = on_middle_click.is_some();
+    let on_click = move |ev: MouseEvent| match ev.button() {
+        1 => {
+            if let Some(on_middle_click) = &on_middle_click {
+                on_middle_click(id);
+                ev.prevent_default();
+            }
+        }
+        2 if deletes() => {
+            if let Some(on_delete) = &on_delete {
+                on_delete(id);
+                ev.prevent_default();
+            }
+        }
+        _ => {}
     };
 
-    let set_tooltip_data = expect_context::<WriteSignal<Option<TooltipData>>>();
-    let node = create_node_ref();
     view! {
         <div
             class=get_class(&card.card_type)
             ref=node
+            role="button"
+            tabindex="0"
+            aria-label=label
+            aria-keyshortcuts="1 2 A Enter Delete"
             draggable="true"
-            on:dragstart=move |ev| start_drag(&ev, card)
-            on:mouseover=move |_| set_tooltip_data.set(Some(TooltipData { card, node }))
-            on:mouseout=move |_| set_tooltip_data.set(None)
+            on:dragstart=move |ev| {
+                start_drag(&ev, card);
+                if let Some(copies) = drag_copies {
+                    set_drag_copies(&ev, copies);
+                }
+            }
+            on:mouseover=move |_| tooltip.show(TooltipData { id, card, node })
+            on:mouseout=move |_| tooltip.hide()
+            on:focus=move |_| tooltip.show(TooltipData { id, card, node })
+            on:blur=move |_| tooltip.hide()
+            on:keydown=on_key
+            on:click=move |_| tooltip.pin(TooltipData { id, card, node })
             on:mouseup=on_click
-            on:contextmenu=|ev| ev.prevent_default()
+            on:mousedown=move |ev| {
+                // Prevent autoscrolling when the middle mouse button is used
+                if ev.button() == 1 && has_middle_click {
+                    ev.prevent_default();
+                }
+            }
+            on:contextmenu=move |ev| {
+                if deletes() {
+                    ev.prevent_default();
+                }
+            }
         >
-            <img src=format!("{IMAGE_DIRECTORY}/{password}.{IMAGE_FILE_ENDING}") />
-            {(count > 1)
-                .then(|| html::div().class("count", true).class("backdrop", true).child(count))}
-            {(count > card.limit.count())
-                .then(|| html::div().class("error", true).class("backdrop", true).child("!"))}
+            <img
+                src=image_url(image)
+                width=IMAGE_SIZE
+                height=IMAGE_SIZE
+                on:error=move |ev| {
+                    let img = event_target::<HtmlImageElement>(&ev);
+                    // The placeholder is inline, but never retry it to rule out error loops
+                    if !img.src().starts_with("data:") {
+                        img.set_src(PLACEHOLDER_IMAGE);
+                    }
+                }
+            />
+            <CardBadges
+                id=id
+                count=count
+                total=total
+                locked=locked
+                show_target=show_target
+                show_engine=show_engine
+            />
         </div>
     }
 }
 
-fn get_class(card_type: &CardType) -> String {
+/// CSS classes coloring a card by its type, also used for the exported deck image.
+#[must_use]
+pub fn get_class(card_type: &CardType) -> String {
     let mut classes = vec!["card"];
 
     classes.push(match card_type {
@@ -342,37 +1144,27 @@ fn get_class(card_type: &CardType) -> String {
         CardType::Trap(..) => "trap",
     });
 
-    if let CardType::Monster { stats, effect, .. } = card_type {
+    if let CardType::Monster { effect, .. } = card_type {
         if !matches!(effect, MonsterEffect::Normal) {
             classes.push("effect");
         }
+    }
 
-        if matches!(stats, MonsterStats::Link { .. }) {
-            classes.push("link");
-        }
+    if card_type.is_link() {
+        classes.push("link");
+    }
 
-        if let MonsterStats::Normal {
-            monster_type: Some(monster_type),
-            ..
-        } = stats
-        {
-            classes.push(match monster_type {
-                MonsterType::Ritual => "ritual",
-                MonsterType::Fusion => "fusion",
-                MonsterType::Synchro => "synchro",
-                MonsterType::Xyz => "xyz",
-            });
-        }
+    if let Some(monster_type) = card_type.monster_type() {
+        classes.push(match monster_type {
+            MonsterType::Ritual => "ritual",
+            MonsterType::Fusion => "fusion",
+            MonsterType::Synchro => "synchro",
+            MonsterType::Xyz => "xyz",
+        });
+    }
 
-        if matches!(
-            stats,
-            MonsterStats::Normal {
-                pendulum_scale: Some(_),
-                ..
-            }
-        ) {
-            classes.push("pendulum");
-        }
+    if card_type.is_pendulum() {
+        classes.push("pendulum");
     }
 
     classes.join(" ")
@@ -0,0 +1,64 @@
+use common::card::Card;
+use leptos::{
+    component, create_effect, expect_context, provide_context, view, IntoView, RwSignal, Show,
+    SignalUpdate, SignalWith,
+};
+
+use crate::{collection::Collection, text_encoding::TextEncoding, ui::settings};
+
+/// Install the card collection as leptos context
+pub fn install_as_context() {
+    const KEY: &str = "collection";
+    let collection = RwSignal::new(settings::load_encoded::<Collection>(KEY));
+    create_effect(move |_| settings::save(KEY, &collection.with(TextEncoding::encode_string)));
+
+    provide_context(collection);
+}
+
+/// Checkbox to enable collection tracking.
+#[component]
+#[must_use]
+pub fn CollectionToggle() -> impl IntoView {
+    let collection = expect_context::<RwSignal<Collection>>();
+
+    view! {
+        <label>
+            <input
+                type="checkbox"
+                prop:checked=move || collection.with(|collection| collection.enabled)
+                on:change=move |_| collection.update(|collection| {
+                    collection.enabled = !collection.enabled;
+                })
+            />
+            "Track Collection"
+        </label>
+    }
+}
+
+/// Editor for the owned copies of a card, shown while collection tracking is enabled.
+#[component]
+#[must_use]
+pub fn OwnedCopies(card: &'static Card) -> impl IntoView {
+    let collection = expect_context::<RwSignal<Collection>>();
+    let password = card.password;
+    let owned = move || collection.with(|collection| collection.owned(password));
+    let change = move |delta: i8| {
+        collection.update(|collection| {
+            let count = collection.owned(password).saturating_add_signed(delta);
+            collection.set_owned(password, count);
+        });
+    };
+
+    view! {
+        <Show when=move || collection.with(|collection| collection.enabled)>
+            <div class="owned">
+                <span class="label">"OWNED"</span>
+                <button on:click=move |_| change(-1) disabled=move || owned() == 0>
+                    "-"
+                </button>
+                <span class="data">{owned}</span>
+                <button on:click=move |_| change(1)>"+"</button>
+            </div>
+        </Show>
+    }
+}
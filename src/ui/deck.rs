@@ -1,36 +1,235 @@
 use std::error::Error;
 
-use common::{card_data::CardData, ydk};
+use common::{
+    card::CardLimit,
+    card_data::CardData,
+    export_target::ExportTarget,
+    password_list,
+    validation::{validate, CopyLimits},
+    ydk, ydke,
+};
 use gloo_file::{futures::read_as_text, Blob, File};
 use leptos::{
-    component, create_effect, expect_context, html, logging, provide_context, spawn_local, view,
-    IntoView, NodeRef, RwSignal, SignalSet, SignalUpdate, SignalWith,
+    component, create_effect, create_memo, create_rw_signal, event_target_value, expect_context,
+    html, logging, provide_context, spawn_local, store_value, view, CollectView, IntoView, NodeRef,
+    RwSignal, Show, SignalGet, SignalSet, SignalUpdate, SignalWith, SignalWithUntracked,
 };
-use wasm_bindgen::{closure::Closure, JsCast};
-use web_sys::{KeyboardEvent, Url};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{js_sys, HtmlImageElement, KeyboardEvent, Url, UrlSearchParams};
 
-use crate::{deck::Deck, error_handling::JsException, print_error, text_encoding::TextEncoding};
+use crate::{
+    app_state::AppState,
+    deck::Deck,
+    deck_meta::DeckMeta,
+    deck_order::Grouping,
+    error_handling::JsException,
+    i18n::{Locale, Text},
+    print_error,
+    text_encoding::TextEncoding,
+    ui::{
+        app::{Density, Theme},
+        card_view::{image_url, write_clipboard, EngineMarkers, RightClick, TooltipDelay},
+        collection::CollectionToggle,
+        drawers::DrawerData,
+        samples::SampleDecks,
+        settings::{self, SettingSelect},
+        tools::{
+            deck_image::{deck_image, Header},
+            report,
+        },
+    },
+};
 
-async fn do_import(file: File, cards: &CardData) -> Result<Deck, Box<dyn Error>> {
-    Ok(Deck::new(ydk::load(
-        &read_as_text(&file.into()).await?,
-        cards,
-    )?))
+/// Start loading the images of the cards in the deck, so the deck view shows them right away.
+///
+/// This loads the same images as [`CardView`](crate::ui::card_view::CardView), which shows the
+/// shared image of a card if there is one. Chosen printings only affect the export and have no
+/// images of their own.
+pub fn preload_images(deck: &common::deck::Deck, cards: &CardData) {
+    for entry in deck.entries() {
+        if let Ok(image) = HtmlImageElement::new() {
+            image.set_src(&image_url(cards.image_password(entry.id())));
+        }
+    }
 }
 
-fn do_export(deck: &Deck, cards: &CardData) -> Result<(), Box<dyn Error>> {
-    let mut buffer = Vec::new();
-    ydk::save(deck, cards, &mut buffer)?;
+/// Deck loaded from a YDK file, with the problems to report to the user.
+struct ImportedDeck {
+    deck: common::deck::Deck,
+    /// Names of forbidden cards, as imported decks may be from an older format
+    forbidden: Vec<&'static str>,
+    /// Sections which are duplicated, missing or out of order, and ignored content
+    warnings: Vec<ydk::Warning>,
+}
+
+/// Load a YDK file, reporting the line which could not be parsed on failure.
+async fn do_import(file: File, cards: &CardData) -> Result<ImportedDeck, Box<dyn Error>> {
+    let (deck, warnings) = match ydk::load_with_warnings(&read_as_text(&file.into()).await?, cards)
+    {
+        Ok(result) => result,
+        Err(ydk::Error::Parser(ydk::ParseError { line, column, text })) => {
+            return Err(format!(
+                "Line {line} is not valid, starting at column {column}:\n\n{text}"
+            )
+            .into());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let forbidden = deck
+        .entries()
+        .map(|entry| &cards[entry.id()])
+        .filter(|card| card.limit == CardLimit::Forbidden)
+        .map(|card| card.name)
+        .collect();
+    Ok(ImportedDeck {
+        deck,
+        forbidden,
+        warnings,
+    })
+}
+
+/// Notes about the last imported YDK file, shown in the menu until dismissed.
+#[derive(Debug, Clone)]
+struct ImportSummary {
+    name: String,
+    /// Copies which were not merged due to the card limits
+    skipped: Vec<String>,
+    forbidden: Vec<&'static str>,
+    warnings: Vec<ydk::Warning>,
+}
+
+/// Import a YDK file, replacing the deck or merging the file into it, and report any issues.
+///
+/// Skipped copies, forbidden cards and deviations from the YDK format are collected in `summary`,
+/// as the deck was still imported.
+async fn import_file(
+    file: File,
+    merge: bool,
+    cards: CardData,
+    deck: RwSignal<Deck>,
+    limits: RwSignal<CopyLimits>,
+    summary: RwSignal<Option<ImportSummary>>,
+) {
+    let name = file.name();
+    summary.set(None);
+    match do_import(file, &cards).await {
+        Ok(ImportedDeck {
+            deck: new_deck,
+            forbidden,
+            warnings,
+        }) => {
+            preload_images(&new_deck, &cards);
+            let mut skipped = Vec::new();
+            if merge {
+                let capped = limits.with_untracked(|limits| {
+                    deck.try_update(|deck| deck.merge_entries(&new_deck, &cards, limits))
+                        .unwrap_or_default()
+                });
+                skipped = capped
+                    .iter()
+                    .map(|(id, count)| {
+                        let allowed = limits.with_untracked(|limits| limits.allowed(*id, &cards));
+                        format!("{count}x {} (limit {allowed})", cards[*id].name)
+                    })
+                    .collect();
+            } else {
+                // Name the deck after the imported file
+                let meta = DeckMeta {
+                    name: name.strip_suffix(".ydk").unwrap_or(&name).to_owned(),
+                    ..DeckMeta::default()
+                };
+                deck.update(|deck| {
+                    deck.set_entries(&new_deck);
+                    deck.set_meta(meta);
+                });
+            }
+
+            if !skipped.is_empty() || !forbidden.is_empty() || !warnings.is_empty() {
+                summary.set(Some(ImportSummary {
+                    name,
+                    skipped,
+                    forbidden,
+                    warnings,
+                }));
+            }
+        }
+        Err(err) => print_error!("Error while importing \"{name}\":\n\n{err}"),
+    }
+}
 
-    let blob = Blob::new_with_options(buffer.as_slice(), Some("text/ydk"));
+/// Let the browser download `data` as a file.
+pub fn download(data: &[u8], mime_type: &str, file_name: &str) -> Result<(), Box<dyn Error>> {
+    let blob = Blob::new_with_options(data, Some(mime_type));
     let url = Url::create_object_url_with_blob(blob.as_ref()).map_err(JsException::from)?;
 
-    view! { <a href=&url download="deck.ydk"></a> }.click();
+    view! { <a href=&url download=file_name.to_owned()></a> }.click();
     Url::revoke_object_url(&url).map_err(JsException::from)?;
 
     Ok(())
 }
 
+/// Export the deck for a simulator, letting the user cancel if it would not arrive unchanged.
+fn do_export(deck: &Deck, cards: &CardData, target: ExportTarget) -> Result<(), Box<dyn Error>> {
+    let issues = target.issues(deck, cards);
+    if !issues.is_empty()
+        && !gloo_dialogs::confirm(&format!(
+            "The deck can not be exported for {} as it is:\n\n{}\n\nExport anyway?",
+            target.name(),
+            issues.join("\n")
+        ))
+    {
+        return Ok(());
+    }
+
+    let mut buffer = Vec::new();
+    target.save(deck, cards, &mut buffer)?;
+    let file_name = target.file_name(&deck.meta().file_stem("deck"));
+    download(&buffer, target.mime_type(), &file_name)
+}
+
+/// Download the Markdown report of the deck.
+fn do_export_report(deck: &Deck, cards: &CardData) -> Result<(), Box<dyn Error>> {
+    let report = report::report(deck, deck.meta(), cards);
+    let file_name = format!("{}_report.md", deck.meta().file_stem("deck"));
+    download(report.as_bytes(), "text/markdown", &file_name)
+}
+
+/// Download the deck as an SVG image, with a title chosen by the user.
+fn do_export_image(deck: &Deck, cards: &CardData, locale: Locale) -> Result<(), Box<dyn Error>> {
+    let Some(title) =
+        gloo_dialogs::prompt(&Text::ImageTitle.localize(locale), Some(&deck.meta().name))
+    else {
+        return Ok(());
+    };
+    let date = String::from(js_sys::Date::new_0().to_iso_string());
+    let header = Header {
+        title: title.trim(),
+        // Only the date part of the ISO timestamp
+        date: &date[..10],
+    };
+    let header = (!header.title.is_empty()).then_some(header);
+
+    let image = deck_image(deck, cards, header);
+    let file_name = format!("{}.svg", deck.meta().file_stem("deck"));
+    download(image.as_bytes(), "image/svg+xml", &file_name)
+}
+
+/// Link to the app which imports the deck and its meta, see [`import_from_query`].
+///
+/// The undo history is not part of the link.
+fn share_link(deck: &Deck) -> String {
+    let mut deck = deck.clone();
+    deck.reset_history();
+
+    let location = leptos::window().location();
+    format!(
+        "{}{}?import={}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default(),
+        String::from(js_sys::encode_uri_component(&deck.encode_string()))
+    )
+}
+
 fn install_undo_redo_shortcuts(deck: RwSignal<Deck>) {
     let keyup = Closure::<dyn Fn(KeyboardEvent)>::new(move |ev: KeyboardEvent| {
         let key = if ev.shift_key() {
@@ -50,29 +249,372 @@ fn install_undo_redo_shortcuts(deck: RwSignal<Deck>) {
     leptos::document().set_onkeyup(Some(keyup.as_ref().unchecked_ref()));
     keyup.forget();
 }
-/// Install the main deck instance as leptos context
+
+/// Import the deck passed with `?import=`, either as ydke URL or in the deck text encoding.
+///
+/// The parameter is removed from the address, so reloading the page does not import the deck
+/// again. Cards which are not in the card data are skipped in either format and reported
+/// afterwards. Returns whether a deck was imported.
+fn import_from_query(deck: RwSignal<Deck>, cards: &CardData) -> bool {
+    const PARAM: &str = "import";
+
+    let window = leptos::window();
+    let location = window.location();
+    let Ok(search) = location.search() else {
+        return false;
+    };
+    let Ok(params) = UrlSearchParams::new_with_str(&search) else {
+        return false;
+    };
+    // `UrlSearchParams` decodes `+` as space, which breaks pasted ydke URLs as their base64 can
+    // contain `+`, so only the percent-encoding of the raw value is decoded
+    let Some(text) = search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(PARAM)?.strip_prefix('='))
+        .and_then(|raw| js_sys::decode_uri_component(raw).ok())
+        .map(String::from)
+    else {
+        return false;
+    };
+
+    params.delete(PARAM);
+    let query = String::from(params.to_string());
+    let mut url = location.pathname().unwrap_or_default();
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query);
+    }
+    url.push_str(&location.hash().unwrap_or_default());
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+    }
+
+    let new_deck = if text.starts_with(ydke::PREFIX) {
+        ydke::load_skipping_unknown(&text, cards)
+            .map(|(deck, unknown)| (deck, DeckMeta::default(), unknown.len()))
+            .map_err(|err| err.to_string())
+    } else {
+        Deck::decode_skipping_unknown(&text)
+            .map(|(deck, dropped)| {
+                let meta = deck.meta().clone();
+                (common::deck::Deck::clone(&deck), meta, dropped)
+            })
+            .ok_or_else(|| "unknown deck format".to_owned())
+    };
+    let (new_deck, meta, dropped) = match new_deck {
+        Ok(new_deck) => new_deck,
+        Err(err) => {
+            print_error!("Error while importing the linked deck:\n\n{err}");
+            return false;
+        }
+    };
+
+    let is_empty = deck.with_untracked(|deck| deck.entries().next().is_none());
+    if !is_empty && !gloo_dialogs::confirm("Replace the current deck with the linked deck?") {
+        return false;
+    }
+
+    preload_images(&new_deck, cards);
+
+    deck.update(|deck| {
+        deck.set_entries(&new_deck);
+        deck.set_meta(meta);
+    });
+    if dropped > 0 {
+        print_error!(
+            "{dropped} card{} of the linked deck could not be found in the current card data and \
+            {} removed.",
+            if dropped > 1 { "s" } else { "" },
+            if dropped > 1 { "were" } else { "was" }
+        );
+    }
+    true
+}
+
+/// Install the main deck instance and the drawers as leptos context
 pub fn install_as_context() {
-    const KEY: &str = "deck";
+    const KEY: &str = "state";
+    // Deck saved before the drawers were saved with it
+    const LEGACY_DECK_KEY: &str = "deck";
+
     let storage = leptos::window().local_storage().ok().flatten();
-    let deck = storage
+    let first_visit = storage.as_ref().is_none_or(|storage| {
+        [KEY, LEGACY_DECK_KEY]
+            .into_iter()
+            .all(|key| storage.get_item(key).ok().flatten().is_none())
+    });
+    let (state, dropped) = storage
         .as_ref()
-        .and_then(|storage| storage.get_item(KEY).ok().flatten())
-        .as_deref()
-        .and_then(Deck::decode)
+        .and_then(|storage| {
+            if let Some(text) = storage.get_item(KEY).ok().flatten() {
+                return AppState::decode_skipping_unknown(&text);
+            }
+
+            let text = storage.get_item(LEGACY_DECK_KEY).ok().flatten()?;
+            let (deck, dropped) = Deck::decode_skipping_unknown(&text)?;
+            let state = AppState {
+                deck,
+                ..AppState::default()
+            };
+            Some((state, dropped))
+        })
         .unwrap_or_default();
-    let deck = RwSignal::new(deck);
+    if dropped > 0 {
+        print_error!(
+            "{dropped} card{} of the saved deck could not be found in the current card data and \
+            {} removed.",
+            if dropped > 1 { "s" } else { "" },
+            if dropped > 1 { "were" } else { "was" }
+        );
+    }
+    preload_images(&state.deck, &expect_context());
+    let deck = RwSignal::new(state.deck);
+    let imported = import_from_query(deck, &expect_context());
+    let drawers = crate::ui::drawers::install_as_context(state.drawers);
+    let part_groupings = RwSignal::new(state.part_groupings);
+    provide_context(part_groupings);
 
+    // Deck and drawers are saved together, so they can not get out of sync
     if let Some(storage) = storage {
         create_effect(move |_| {
-            let text = deck.with(TextEncoding::encode_string);
+            let drawers =
+                drawers.with(|drawers| drawers.iter().map(DrawerData::get).collect::<Vec<_>>());
+            let text =
+                deck.with(|deck| AppState::encode_parts(deck, &drawers, part_groupings.get()));
             if storage.set_item(KEY, &text).is_err() {
                 logging::error!("Saving deck failed");
+            } else {
+                let _ = storage.remove_item(LEGACY_DECK_KEY);
             }
         });
     }
 
     install_undo_redo_shortcuts(deck);
     provide_context(deck);
+    crate::ui::samples::install_as_context(first_visit && !imported);
+
+    // Legality errors, shared by the error list and the cards in the deck
+    let cards = expect_context::<CardData>();
+    let limits = install_copy_limits();
+    let errors = create_memo(move |_| {
+        limits.with(|limits| deck.with(|deck| validate(deck, &cards, limits)))
+    });
+    provide_context(errors);
+}
+
+/// Install the per-card copy limits as leptos context, saving changes.
+fn install_copy_limits() -> RwSignal<CopyLimits> {
+    const KEY: &str = "copy_limits";
+
+    let limits = RwSignal::new(settings::load_encoded::<CopyLimits>(KEY));
+    create_effect(move |_| settings::save(KEY, &limits.with(TextEncoding::encode_string)));
+
+    provide_context(limits);
+    limits
+}
+
+/// Inputs for the name, notes and other information of the deck.
+#[component]
+#[must_use]
+fn DeckMetaEditor() -> impl IntoView {
+    let cards = expect_context::<CardData>();
+    let deck = expect_context::<RwSignal<Deck>>();
+
+    let meta = move || deck.with(|deck| deck.meta().clone());
+    let update_meta = move |change: &dyn Fn(&mut DeckMeta)| {
+        deck.update(|deck| {
+            let mut meta = deck.meta().clone();
+            change(&mut meta);
+            deck.set_meta(meta);
+        });
+    };
+
+    let cover_options = move || {
+        let cover = deck.with(|deck| deck.meta().cover);
+        let mut entries =
+            deck.with(|deck| deck.entries().map(|entry| entry.id()).collect::<Vec<_>>());
+        entries.sort_unstable_by_key(|id| cards[*id].name);
+        entries
+            .into_iter()
+            .map(|id| {
+                let password = cards.password_for_id(id);
+                view! {
+                    <option value=password.to_string() selected=cover == Some(id)>
+                        {cards[id].name}
+                    </option>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let select_cover = move |ev: web_sys::Event| {
+        let cover = event_target_value(&ev)
+            .parse()
+            .ok()
+            .and_then(|password| cards.id_for_password(password));
+        update_meta(&|meta| meta.cover = cover);
+    };
+
+    view! {
+        <div class="deck-meta">
+            <input
+                type="text"
+                placeholder="Name"
+                prop:value=move || meta().name
+                on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    update_meta(&|meta| meta.name.clone_from(&value));
+                }
+            />
+            <input
+                type="text"
+                placeholder="Author"
+                prop:value=move || meta().author
+                on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    update_meta(&|meta| meta.author.clone_from(&value));
+                }
+            />
+            <input
+                type="text"
+                placeholder="Format"
+                prop:value=move || meta().format
+                on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    update_meta(&|meta| meta.format.clone_from(&value));
+                }
+            />
+            <select on:change=select_cover>
+                <option value="" selected=move || meta().cover.is_none()>
+                    "No Cover Card"
+                </option>
+                {cover_options}
+            </select>
+            <textarea
+                rows="4"
+                placeholder="Notes"
+                prop:value=move || meta().notes
+                on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    update_meta(&|meta| meta.notes.clone_from(&value));
+                }
+            ></textarea>
+        </div>
+    }
+}
+
+/// Text area to replace the deck with a list of passwords.
+#[component]
+#[must_use]
+fn PasswordListImport(show: RwSignal<bool>) -> impl IntoView {
+    let cards = expect_context::<CardData>();
+    let deck = expect_context::<RwSignal<Deck>>();
+
+    let textarea_ref = NodeRef::<html::Textarea>::new();
+    let import = move |_| {
+        let text = textarea_ref.get().unwrap().value();
+        let (new_deck, unknown) = password_list::load(&text, &cards);
+        preload_images(&new_deck, &cards);
+        deck.update(|deck| deck.set_entries(&new_deck));
+        show.set(false);
+
+        if !unknown.is_empty() {
+            print_error!(
+                "{} unknown password{} skipped:\n\n{}",
+                unknown.len(),
+                if unknown.len() > 1 { "s were" } else { " was" },
+                unknown.join("\n")
+            );
+        }
+    };
+
+    view! {
+        <div class="password-list">
+            <textarea
+                ref=textarea_ref
+                rows="8"
+                placeholder="One password per line, or separated by commas"
+            ></textarea>
+            <button on:click=import>"Import"</button>
+            <button on:click=move |_| show.set(false)>"Cancel"</button>
+        </div>
+    }
+}
+
+/// Skipped copies, forbidden cards and format notes of the last imported file.
+#[component]
+#[must_use]
+fn ImportNotes(summary: RwSignal<Option<ImportSummary>>) -> impl IntoView {
+    move || {
+        summary.get().map(|summary_data| {
+            let skipped = (!summary_data.skipped.is_empty()).then(|| {
+                let lines = summary_data
+                    .skipped
+                    .iter()
+                    .map(|line| view! { <li>{line.clone()}</li> })
+                    .collect_view();
+                view! {
+                    <p>"Skipped due to the card limits:"</p>
+                    <ul>{lines}</ul>
+                }
+            });
+            let forbidden = (!summary_data.forbidden.is_empty()).then(|| {
+                let names = summary_data
+                    .forbidden
+                    .iter()
+                    .map(|name| view! { <li>{*name}</li> })
+                    .collect_view();
+                view! {
+                    <p>"Forbidden cards:"</p>
+                    <ul>{names}</ul>
+                }
+            });
+            let warnings = (!summary_data.warnings.is_empty()).then(|| {
+                let notes = summary_data
+                    .warnings
+                    .iter()
+                    .map(|warning| view! { <li>{warning.to_string()}</li> })
+                    .collect_view();
+                view! {
+                    <p>"Notes on the file:"</p>
+                    <ul>{notes}</ul>
+                }
+            });
+
+            view! {
+                <div class="import-summary">
+                    <p>"Imported \"" {summary_data.name} "\""</p>
+                    {skipped}
+                    {forbidden}
+                    {warnings}
+                    <button on:click=move |_| summary.set(None)>"Dismiss"</button>
+                </div>
+            }
+        })
+    }
+}
+
+/// Selects for the settings of the user interface.
+#[component]
+#[must_use]
+fn Settings() -> impl IntoView {
+    let right_click = expect_context::<RwSignal<RightClick>>();
+    let tooltip_delay = expect_context::<RwSignal<TooltipDelay>>();
+    let engine_markers = expect_context::<RwSignal<EngineMarkers>>();
+    let grouping = expect_context::<RwSignal<Grouping>>();
+    let density = expect_context::<RwSignal<Density>>();
+    let theme = expect_context::<RwSignal<Theme>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    view! {
+        <SettingSelect setting=right_click />
+        <SettingSelect setting=tooltip_delay />
+        <SettingSelect setting=engine_markers />
+        <SettingSelect setting=grouping />
+        <SettingSelect setting=density />
+        <SettingSelect setting=theme />
+        <SettingSelect setting=locale />
+    }
 }
 
 #[component]
@@ -80,34 +622,109 @@ pub fn install_as_context() {
 pub fn Menu() -> impl IntoView {
     let cards = expect_context::<CardData>();
     let deck = expect_context::<RwSignal<Deck>>();
+    let limits = expect_context::<RwSignal<CopyLimits>>();
+
+    // Whether the next import is added to the current deck instead of replacing it
+    let merge = store_value(false);
+
+    // Issues of the last imported file
+    let notes = create_rw_signal(None);
 
     let input_ref = NodeRef::<html::Input>::new();
     let import = move |_| {
         let input = input_ref.get().unwrap();
         let files = input.files().unwrap(/* should only be null if type!=file */);
         if let Some(file) = files.get(0) {
-            spawn_local(async move {
-                let name = file.name();
-                match do_import(file.into(), &cards).await {
-                    Ok(new_deck) => deck.set(new_deck),
-                    Err(err) => print_error!("Error while importing \"{name}\":\n\n{err}"),
-                }
-            });
+            let merge = merge.get_value();
+            spawn_local(import_file(file.into(), merge, cards, deck, limits, notes));
         }
+        // Allow importing the same file again
+        input.set_value("");
+    };
+    let open_import = move |merge_import| {
+        merge.set_value(merge_import);
+        input_ref.get().unwrap().click();
     };
 
-    let export = move |_| match deck.with(|deck| do_export(deck, &cards)) {
-        Ok(()) => {}
-        Err(err) => print_error!("Error while exporting:\n\n{err}"),
+    let show_password_list = create_rw_signal(false);
+    let show_meta = create_rw_signal(false);
+
+    let copy_link = move |_| {
+        let link = deck.with_untracked(share_link);
+        spawn_local(async move {
+            if !write_clipboard(&link).await {
+                // Let the user copy the link by hand
+                let _ = gloo_dialogs::prompt("Link to the deck", Some(&link));
+            }
+        });
+    };
+
+    let new_deck = move |_| {
+        deck.update(|deck| {
+            deck.clear();
+            deck.set_meta(DeckMeta::default());
+        });
+    };
+
+    let locale = expect_context::<RwSignal<Locale>>();
+    let text = move |text: Text| move || text.localize(locale.get());
+
+    let export_buttons = ExportTarget::iter()
+        .map(|target| {
+            let export = move |_| {
+                if let Err(err) = deck.with_untracked(|deck| do_export(deck, &cards, target)) {
+                    print_error!("Error while exporting:\n\n{err}");
+                }
+            };
+            view! { <button on:click=export>{text(Text::ExportFor(target))}</button> }
+        })
+        .collect::<Vec<_>>();
+
+    let export_report = move |_| {
+        if let Err(err) = deck.with_untracked(|deck| do_export_report(deck, &cards)) {
+            print_error!("Error while exporting:\n\n{err}");
+        }
+    };
+    let export_image = move |_| {
+        let result = deck.with_untracked(|deck| do_export_image(deck, &cards, locale.get()));
+        if let Err(err) = result {
+            print_error!("Error while exporting:\n\n{err}");
+        }
     };
 
     view! {
         <div class="menu">
-            <button on:click=move |_| deck.set(Deck::default())>"New"</button>
-            <button on:click:undelegated=move |_| {
-                input_ref.get().unwrap().click();
-            }>"Import..."</button>
-            <button on:click=export>"Export..."</button>
+            <SampleDecks />
+            <button on:click=new_deck>{text(Text::New)}</button>
+            <button on:click:undelegated=move |_| open_import(false)>{text(Text::Import)}</button>
+            <button on:click:undelegated=move |_| open_import(true)>{text(Text::Merge)}</button>
+            <ImportNotes summary=notes />
+            <button on:click=move |_| show_password_list.update(|show| *show = !*show)>
+                {text(Text::ImportPasswords)}
+            </button>
+            <Show when=move || show_password_list.get()>
+                <PasswordListImport show=show_password_list />
+            </Show>
+            <button on:click=move |_| show_meta.update(|show| *show = !*show)>
+                {text(Text::DeckInfo)}
+            </button>
+            <Show when=move || show_meta.get()>
+                <DeckMetaEditor />
+            </Show>
+            {export_buttons}
+            <button on:click=copy_link>{text(Text::CopyLink)}</button>
+            <button on:click=export_report>{text(Text::ExportReport)}</button>
+            <button on:click=export_image>{text(Text::ExportImage)}</button>
+            <CollectionToggle />
+            <label>
+                <input
+                    type="checkbox"
+                    prop:checked=move || limits.with(|limits| limits.singleton)
+                    on:change=move |_| limits.update(|limits| limits.singleton = !limits.singleton)
+                />
+                "Singleton Format"
+            </label>
+            <Settings />
             <input type="file" accept=".ydk" ref=input_ref on:change=import style="display: none" />
         </div>
     }
@@ -1,28 +1,151 @@
 use std::rc::Rc;
 
-use common::{
-    card_data::CardData,
-    deck_part::{DeckPart, EntriesForPart},
-};
+use common::{card_data::CardData, deck_part::DeckPart, validation::CopyLimits};
 use leptos::{
-    component, create_memo, expect_context, view, For, IntoView, RwSignal, SignalGet, SignalUpdate,
-    SignalWith,
+    component, create_effect, create_memo, create_node_ref, create_rw_signal, ev,
+    event_target_value, expect_context, html, provide_context, view, window_event_listener, For,
+    IntoView, NodeRef, RwSignal, Signal, SignalGet, SignalSet, SignalUpdate, SignalWith,
+    SignalWithUntracked,
 };
+use web_sys::DragEvent;
 
 use crate::{
     deck::Deck,
-    deck_order::deck_order,
+    deck_order::{Grouping, PartGroupings},
+    i18n::Locale,
+    print_error,
     ui::{
         card_view::CardView,
-        drag_drop::{get_drag_info, get_dropped_card, set_drop_effect, DragInfo, DropEffect},
+        drag_drop::{
+            get_drag_info, get_dropped_card, get_dropped_copies, set_drop_effect, DragInfo,
+            DropEffect,
+        },
+        settings::{self, Setting},
     },
 };
 
+/// The deck part the user interacted with last, used as target for adding cards without an
+/// explicit part.
+#[derive(Debug, Clone, Copy)]
+pub struct ActivePart(pub RwSignal<DeckPart>);
+
+impl ActivePart {
+    const STORAGE_KEY: &'static str = "active_part";
+
+    /// Install the stored part as leptos context
+    pub fn install_as_context() {
+        let part = settings::load(Self::STORAGE_KEY)
+            .and_then(|value| DeckPart::iter().find(|part| part.to_string() == value))
+            .unwrap_or(DeckPart::Main);
+        let part = create_rw_signal(part);
+        create_effect(move |_| settings::save(Self::STORAGE_KEY, &part.get().to_string()));
+        provide_context(Self(part));
+    }
+}
+
+/// Number of copies to add when dropping a card.
+///
+/// Holding ctrl adds the copies remaining under the card's limit, holding shift adds the copies
+/// remaining under the `cap`, see [`CopyLimits`]. Otherwise the copies suggested by the drag source
+/// are added, like the ones staged in a drawer, or a single copy.
+fn drop_amount(ev: &DragEvent, limit: u8, cap: u8, current: u8) -> u8 {
+    if ev.shift_key() {
+        cap.saturating_sub(current)
+    } else if ev.ctrl_key() {
+        limit.saturating_sub(current)
+    } else {
+        get_dropped_copies(ev).map_or(1, |copies| copies.min(cap.saturating_sub(current)))
+    }
+}
+
+/// Digit which focuses a deck part together with `Alt`, see [`install_part_shortcuts`].
+fn shortcut_digit(part: DeckPart) -> u8 {
+    part as u8 + 1
+}
+
+/// Focus the Main, Extra or Side deck by pressing `Alt+1`, `Alt+2` or `Alt+3`, scrolling it into
+/// view and making it the active part.
+fn install_part_shortcuts(lists: [NodeRef<html::Div>; 3]) {
+    let active = expect_context::<ActivePart>().0;
+
+    window_event_listener(ev::keydown, move |ev| {
+        if !ev.alt_key() || ev.ctrl_key() || ev.meta_key() || ev.repeat() {
+            return;
+        }
+
+        // The key code does not depend on the characters Alt produces on some layouts
+        let code = ev.code();
+        let Some(part) = DeckPart::iter().find(|part| {
+            let digit = shortcut_digit(*part);
+            code == format!("Digit{digit}") || code == format!("Numpad{digit}")
+        }) else {
+            return;
+        };
+
+        active.set(part);
+        if let Some(list) = lists[part as usize].get_untracked() {
+            list.scroll_into_view();
+            let _ = list.focus();
+        }
+        ev.prevent_default();
+    });
+}
+
+/// Name, grouping and size of a deck part.
 #[component]
-fn PartView(part: DeckPart) -> impl IntoView {
+fn PartHeader(part: DeckPart) -> impl IntoView {
     let deck = expect_context::<RwSignal<Deck>>();
     let cards = expect_context::<CardData>();
 
+    let part_groupings = expect_context::<RwSignal<PartGroupings>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+    let part_grouping = move || part_groupings.get().get(part);
+    let select_grouping = move |ev: web_sys::Event| {
+        let value = event_target_value(&ev);
+        let new_grouping = Grouping::iter().find(|grouping| grouping.key() == value);
+        part_groupings.update(|groupings| groupings.set(part, new_grouping));
+    };
+    let grouping_options = Grouping::iter()
+        .map(|option| {
+            view! {
+                <option value=option.key() selected=move || part_grouping() == Some(option)>
+                    {move || option.name().localize(locale.get())}
+                </option>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <h2>{part.to_string()}</h2>
+        <div class="part-size">
+            <select class="part-grouping" aria-label="Grouping" on:change=select_grouping>
+                <option value="" selected=move || part_grouping().is_none()>
+                    "Menu grouping"
+                </option>
+                {grouping_options}
+            </select>
+            <span class="current">
+                {move || {
+                    deck.with(|deck| {
+                        deck.entries_for_part(part, &cards).map(|(_, count)| count).sum::<u8>()
+                    })
+                }}
+
+            </span>
+            <span class="divider">" / "</span>
+            <span class="max">{part.max()}</span>
+        </div>
+    }
+}
+
+#[component]
+fn PartView(part: DeckPart, node_ref: NodeRef<html::Div>) -> impl IntoView {
+    let deck = expect_context::<RwSignal<Deck>>();
+    let cards = expect_context::<CardData>();
+    let limits = expect_context::<RwSignal<CopyLimits>>();
+    let active = expect_context::<ActivePart>().0;
+
+    // Locked copies are kept, see `Deck::decrement`
     let delete = move |delete_id| {
         deck.update(|deck| {
             deck.decrement(delete_id, part.into(), 1);
@@ -30,11 +153,16 @@ fn PartView(part: DeckPart) -> impl IntoView {
     };
     let delete = Rc::new(delete);
 
-    let drag_over = move |ev| {
+    let drag_over = move |ev: DragEvent| {
         let drag_info = get_drag_info(&ev);
 
         let ok = match part {
-            DeckPart::Main => matches!(drag_info, DragInfo::MainCard),
+            // Holding alt overrides the card classification, see `Deck::set_main_override`
+            DeckPart::Main => match drag_info {
+                DragInfo::MainCard => true,
+                DragInfo::ExtraCard => ev.alt_key(),
+                DragInfo::NotCard => false,
+            },
             DeckPart::Extra => matches!(drag_info, DragInfo::ExtraCard),
             DeckPart::Side => matches!(drag_info, DragInfo::MainCard | DragInfo::ExtraCard),
         };
@@ -45,36 +173,53 @@ fn PartView(part: DeckPart) -> impl IntoView {
         }
     };
 
+    let menu_grouping = expect_context::<RwSignal<Grouping>>();
+    let part_groupings = expect_context::<RwSignal<PartGroupings>>();
+    let grouping = move || {
+        part_groupings
+            .get()
+            .get(part)
+            .unwrap_or_else(|| menu_grouping.get())
+    };
+
     let entries = create_memo(move |_| {
-        let mut result =
-            deck.with(|deck| deck.entries().for_part(part, &cards).collect::<Vec<_>>());
-        result.sort_unstable_by(move |(lhs, _), (rhs, _)| deck_order(&cards[*lhs], &cards[*rhs]));
+        let mut result = deck.with(|deck| deck.entries_for_part(part, &cards).collect::<Vec<_>>());
+        let grouping = grouping();
+        result
+            .sort_unstable_by(move |(lhs, _), (rhs, _)| grouping.order(&cards[*lhs], &cards[*rhs]));
         result
     });
 
     view! {
-        <h2>{part.to_string()}</h2>
-        <div class="part-size">
-            <span class="current">
-                {move || {
-                    deck.with(|deck| {
-                        deck.entries().for_part(part, &cards).map(|(_, count)| count).sum::<u8>()
-                    })
-                }}
-
-            </span>
-            <span class="divider">" / "</span>
-            <span class="max">{part.max()}</span>
-        </div>
+        <PartHeader part=part />
         <div
             class="card-list"
+            role="group"
+            aria-label=format!("{part} deck")
+            aria-keyshortcuts=format!("Alt+{}", shortcut_digit(part))
+            tabindex="-1"
+            ref=node_ref
+            class:active=move || active.get() == part
+            on:pointerdown=move |_| active.set(part)
             on:dragenter=drag_over
             on:dragover=drag_over
             on:drop=move |ev| {
+                active.set(part);
                 let id = get_dropped_card(&ev, &cards);
+                let (limit, cap) = limits
+                    .with_untracked(|limits| (limits.allowed(id, &cards), limits.cap(id, &cards)));
+                let mut result = Ok(());
                 deck.update(|deck| {
-                    deck.increment(id, part.into(), 1);
+                    let amount = drop_amount(&ev, limit, cap, deck.total_count(id));
+                    if amount == 0 {
+                        return;
+                    }
+
+                    result = deck.increment_in_part(id, part, amount, cap, &cards);
                 });
+                if let Err(err) = result {
+                    print_error!("Could not add \"{}\": {err}", cards[id].name);
+                }
             }
         >
 
@@ -83,7 +228,20 @@ fn PartView(part: DeckPart) -> impl IntoView {
                 key=|el| *el
                 children=move |(id, count)| {
                     let delete = delete.clone();
-                    view! { <CardView id=id count=count on_delete=delete /> }
+                    let total = Signal::derive(move || deck.with(|deck| deck.total_count(id)));
+                    let locked = Signal::derive(move || {
+                        deck.with(|deck| deck.locked_count(id) > 0)
+                    });
+                    view! {
+                        <CardView
+                            id=id
+                            count=count
+                            total=total
+                            locked=locked
+                            on_delete=delete
+                            show_engine=true
+                        />
+                    }
                 }
             />
 
@@ -94,11 +252,14 @@ fn PartView(part: DeckPart) -> impl IntoView {
 #[component]
 #[must_use]
 pub fn DeckView() -> impl IntoView {
+    let lists = [create_node_ref(), create_node_ref(), create_node_ref()];
+    install_part_shortcuts(lists);
+
     view! {
         <div class="deck-view">
-            <PartView part=DeckPart::Main />
-            <PartView part=DeckPart::Extra />
-            <PartView part=DeckPart::Side />
+            <PartView part=DeckPart::Main node_ref=lists[DeckPart::Main as usize] />
+            <PartView part=DeckPart::Extra node_ref=lists[DeckPart::Extra as usize] />
+            <PartView part=DeckPart::Side node_ref=lists[DeckPart::Side as usize] />
         </div>
     }
 }
@@ -1,12 +1,17 @@
 use common::{
-    card::Card,
+    card::{Card, CardPassword},
     card_data::{CardData, Id},
 };
 use wasm_bindgen::intern;
 use web_sys::{js_sys::JsString, DataTransfer, DragEvent};
 
+use crate::links;
+
 const CARD_PASSWORD_TYPE: &str = "card_password";
 const CARD_IS_EXTRA: &str = "card_is_extra";
+const CARD_COPIES_TYPE: &str = "card_copies";
+/// Prefix of a marker containing the password, as only the types can be read during dragover
+const CARD_PASSWORD_MARKER: &str = "card_password_";
 
 fn data_transfer(ev: &DragEvent) -> DataTransfer {
     ev.data_transfer().expect("data transfer not available")
@@ -22,19 +27,27 @@ pub fn start_drag(ev: &DragEvent, card: &Card) {
     let transfer = data_transfer(ev);
 
     set_data(&transfer, CARD_PASSWORD_TYPE, &card.password.to_string());
+    set_data(
+        &transfer,
+        &format!("{CARD_PASSWORD_MARKER}{}", card.password),
+        "",
+    );
     if card.card_type.is_extra_deck_monster() {
         // Marker for dragover, so content does not matter
         set_data(&transfer, CARD_IS_EXTRA, "");
     }
 
-    set_data(
-        &transfer,
-        "text/uri-list",
-        &format!("https://yugipedia.com/wiki/{}", card.name),
-    );
+    set_data(&transfer, "text/uri-list", &links::yugipedia(card));
     set_data(&transfer, "text/plain", card.name);
 }
 
+/// Suggest a number of copies to add on drop, e.g. for cards staged in a drawer.
+///
+/// Must be called after [`start_drag`].
+pub fn set_drag_copies(ev: &DragEvent, copies: u8) {
+    set_data(&data_transfer(ev), CARD_COPIES_TYPE, &copies.to_string());
+}
+
 pub enum DragInfo {
     NotCard,
     MainCard,
@@ -60,6 +73,18 @@ pub fn get_drag_info(ev: &DragEvent) -> DragInfo {
     }
 }
 
+/// Get the password of the dragged card during `dragenter` and `dragover`.
+#[must_use]
+pub fn get_dragged_password(ev: &DragEvent) -> Option<CardPassword> {
+    data_transfer(ev).types().iter().find_map(|format| {
+        format
+            .as_string()?
+            .strip_prefix(CARD_PASSWORD_MARKER)?
+            .parse()
+            .ok()
+    })
+}
+
 /// Get the password of the dropped card.
 ///
 /// Only available in the `drop` event.
@@ -75,6 +100,18 @@ pub fn get_dropped_card(ev: &DragEvent, cards: &CardData) -> Id {
         .expect("unknown card password")
 }
 
+/// Get the number of copies suggested by [`set_drag_copies`].
+///
+/// Only available in the `drop` event.
+#[must_use]
+pub fn get_dropped_copies(ev: &DragEvent) -> Option<u8> {
+    data_transfer(ev)
+        .get_data(CARD_COPIES_TYPE)
+        .ok()?
+        .parse()
+        .ok()
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum DropEffect {
     None,
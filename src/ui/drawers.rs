@@ -1,79 +1,158 @@
 use std::rc::Rc;
 
-use common::card_data::{CardData, Id};
+use common::{
+    card_data::{CardData, Id},
+    deck::MAX_COPIES,
+};
 use leptos::{
-    component, create_rw_signal, create_signal, expect_context, view, For, IntoView, RwSignal,
-    SignalGet, SignalUpdate, SignalWith, WriteSignal,
+    component, create_rw_signal, event_target_value, expect_context, provide_context, view, For,
+    IntoView, RwSignal, Signal, SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, SignalWith,
+    SignalWithUntracked,
 };
 
 use crate::{
+    app_state::Drawer,
+    deck::Deck,
     deck_order::deck_order,
     ui::{
         card_view::CardView,
-        drag_drop::{get_drag_info, get_dropped_card, set_drop_effect, DragInfo, DropEffect},
+        drag_drop::{
+            get_drag_info, get_dragged_password, get_dropped_card, set_drop_effect, DragInfo,
+            DropEffect,
+        },
     },
 };
 
 #[derive(Debug, Clone, Copy)]
-struct DrawerData {
+pub struct DrawerData {
     id: usize,
     name: RwSignal<String>,
-    content: RwSignal<Vec<Id>>,
+    content: RwSignal<Vec<(Id, u8)>>,
+}
+
+impl DrawerData {
+    fn new(id: usize, drawer: Drawer) -> Self {
+        Self {
+            id,
+            name: create_rw_signal(drawer.name),
+            content: create_rw_signal(drawer.content),
+        }
+    }
+
+    /// Current content of the drawer, tracking the signals.
+    #[must_use]
+    pub fn get(&self) -> Drawer {
+        Drawer::new(&self.name.get(), self.content.get())
+    }
+}
+
+/// Install the drawers as leptos context.
+#[must_use]
+pub fn install_as_context(drawers: Vec<Drawer>) -> RwSignal<Vec<DrawerData>> {
+    let drawers = create_rw_signal(
+        drawers
+            .into_iter()
+            .enumerate()
+            .map(|(id, drawer)| DrawerData::new(id, drawer))
+            .collect(),
+    );
+    provide_context(drawers);
+    drawers
 }
 
 #[component]
-fn Drawer(data: DrawerData, set_drawers: WriteSignal<Vec<DrawerData>>) -> impl IntoView {
+fn Drawer(data: DrawerData) -> impl IntoView {
+    let drawers = expect_context::<RwSignal<Vec<DrawerData>>>();
     let close = move || {
-        set_drawers.update(|drawers| drawers.retain(|drawer| drawer.id != data.id));
+        drawers.update(|drawers| drawers.retain(|drawer| drawer.id != data.id));
     };
 
     let cards = expect_context::<CardData>();
+    let deck = expect_context::<RwSignal<Deck>>();
+    let count = move |id| {
+        data.content.with_untracked(|content| {
+            content
+                .iter()
+                .find(|(entry_id, _)| *entry_id == id)
+                .map_or(0, |(_, count)| *count)
+        })
+    };
     let push = move |id| {
         data.content.update(|content| {
-            if let Err(pos) =
-                content.binary_search_by(|probe| deck_order(&cards[*probe], &cards[id]))
-            {
-                content.insert(pos, id);
+            if let Some((_, count)) = content.iter_mut().find(|(entry_id, _)| *entry_id == id) {
+                *count = (*count + 1).min(MAX_COPIES);
+            } else {
+                let pos = content
+                    .binary_search_by(|(probe, _)| deck_order(&cards[*probe], &cards[id]))
+                    .unwrap_or_else(|pos| pos);
+                content.insert(pos, (id, 1));
             }
         });
     };
 
+    // Removes a single copy, like in the deck
     let delete = move |delete_id| {
-        data.content
-            .update(|content| content.retain(|id| *id != delete_id));
+        data.content.update(|content| {
+            if let Some(pos) = content.iter().position(|(id, _)| *id == delete_id) {
+                content[pos].1 -= 1;
+                if content[pos].1 == 0 {
+                    content.remove(pos);
+                }
+            }
+        });
     };
     let delete: Rc<dyn Fn(Id)> = Rc::new(delete);
 
     let drag_over = move |ev| {
-        if !matches!(get_drag_info(&ev), DragInfo::NotCard) {
-            set_drop_effect(&ev, DropEffect::Copy);
-            ev.prevent_default();
+        if matches!(get_drag_info(&ev), DragInfo::NotCard) {
+            return;
         }
+
+        // Dropping a card of which the drawer already holds the maximum of copies does nothing
+        let full = get_dragged_password(&ev)
+            .and_then(|password| cards.id_for_password(password))
+            .is_some_and(|id| count(id) >= MAX_COPIES);
+        let effect = if full {
+            DropEffect::None
+        } else {
+            DropEffect::Copy
+        };
+        set_drop_effect(&ev, effect);
+        ev.prevent_default();
     };
 
-    // TODO: propagate input updates back to name signal
     view! {
         <div class="drawer">
-            <input type="text" value=data.name />
+            <input
+                type="text"
+                value=data.name.get_untracked()
+                on:change=move |ev| data.name.set(event_target_value(&ev))
+            />
             <button on:click=move |_| close()>"X"</button>
             <div
                 class="card-list"
+                role="group"
+                aria-label=move || data.name.get()
                 on:dragenter=drag_over
                 on:dragover=drag_over
-                on:drop=move |ev| {
-                    let id = get_dropped_card(&ev, &cards);
-                    if !data.content.with(|content| content.contains(&id)) {
-                        push(id);
-                    }
-                }
+                on:drop=move |ev| push(get_dropped_card(&ev, &cards))
             >
 
                 <For
                     each=move || data.content.get()
-                    key=|id| *id
-                    children=move |id| {
+                    key=|entry| *entry
+                    children=move |(id, count)| {
                         let delete = delete.clone();
-                        view! { <CardView id=id on_delete=delete /> }
+                        let total = Signal::derive(move || deck.with(|deck| deck.total_count(id)));
+                        view! {
+                            <CardView
+                                id=id
+                                count=count
+                                total=total
+                                on_delete=delete
+                                drag_copies=count
+                            />
+                        }
                     }
                 />
 
@@ -85,18 +164,13 @@ fn Drawer(data: DrawerData, set_drawers: WriteSignal<Vec<DrawerData>>) -> impl I
 #[component]
 #[must_use]
 pub fn Drawers() -> impl IntoView {
-    let (next_drawer_id, set_next_drawer_id) = create_signal(0);
-    let (drawers, set_drawers) = create_signal(Vec::new());
+    let drawers = expect_context::<RwSignal<Vec<DrawerData>>>();
 
     let new_drawer = move || {
-        set_drawers.update(|drawers| {
-            drawers.push(DrawerData {
-                id: next_drawer_id.get(),
-                name: create_rw_signal("New Drawer".to_owned()),
-                content: create_rw_signal(Vec::new()),
-            });
+        drawers.update(|drawers| {
+            let id = drawers.iter().map(|data| data.id + 1).max().unwrap_or(0);
+            drawers.push(DrawerData::new(id, Drawer::new("New Drawer", Vec::new())));
         });
-        set_next_drawer_id.update(|id| *id += 1);
     };
 
     view! {
@@ -105,7 +179,7 @@ pub fn Drawers() -> impl IntoView {
                 each=move || drawers.get()
                 key=|data| data.id
                 children=move |data| {
-                    view! { <Drawer data=data set_drawers=set_drawers /> }
+                    view! { <Drawer data=data /> }
                 }
             />
 
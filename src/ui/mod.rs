@@ -1,8 +1,11 @@
 pub mod app;
 pub mod card_search;
 pub mod card_view;
+pub mod collection;
 pub mod deck;
 pub mod deck_view;
 pub mod drag_drop;
 pub mod drawers;
+pub mod samples;
+pub mod settings;
 pub mod tools;
@@ -0,0 +1,76 @@
+use common::{card_data::CardData, ydk};
+use leptos::{
+    component, expect_context, provide_context, store_value, view, IntoView, RwSignal, Show,
+    SignalGet, SignalSet, SignalUpdate,
+};
+use web_sys::js_sys;
+
+use crate::{deck::Deck, ui::deck::preload_images};
+
+/// Sample decks offered to new users, by name
+const SAMPLES: &[(&str, &str)] = &[
+    ("Dark Magician", include_str!("samples/dark_magician.ydk")),
+    ("Blue-Eyes", include_str!("samples/blue_eyes.ydk")),
+];
+
+/// Whether the sample decks are offered, which is only the case on the first visit.
+#[derive(Debug, Clone, Copy)]
+struct ShowSamples(RwSignal<bool>);
+
+/// Install the sample deck prompt as leptos context
+pub fn install_as_context(first_visit: bool) {
+    provide_context(ShowSamples(RwSignal::new(first_visit)));
+}
+
+/// One-time prompt to load a sample deck, so new users can explore the tools right away.
+#[component]
+#[must_use]
+pub fn SampleDecks() -> impl IntoView {
+    let cards = expect_context::<CardData>();
+    let deck = expect_context::<RwSignal<Deck>>();
+    let ShowSamples(show) = expect_context();
+
+    // Samples with cards missing from the current card data are not offered
+    let samples = SAMPLES
+        .iter()
+        .filter_map(|&(name, text)| Some((name, ydk::load(text, &cards).ok()?)))
+        .collect::<Vec<_>>();
+    if samples.is_empty() {
+        show.set(false);
+    }
+
+    let count = samples.len();
+    let samples = store_value(samples);
+    let load = move |idx: usize| {
+        samples.with_value(|samples| {
+            preload_images(&samples[idx].1, &cards);
+            deck.update(|deck| deck.set_entries(&samples[idx].1));
+        });
+        show.set(false);
+    };
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )] // random is in [0, 1)
+    let load_random = move |_| load((js_sys::Math::random() * count as f64) as usize);
+
+    let buttons = samples.with_value(|samples| {
+        samples
+            .iter()
+            .enumerate()
+            .map(|(idx, (name, _))| view! { <button on:click=move |_| load(idx)>{*name}</button> })
+            .collect::<Vec<_>>()
+    });
+
+    view! {
+        <Show when=move || show.get()>
+            <div class="samples">
+                <span>"New here? Start with a sample deck:"</span>
+                {buttons.clone()}
+                <button on:click=load_random>"Random"</button>
+                <button on:click=move |_| show.set(false)>"Start Empty"</button>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,112 @@
+//! Settings persisted in the local storage of the browser.
+
+use leptos::{
+    component, create_effect, event_target_value, expect_context, logging, provide_context, view,
+    Callable, Callback, IntoView, RwSignal, SignalGet, SignalSet,
+};
+
+use crate::{
+    i18n::{Locale, Text},
+    text_encoding::TextEncoding,
+};
+
+/// Value stored under `key`, if any.
+#[must_use]
+pub fn load(key: &str) -> Option<String> {
+    let storage = leptos::window().local_storage().ok().flatten()?;
+    storage.get_item(key).ok().flatten()
+}
+
+/// Store `value` under `key`, logging failures like an exceeded quota.
+pub fn save(key: &str, value: &str) {
+    let Some(storage) = leptos::window().local_storage().ok().flatten() else {
+        return;
+    };
+    if storage.set_item(key, value).is_err() {
+        logging::error!("Saving {key} failed");
+    }
+}
+
+/// Value stored under `key`, or the default if there is none or it can not be decoded.
+#[must_use]
+pub fn load_encoded<T: TextEncoding + Default>(key: &str) -> T {
+    load(key).as_deref().and_then(T::decode).unwrap_or_default()
+}
+
+/// A choice between a fixed set of options.
+pub trait Setting: Copy + Default + PartialEq + 'static {
+    /// Local storage key of the setting.
+    const STORAGE_KEY: &'static str;
+
+    /// All options, in the order they are offered.
+    fn iter() -> impl Iterator<Item = Self>;
+
+    /// Identifier of the option in the local storage, which must not change.
+    fn key(self) -> &'static str;
+
+    /// Label of the option in the user interface.
+    fn name(self) -> Text;
+
+    /// Option used while none is stored.
+    #[must_use]
+    fn initial() -> Self {
+        Self::default()
+    }
+
+    /// The stored option, or the initial one.
+    #[must_use]
+    fn load() -> Self {
+        load(Self::STORAGE_KEY)
+            .and_then(|value| Self::iter().find(|option| option.key() == value))
+            .unwrap_or_else(Self::initial)
+    }
+
+    fn save(self) {
+        save(Self::STORAGE_KEY, self.key());
+    }
+
+    /// Signal of the stored option, saving changes.
+    #[must_use]
+    fn stored() -> RwSignal<Self> {
+        let setting = RwSignal::new(Self::load());
+        create_effect(move |_| setting.get().save());
+        setting
+    }
+
+    /// Install the stored option as leptos context, saving changes.
+    fn install_as_context() {
+        provide_context(Self::stored());
+    }
+}
+
+/// Select between the options of a setting.
+#[component]
+#[must_use]
+pub fn SettingSelect<T: Setting>(
+    setting: RwSignal<T>,
+    /// Called with the newly selected option
+    #[prop(optional)]
+    on_change: Option<Callback<T>>,
+) -> impl IntoView {
+    let locale = expect_context::<RwSignal<Locale>>();
+    let select = move |ev: web_sys::Event| {
+        let value = event_target_value(&ev);
+        if let Some(option) = T::iter().find(|option| option.key() == value) {
+            setting.set(option);
+            if let Some(on_change) = on_change {
+                on_change.call(option);
+            }
+        }
+    };
+    let options = T::iter()
+        .map(|option| {
+            view! {
+                <option value=option.key() selected=move || setting.get() == option>
+                    {move || option.name().localize(locale.get())}
+                </option>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! { <select on:change=select>{options}</select> }
+}
@@ -0,0 +1,77 @@
+use bincode::Options;
+use common::transfer::{self, Metadata};
+use gloo_net::http::Request;
+use leptos::{create_local_resource, view, IntoView, Signal, SignalGet, View};
+use wasm_bindgen::JsValue;
+use web_sys::js_sys;
+
+use crate::{deck::Deck, i18n::Text};
+
+use super::{Tool, ToolHeading};
+
+/// Shows how recent the card data is, so users can tell whether new cards are available yet.
+pub struct DataInfo;
+
+/// Metadata written by the data processor next to the card data, if available.
+async fn load_metadata() -> Option<Metadata> {
+    let response = Request::get(transfer::METADATA_FILENAME)
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    let bytes = response.binary().await.ok()?;
+    transfer::bincode_options().deserialize(&bytes).ok()
+}
+
+/// Date of a Unix timestamp in ISO format.
+fn format_date(timestamp: u64) -> String {
+    #[allow(clippy::cast_precision_loss)] // timestamps are far below 2^53
+    let millis = timestamp as f64 * 1000.0;
+    let date = String::from(js_sys::Date::new(&JsValue::from_f64(millis)).to_iso_string());
+    date[..10].to_owned()
+}
+
+impl Tool for DataInfo {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "data_info"
+    }
+
+    fn name(&self) -> Text {
+        Text::CardData
+    }
+
+    fn view(&self, _deck: Signal<Deck>) -> View {
+        let metadata = create_local_resource(|| (), |()| load_metadata());
+
+        let info = move || match metadata.get() {
+            None => view! { <p>"Loading..."</p> }.into_view(),
+            Some(None) => view! { <p>"No information available"</p> }.into_view(),
+            Some(Some(metadata)) => view! {
+                <dl>
+                    <dt>"Cards"</dt>
+                    <dd>{metadata.card_count}</dd>
+                    <dt>"Database version"</dt>
+                    <dd>{metadata.database_version}</dd>
+                    <dt>"Updated"</dt>
+                    <dd>{format_date(metadata.build_timestamp)}</dd>
+                </dl>
+            }
+            .into_view(),
+        };
+
+        view! {
+            <div>
+                <ToolHeading text=Text::CardData />
+                {info}
+            </div>
+        }
+        .into_view()
+    }
+}
@@ -0,0 +1,167 @@
+//! SVG image of the deck list, for sharing the deck where YDK files are not supported
+
+use std::fmt::{self, Write};
+
+use common::{card_data::CardData, deck::Deck, deck_part::DeckPart};
+
+use crate::{deck_order::deck_order, ui::card_view::get_class};
+
+const WIDTH: u32 = 480;
+const MARGIN: u32 = 16;
+const LINE_HEIGHT: u32 = 20;
+const HEADER_HEIGHT: u32 = 40;
+
+/// Card colors, matching the ones of the app stylesheet
+const STYLE: &str = "\
+.card { fill: var(--primary-color); stroke: black; }
+.monster { --primary-color: #bdaf7e; }
+.monster.effect { --primary-color: #ac6c46; }
+.monster.ritual { --primary-color: #206ab3; }
+.monster.fusion { --primary-color: #5d3c80; }
+.monster.synchro { --primary-color: #acacae; }
+.monster.xyz { --primary-color: #1e2628; }
+.monster.link { --primary-color: #429dbb; }
+.spell { --primary-color: #208f86; }
+.trap { --primary-color: #a43278; }
+.title { font-size: 20px; font-weight: bold; }
+.part { font-weight: bold; }";
+
+/// Optional header of the image.
+#[derive(Debug, Clone, Copy)]
+pub struct Header<'a> {
+    pub title: &'a str,
+    /// Date of the export, already formatted
+    pub date: &'a str,
+}
+
+/// Escape text for use in XML content.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn write_image(
+    deck: &Deck,
+    cards: &CardData,
+    header: Option<Header>,
+    writer: &mut impl Write,
+) -> fmt::Result {
+    let parts = DeckPart::iter()
+        .map(|part| {
+            let mut entries = deck.entries_for_part(part, cards).collect::<Vec<_>>();
+            entries.sort_unstable_by(|(lhs, _), (rhs, _)| deck_order(&cards[*lhs], &cards[*rhs]));
+            (part, entries)
+        })
+        .filter(|(_, entries)| !entries.is_empty())
+        .collect::<Vec<_>>();
+
+    let lines = parts
+        .iter()
+        .map(|(_, entries)| u32::try_from(entries.len() + 1).unwrap())
+        .sum::<u32>();
+    let header_height = if header.is_some() { HEADER_HEIGHT } else { 0 };
+    let height = 2 * MARGIN + header_height + lines * LINE_HEIGHT;
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" font-family="sans-serif" font-size="14">"#
+    )?;
+    writeln!(writer, "<style>\n{STYLE}\n</style>")?;
+    writeln!(
+        writer,
+        r#"<rect width="100%" height="100%" fill="white" />"#
+    )?;
+
+    if let Some(header) = header {
+        writeln!(
+            writer,
+            r#"<text class="title" x="{MARGIN}" y="{}">{}</text>"#,
+            MARGIN + 20,
+            escape(header.title)
+        )?;
+        writeln!(
+            writer,
+            r#"<text x="{}" y="{}" text-anchor="end">{}</text>"#,
+            WIDTH - MARGIN,
+            MARGIN + 20,
+            escape(header.date)
+        )?;
+    }
+
+    let mut y = MARGIN + header_height;
+    for (part, entries) in parts {
+        let size = entries
+            .iter()
+            .map(|(_, count)| u32::from(*count))
+            .sum::<u32>();
+        y += LINE_HEIGHT;
+        writeln!(
+            writer,
+            r#"<text class="part" x="{MARGIN}" y="{y}">{part} Deck ({size})</text>"#
+        )?;
+
+        for (id, count) in entries {
+            let card = &cards[id];
+            y += LINE_HEIGHT;
+            writeln!(
+                writer,
+                r#"<rect class="{}" x="{MARGIN}" y="{}" width="12" height="12" />"#,
+                get_class(&card.card_type),
+                y - 11
+            )?;
+            writeln!(
+                writer,
+                r#"<text x="{}" y="{y}">{count}x {}</text>"#,
+                MARGIN + 20,
+                escape(card.name)
+            )?;
+        }
+    }
+
+    writeln!(writer, "</svg>")
+}
+
+/// Generate an SVG image listing the cards of each deck part.
+#[must_use]
+pub fn deck_image(deck: &Deck, cards: &CardData, header: Option<Header>) -> String {
+    let mut result = String::new();
+    write_image(deck, cards, header, &mut result).unwrap(/* Write for String should never fail */);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        card::test_util::make_card,
+        card_data::{CardDataStorage, Id},
+        deck::PartType,
+    };
+
+    use super::*;
+
+    #[test]
+    fn image_header() {
+        let mut card = make_card(1234);
+        card.name = "Pot & Jar".to_owned();
+        let cards = CardData::from(CardDataStorage::new(vec![card], vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 3);
+
+        let header = Header {
+            title: "<Test>",
+            date: "2024-06-01",
+        };
+        let image = deck_image(&deck, &cards, Some(header));
+        assert!(image.contains(">&lt;Test&gt;</text>"));
+        assert!(image.contains(">2024-06-01</text>"));
+        assert!(image.contains(">Main Deck (3)</text>"));
+        assert!(image.contains(">3x Pot &amp; Jar</text>"));
+        assert!(!image.contains("Side Deck"));
+
+        let image = deck_image(&deck, &cards, None);
+        assert!(!image.contains("class=\"title\""));
+        assert!(image.contains(">3x Pot &amp; Jar</text>"));
+    }
+}
@@ -1,11 +1,15 @@
-use common::{card_data::CardData, deck::PartType, deck_part::DeckPart};
+use common::validation::DeckError;
 use leptos::{
-    expect_context, html, view, For, IntoView, Memo, Show, Signal, SignalGet, SignalWith, View,
+    expect_context, html, view, For, IntoView, Memo, RwSignal, Show, Signal, SignalGet, SignalWith,
+    View,
 };
 
-use crate::deck::Deck;
+use crate::{
+    deck::Deck,
+    i18n::{Locale, Text},
+};
 
-use super::Tool;
+use super::{Tool, ToolHeading};
 
 pub struct ErrorList;
 
@@ -14,66 +18,56 @@ impl Tool for ErrorList {
         Self
     }
 
-    fn view(&self, deck: Signal<Deck>) -> View {
-        let cards = expect_context::<CardData>();
-
-        let errors = Memo::new(move |_| {
-            let mut totals = [0; 3];
-            let mut limit_exceeded = 0;
-
-            deck.with(|deck| {
-                for entry in deck.entries() {
-                    let card = &cards[entry.id()];
-                    let playing = entry.count(PartType::Playing);
-                    let side = entry.count(PartType::Side);
-
-                    let playing_part = if card.card_type.is_extra_deck_monster() {
-                        DeckPart::Extra
-                    } else {
-                        DeckPart::Main
-                    };
-
-                    totals[playing_part as usize] += playing;
-                    totals[DeckPart::Side as usize] += side;
+    fn key(&self) -> &'static str {
+        "errors"
+    }
 
-                    if playing + side > card.limit.count() {
-                        limit_exceeded += 1;
-                    }
-                }
-            });
+    fn name(&self) -> Text {
+        Text::Errors
+    }
 
-            let mut errors = vec![];
+    fn view(&self, _: Signal<Deck>) -> View {
+        let deck_errors = expect_context::<Memo<Vec<DeckError>>>();
+        let locale = expect_context::<RwSignal<Locale>>();
 
+        let errors = Memo::new(move |_| {
+            let errors = deck_errors.get();
+            let count = |predicate: fn(&DeckError) -> bool| {
+                errors.iter().filter(|error| predicate(error)).count()
+            };
+            let locale = locale.get();
+            let mut messages = vec![];
+
+            let limit_exceeded = count(|error| matches!(error, DeckError::OverLimit { .. }));
             if limit_exceeded > 0 {
-                errors.push(format!(
-                    "Too many copies of {limit_exceeded} card{}",
-                    if limit_exceeded > 1 { "s" } else { "" }
-                ));
+                messages.push(Text::TooManyCopies(limit_exceeded).localize(locale));
             }
 
-            for part in DeckPart::iter() {
-                let len = totals[part as usize];
+            let main_overrides =
+                count(|error| matches!(error, DeckError::IllegalPartPlacement { .. }));
+            if main_overrides > 0 {
+                messages.push(Text::MainOverrides(main_overrides).localize(locale));
+            }
 
-                if len < part.min() {
-                    errors.push(format!(
-                        "{part} deck contains less than {} cards",
-                        part.min(),
-                    ));
-                } else if len > part.max() {
-                    errors.push(format!(
-                        "{part} deck contains more than {} cards",
-                        part.max(),
-                    ));
+            for error in &errors {
+                match *error {
+                    DeckError::PartTooSmall { part, min, .. } => {
+                        messages.push(Text::PartTooSmall(part, min).localize(locale));
+                    }
+                    DeckError::PartTooLarge { part, max, .. } => {
+                        messages.push(Text::PartTooLarge(part, max).localize(locale));
+                    }
+                    DeckError::OverLimit { .. } | DeckError::IllegalPartPlacement { .. } => {}
                 }
             }
 
-            errors
+            messages
         });
 
         view! {
             <Show when=move || !errors.with(Vec::is_empty)>
                 <div>
-                    <h3>"Errors"</h3>
+                    <ToolHeading text=Text::Errors />
                     <ul class="errors">
                         <For
                             each=move || errors.get()
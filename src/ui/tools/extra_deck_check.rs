@@ -0,0 +1,172 @@
+use common::{
+    card::{CardType, MonsterStats, MonsterType},
+    card_data::CardData,
+    deck::PartType,
+    deck_part::DeckPart,
+    search,
+};
+use leptos::{
+    expect_context, html, view, For, IntoView, Memo, Show, Signal, SignalGet, SignalWith, View,
+};
+
+use crate::{deck::Deck, i18n::Text};
+
+use super::{Tool, ToolHeading};
+
+/// Monsters needed for the usual Xyz Summon.
+const XYZ_MATERIALS: usize = 2;
+
+/// Notes for Fusion, Xyz and Link monsters which the main deck has no obvious way to summon.
+///
+/// Only generic requirements are checked: a card mentioning Fusion Summons for Fusion monsters,
+/// enough monsters of the same level for Xyz monsters, and any monster for Link monsters. Synchro
+/// monsters are covered by the Synchro check.
+fn extra_deck_notes(deck: &common::deck::Deck, cards: &CardData) -> Vec<String> {
+    let fusion_text = search::fold("Fusion Summon");
+
+    let mut fusions = false;
+    let mut xyzs = vec![];
+    let mut links = false;
+    let mut fusion_enabler = false;
+    let mut monsters = 0;
+    let mut level_counts = [0; 13];
+
+    for entry in deck.entries() {
+        let count = usize::from(entry.count(PartType::Playing));
+        if count == 0 {
+            continue;
+        }
+
+        let card = &cards[entry.id()];
+        if deck.playing_part(entry.id(), cards) == DeckPart::Extra {
+            match (card.card_type.monster_type(), &card.card_type) {
+                (Some(MonsterType::Fusion), _) => fusions = true,
+                (
+                    Some(MonsterType::Xyz),
+                    CardType::Monster {
+                        stats: MonsterStats::Normal { level, .. },
+                        ..
+                    },
+                ) => xyzs.push((card.name, usize::from(*level))),
+                _ if card.card_type.is_link() => links = true,
+                _ => {}
+            }
+            continue;
+        }
+
+        fusion_enabler |= card.search_text.contains(&fusion_text);
+        if let CardType::Monster { stats, .. } = &card.card_type {
+            monsters += count;
+            if let MonsterStats::Normal { level, .. } = stats {
+                if let Some(level_count) = level_counts.get_mut(usize::from(*level)) {
+                    *level_count += count;
+                }
+            }
+        }
+    }
+
+    let mut notes = vec![];
+    if fusions && !fusion_enabler {
+        notes.push("No card to Fusion Summon with, like Polymerization".to_owned());
+    }
+
+    xyzs.sort_unstable();
+    xyzs.dedup();
+    for (name, rank) in xyzs {
+        if level_counts.get(rank).copied().unwrap_or_default() < XYZ_MATERIALS {
+            notes.push(format!(
+                "Less than {XYZ_MATERIALS} Level {rank} monsters for {name} (Rank {rank})"
+            ));
+        }
+    }
+
+    if links && monsters == 0 {
+        notes.push("Main deck contains no monsters to use as Link Material".to_owned());
+    }
+
+    notes
+}
+
+/// Advisory notes for Extra deck monsters the main deck can not obviously summon.
+pub struct ExtraDeckCheck;
+
+impl Tool for ExtraDeckCheck {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "extra_deck_check"
+    }
+
+    fn name(&self) -> Text {
+        Text::ExtraDeckMaterials
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+
+        let notes = Memo::new(move |_| deck.with(|deck| extra_deck_notes(deck, &cards)));
+
+        view! {
+            <Show when=move || !notes.with(Vec::is_empty)>
+                <div>
+                    <ToolHeading text=Text::ExtraDeckMaterials />
+                    <ul class="advice">
+                        <For
+                            each=move || notes.get()
+                            key=Clone::clone
+                            children=move |note| { html::li().child(note) }
+                        />
+                    </ul>
+                </div>
+            </Show>
+        }
+        .into_view()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        card::{test_util::make_monster, FullCard, SpellType},
+        card_data::{CardDataStorage, Id},
+    };
+
+    use super::*;
+
+    fn make_named_monster(password: u32, level: u8, monster_type: Option<MonsterType>) -> FullCard {
+        let mut card = make_monster(password, level, monster_type);
+        card.name = format!("Monster {password}");
+        card
+    }
+
+    #[test]
+    fn missing_materials() {
+        let mut polymerization = make_named_monster(4, 0, None);
+        polymerization.card_type = CardType::Spell(SpellType::Normal);
+        polymerization.search_text = search::fold("Fusion Summon 1 Fusion Monster");
+        let cards = vec![
+            make_named_monster(1, 4, None),
+            make_named_monster(2, 8, Some(MonsterType::Fusion)),
+            make_named_monster(3, 4, Some(MonsterType::Xyz)),
+            polymerization,
+        ];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = common::deck::Deck::default();
+        deck.increment(Id::new(1), PartType::Playing, 1);
+        deck.increment(Id::new(2), PartType::Playing, 1);
+        assert_eq!(
+            extra_deck_notes(&deck, &cards),
+            [
+                "No card to Fusion Summon with, like Polymerization",
+                "Less than 2 Level 4 monsters for Monster 3 (Rank 4)",
+            ]
+        );
+
+        deck.increment(Id::new(0), PartType::Playing, 2);
+        deck.increment(Id::new(3), PartType::Playing, 1);
+        assert!(extra_deck_notes(&deck, &cards).is_empty());
+    }
+}
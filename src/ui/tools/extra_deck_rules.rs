@@ -0,0 +1,190 @@
+use std::fmt;
+
+use common::card_data::CardData;
+use leptos::{
+    create_effect, create_rw_signal, event_target_value, expect_context, view, CollectView,
+    IntoView, Memo, Signal, SignalGet, SignalSet, SignalUpdate, SignalWith, View,
+};
+
+use crate::{deck::Deck, i18n::Text, text_encoding::TextEncoding, ui::settings};
+
+use super::{
+    graphs::{extra_type_counts, ExtraTypeCounts},
+    Tool, ToolHeading,
+};
+
+/// Self-imposed maximum of Extra deck monsters per summoning type, `None` for no maximum.
+///
+/// The maximums are in the order of [`ExtraTypeCounts::rows`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ExtraDeckRules([Option<u8>; 4]);
+
+impl ExtraDeckRules {
+    const STORAGE_KEY: &'static str = "extra_deck_rules";
+
+    fn load() -> Self {
+        settings::load_encoded(Self::STORAGE_KEY)
+    }
+
+    fn save(self) {
+        settings::save(Self::STORAGE_KEY, &self.encode_string());
+    }
+}
+
+impl TextEncoding for ExtraDeckRules {
+    /// Maximums separated by commas, empty for types without a maximum.
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        for (idx, max) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writer.write_char(',')?;
+            }
+            if let Some(max) = max {
+                write!(writer, "{max}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        let mut result = Self::default();
+        let mut parts = text.split(',');
+        for max in &mut result.0 {
+            *max = match parts.next()? {
+                "" => None,
+                value => Some(value.parse().ok()?),
+            };
+        }
+        parts.next().is_none().then_some(result)
+    }
+}
+
+/// Advisory notes for the summoning types exceeding their maximum.
+fn rule_notes(counts: &ExtraTypeCounts, rules: ExtraDeckRules) -> Vec<String> {
+    counts
+        .rows()
+        .into_iter()
+        .zip(rules.0)
+        .filter_map(|((name, count), max)| {
+            let max = max?;
+            (count > usize::from(max))
+                .then(|| format!("{count} {name} monsters, more than the chosen maximum of {max}"))
+        })
+        .collect()
+}
+
+/// Checks the Extra deck against self-imposed limits per summoning type.
+///
+/// Unlike the deck validation, exceeding a limit only leads to a note.
+pub struct ExtraDeckRuleCheck;
+
+impl Tool for ExtraDeckRuleCheck {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "extra_deck_rules"
+    }
+
+    fn name(&self) -> Text {
+        Text::ExtraDeckRules
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+
+        let counts = Memo::new(move |_| deck.with(|deck| extra_type_counts(deck, &cards)));
+        let rules = create_rw_signal(ExtraDeckRules::load());
+        create_effect(move |_| rules.get().save());
+        let notes = move || counts.with(|counts| rule_notes(counts, rules.get()));
+
+        let inputs = ExtraTypeCounts::default()
+            .rows()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (name, _))| {
+                let value = move || {
+                    rules.get().0[idx]
+                        .map(|max| max.to_string())
+                        .unwrap_or_default()
+                };
+                let update = move |ev| {
+                    let value = event_target_value(&ev);
+                    let max = if value.is_empty() {
+                        None
+                    } else if let Ok(max) = value.parse() {
+                        Some(max)
+                    } else {
+                        return;
+                    };
+                    rules.update(|rules| rules.0[idx] = max);
+                };
+                view! {
+                    <label>
+                        {name}
+                        <input
+                            type="number"
+                            min="0"
+                            max="15"
+                            placeholder="Any"
+                            prop:value=value
+                            on:change=update
+                        />
+                    </label>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <div>
+                <ToolHeading text=Text::ExtraDeckRules />
+                <ul class="advice">
+                    {move || notes().into_iter().map(|note| view! { <li>{note}</li> }).collect_view()}
+                </ul>
+                <details class="type-targets">
+                    <summary>"Maximum per Type"</summary>
+                    {inputs}
+                    <button on:click=move |_| rules.set(ExtraDeckRules::default())>"Reset"</button>
+                </details>
+            </div>
+        }
+        .into_view()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        let rules = ExtraDeckRules([Some(3), None, Some(0), None]);
+        assert_eq!(rules.encode_string(), "3,,0,");
+        assert_eq!(ExtraDeckRules::decode(&rules.encode_string()), Some(rules));
+        assert_eq!(
+            ExtraDeckRules::decode(",,,"),
+            Some(ExtraDeckRules::default())
+        );
+        assert!(ExtraDeckRules::decode("1,2,3").is_none());
+        assert!(ExtraDeckRules::decode("x,,,").is_none());
+    }
+
+    #[test]
+    fn notes() {
+        let counts = ExtraTypeCounts {
+            fusion: 4,
+            synchro: 2,
+            xyz: 6,
+            link: 3,
+        };
+        let rules = ExtraDeckRules([Some(3), None, Some(6), Some(1)]);
+        assert_eq!(
+            rule_notes(&counts, rules),
+            [
+                "4 Fusion monsters, more than the chosen maximum of 3",
+                "3 Link monsters, more than the chosen maximum of 1",
+            ]
+        );
+        assert!(rule_notes(&counts, ExtraDeckRules::default()).is_empty());
+    }
+}
@@ -1,19 +1,26 @@
-use std::fmt::Write;
+use std::fmt::{self, Write};
 
 use common::{
-    card::{CardType, MonsterStats, MonsterType},
+    card::{tributes, CardType, MonsterStats, MonsterType},
     card_data::CardData,
-    deck::PartType,
+    deck::{DeckEntry, PartType},
 };
 use itertools::intersperse;
 use leptos::{
-    component, expect_context, view, CollectView, IntoSignal, IntoView, Memo, Signal, SignalWith,
-    View,
+    component, create_effect, create_rw_signal, event_target_value, expect_context, view,
+    CollectView, IntoSignal, IntoView, Memo, Signal, SignalGet, SignalSet, SignalUpdate,
+    SignalWith, View,
 };
 
-use crate::deck::Deck;
+use crate::{
+    deck::Deck,
+    i18n::Text,
+    print_error,
+    text_encoding::TextEncoding,
+    ui::{deck::download, settings},
+};
 
-use super::Tool;
+use super::{Tool, ToolHeading};
 
 #[derive(Debug, Clone)]
 struct GraphBar {
@@ -106,13 +113,138 @@ fn Graph<'a, const N: usize>(
     }
 }
 
+/// Counts as CSV with a header, for analysis in a spreadsheet.
+pub(crate) fn counts_csv(rows: &[(&str, usize)]) -> String {
+    let mut csv = "category,count\n".to_owned();
+    for (category, count) in rows {
+        writeln!(csv, "{category},{count}").unwrap(/* Write for String should never fail */);
+    }
+    csv
+}
+
+/// Button to download the counts shown by a graph.
+#[must_use]
+#[component]
+fn CsvExport<const N: usize>(
+    file_name: &'static str,
+    rows: impl Fn() -> [(&'static str, usize); N] + 'static,
+) -> impl IntoView {
+    let export = move |_| {
+        let csv = counts_csv(&rows());
+        if let Err(err) = download(csv.as_bytes(), "text/csv", file_name) {
+            print_error!("Error while exporting:\n\n{err}");
+        }
+    };
+
+    view! { <button class="export" on:click=export>"Export CSV"</button> }
+}
+
 pub struct TypeGraph;
 
 #[derive(Default, PartialEq, Eq)]
-struct TypeCounts {
-    monster: usize,
-    spell: usize,
-    trap: usize,
+pub(crate) struct TypeCounts {
+    pub monster: usize,
+    pub spell: usize,
+    pub trap: usize,
+}
+
+impl TypeCounts {
+    /// Counts by category, as shown in exports.
+    pub fn rows(&self) -> [(&'static str, usize); 3] {
+        [
+            ("Monsters", self.monster),
+            ("Spells", self.spell),
+            ("Traps", self.trap),
+        ]
+    }
+}
+
+pub(crate) fn type_counts(deck: &common::deck::Deck, cards: &CardData) -> TypeCounts {
+    let playing = |entry: DeckEntry| usize::from(entry.count(PartType::Playing));
+    TypeCounts {
+        monster: deck.monsters(cards).map(playing).sum(),
+        spell: deck.spells(cards).map(playing).sum(),
+        trap: deck.traps(cards).map(playing).sum(),
+    }
+}
+
+/// Card type counts a deck is compared against, following the common advice for 40 card decks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TypeTargets {
+    pub monster: u8,
+    pub spell: u8,
+    pub trap: u8,
+}
+
+impl Default for TypeTargets {
+    fn default() -> Self {
+        Self {
+            monster: 20,
+            spell: 13,
+            trap: 7,
+        }
+    }
+}
+
+impl TypeTargets {
+    const STORAGE_KEY: &'static str = "type_targets";
+
+    fn load() -> Self {
+        settings::load_encoded(Self::STORAGE_KEY)
+    }
+
+    fn save(self) {
+        settings::save(Self::STORAGE_KEY, &self.encode_string());
+    }
+}
+
+impl TextEncoding for TypeTargets {
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        write!(writer, "{},{},{}", self.monster, self.spell, self.trap)
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        let mut parts = text.split(',').map(str::parse);
+        let result = Self {
+            monster: parts.next()?.ok()?,
+            spell: parts.next()?.ok()?,
+            trap: parts.next()?.ok()?,
+        };
+        parts.next().is_none().then_some(result)
+    }
+}
+
+/// Advice for card types deviating from the targets, scaled to the size of the deck.
+///
+/// Deviations of less than three cards are considered fine.
+pub(crate) fn type_advice(counts: &TypeCounts, targets: TypeTargets) -> Vec<String> {
+    const TYPE_TOLERANCE: usize = 3;
+
+    let total = counts.monster + counts.spell + counts.trap;
+    let target_total =
+        usize::from(targets.monster) + usize::from(targets.spell) + usize::from(targets.trap);
+    if total == 0 || target_total == 0 {
+        return vec![];
+    }
+
+    [
+        ("monsters", counts.monster, targets.monster),
+        ("spells", counts.spell, targets.spell),
+        ("traps", counts.trap, targets.trap),
+    ]
+    .into_iter()
+    .filter_map(|(name, count, target)| {
+        // Rounded share of the target ratio
+        let expected = (usize::from(target) * total * 2 + target_total) / (2 * target_total);
+        let (difference, direction) = if count > expected {
+            (count - expected, "more")
+        } else {
+            (expected - count, "fewer")
+        };
+        (difference >= TYPE_TOLERANCE)
+            .then(|| format!("{difference} {direction} {name} than the suggested {expected}"))
+    })
+    .collect()
 }
 
 impl Tool for TypeGraph {
@@ -120,32 +252,21 @@ impl Tool for TypeGraph {
         Self
     }
 
+    fn key(&self) -> &'static str {
+        "type_graph"
+    }
+
+    fn name(&self) -> Text {
+        Text::CardTypes
+    }
+
     fn view(&self, deck: Signal<Deck>) -> View {
         let cards = expect_context::<CardData>();
 
-        let counts = Memo::new(move |_| {
-            let mut counts = TypeCounts::default();
-
-            deck.with(|deck| {
-                for entry in deck.entries() {
-                    let card = &cards[entry.id()];
-                    let counter = match card.card_type {
-                        CardType::Monster { .. } => {
-                            if card.card_type.is_extra_deck_monster() {
-                                continue;
-                            }
-
-                            &mut counts.monster
-                        }
-                        CardType::Spell(_) => &mut counts.spell,
-                        CardType::Trap(_) => &mut counts.trap,
-                    };
-                    *counter += usize::from(entry.count(PartType::Playing));
-                }
-            });
-
-            counts
-        });
+        let counts = Memo::new(move |_| deck.with(|deck| type_counts(deck, &cards)));
+        let targets = create_rw_signal(TypeTargets::load());
+        create_effect(move |_| targets.get().save());
+        let advice = move || counts.with(|counts| type_advice(counts, targets.get()));
 
         let bars = [
             GraphBar::new(
@@ -156,10 +277,41 @@ impl Tool for TypeGraph {
             GraphBar::new(move || counts.with(|counts| counts.trap), "trap"),
         ];
 
+        let target_input = |label: &'static str, field: fn(&mut TypeTargets) -> &mut u8| {
+            let value = move || {
+                let mut targets = targets.get();
+                *field(&mut targets)
+            };
+            let update = move |ev| {
+                if let Ok(value) = event_target_value(&ev).parse() {
+                    targets.update(|targets| *field(targets) = value);
+                }
+            };
+            view! {
+                <label>
+                    {label}
+                    <input type="number" min="0" max="60" prop:value=value on:change=update />
+                </label>
+            }
+        };
+
         view! {
             <div>
-                <h3>"Card Types"</h3>
+                <ToolHeading text=Text::CardTypes />
                 <Graph extent=40 bars=&bars />
+                <ul class="advice">
+                    {move || {
+                        advice().into_iter().map(|advice| view! { <li>{advice}</li> }).collect_view()
+                    }}
+                </ul>
+                <details class="type-targets">
+                    <summary>"Suggested Ratio"</summary>
+                    {target_input("Monsters", |targets| &mut targets.monster)}
+                    {target_input("Spells", |targets| &mut targets.spell)}
+                    {target_input("Traps", |targets| &mut targets.trap)}
+                    <button on:click=move |_| targets.set(TypeTargets::default())>"Reset"</button>
+                </details>
+                <CsvExport file_name="card_types.csv" rows=move || counts.with(TypeCounts::rows) />
             </div>
         }
         .into_view()
@@ -169,11 +321,42 @@ impl Tool for TypeGraph {
 pub struct ExtraTypeGraph;
 
 #[derive(Default, PartialEq, Eq)]
-struct ExtraTypeCounts {
-    fusion: usize,
-    synchro: usize,
-    xyz: usize,
-    link: usize,
+pub(crate) struct ExtraTypeCounts {
+    pub fusion: usize,
+    pub synchro: usize,
+    pub xyz: usize,
+    pub link: usize,
+}
+
+impl ExtraTypeCounts {
+    /// Counts by category, as shown in exports.
+    pub fn rows(&self) -> [(&'static str, usize); 4] {
+        [
+            ("Fusion", self.fusion),
+            ("Synchro", self.synchro),
+            ("Xyz", self.xyz),
+            ("Link", self.link),
+        ]
+    }
+}
+
+pub(crate) fn extra_type_counts(deck: &common::deck::Deck, cards: &CardData) -> ExtraTypeCounts {
+    let mut counts = ExtraTypeCounts::default();
+
+    for entry in deck.extra_deck(cards) {
+        let card = &cards[entry.id()];
+        let counter = match card.card_type.monster_type() {
+            Some(MonsterType::Fusion) => &mut counts.fusion,
+            Some(MonsterType::Synchro) => &mut counts.synchro,
+            Some(MonsterType::Xyz) => &mut counts.xyz,
+            _ if card.card_type.is_link() => &mut counts.link,
+            _ => continue,
+        };
+
+        *counter += usize::from(entry.count(PartType::Playing));
+    }
+
+    counts
 }
 
 impl Tool for ExtraTypeGraph {
@@ -181,40 +364,18 @@ impl Tool for ExtraTypeGraph {
         Self
     }
 
+    fn key(&self) -> &'static str {
+        "extra_type_graph"
+    }
+
+    fn name(&self) -> Text {
+        Text::ExtraDeckCardTypes
+    }
+
     fn view(&self, deck: Signal<Deck>) -> View {
         let cards = expect_context::<CardData>();
 
-        let counts = Memo::new(move |_| {
-            let mut counts = ExtraTypeCounts::default();
-
-            deck.with(|deck| {
-                for entry in deck.entries() {
-                    let card = &cards[entry.id()];
-                    if let CardType::Monster { stats, .. } = &card.card_type {
-                        let counter = match stats {
-                            MonsterStats::Normal {
-                                monster_type: Some(MonsterType::Fusion),
-                                ..
-                            } => &mut counts.fusion,
-                            MonsterStats::Normal {
-                                monster_type: Some(MonsterType::Synchro),
-                                ..
-                            } => &mut counts.synchro,
-                            MonsterStats::Normal {
-                                monster_type: Some(MonsterType::Xyz),
-                                ..
-                            } => &mut counts.xyz,
-                            MonsterStats::Normal { .. } => continue,
-                            MonsterStats::Link { .. } => &mut counts.link,
-                        };
-
-                        *counter += usize::from(entry.count(PartType::Playing));
-                    }
-                }
-            });
-
-            counts
-        });
+        let counts = Memo::new(move |_| deck.with(|deck| extra_type_counts(deck, &cards)));
 
         let bars = [
             GraphBar::new(
@@ -231,8 +392,12 @@ impl Tool for ExtraTypeGraph {
 
         view! {
             <div>
-                <h3>"Extra Deck Card Types"</h3>
+                <ToolHeading text=Text::ExtraDeckCardTypes />
                 <Graph extent=15 spacing=5 bars=&bars />
+                <CsvExport
+                    file_name="extra_deck_card_types.csv"
+                    rows=move || counts.with(ExtraTypeCounts::rows)
+                />
             </div>
         }
         .into_view()
@@ -242,10 +407,55 @@ impl Tool for ExtraTypeGraph {
 pub struct LevelGraph;
 
 #[derive(Default, PartialEq, Eq)]
-struct LevelCounts {
-    no_tribute: usize,
-    one_tribute: usize,
-    two_tributes: usize,
+pub(crate) struct LevelCounts {
+    pub no_tribute: usize,
+    pub one_tribute: usize,
+    pub two_tributes: usize,
+}
+
+impl LevelCounts {
+    /// Counts by category, as shown in exports.
+    pub fn rows(&self) -> [(&'static str, usize); 3] {
+        [
+            ("0 - 4", self.no_tribute),
+            ("5 - 6", self.one_tribute),
+            ("7+", self.two_tributes),
+        ]
+    }
+}
+
+pub(crate) fn level_counts(deck: &common::deck::Deck, cards: &CardData) -> LevelCounts {
+    let mut counts = LevelCounts::default();
+
+    for entry in deck.monsters(cards) {
+        let card = &cards[entry.id()];
+        if let CardType::Monster {
+            stats:
+                MonsterStats::Normal {
+                    level,
+                    monster_type,
+                    ..
+                },
+            ..
+        } = &card.card_type
+        {
+            // Ritual monsters are not Tribute Summoned. Xyz monsters have a rank stored as level,
+            // and like the other Extra deck monsters they can not be Tribute Summoned either, which
+            // leaves the monsters without a type
+            if monster_type.is_some() {
+                continue;
+            }
+
+            let counter = match tributes(*level) {
+                0 => &mut counts.no_tribute,
+                1 => &mut counts.one_tribute,
+                _ => &mut counts.two_tributes,
+            };
+            *counter += usize::from(entry.count(PartType::Playing));
+        }
+    }
+
+    counts
 }
 
 impl Tool for LevelGraph {
@@ -253,41 +463,18 @@ impl Tool for LevelGraph {
         Self
     }
 
+    fn key(&self) -> &'static str {
+        "level_graph"
+    }
+
+    fn name(&self) -> Text {
+        Text::MonsterLevels
+    }
+
     fn view(&self, deck: Signal<Deck>) -> View {
         let cards = expect_context::<CardData>();
 
-        let counts = Memo::new(move |_| {
-            let mut counts = LevelCounts::default();
-
-            deck.with(|deck| {
-                for entry in deck.entries() {
-                    let card = &cards[entry.id()];
-                    if let CardType::Monster {
-                        stats:
-                            MonsterStats::Normal {
-                                level,
-                                monster_type,
-                                ..
-                            },
-                        ..
-                    } = &card.card_type
-                    {
-                        if monster_type.is_some() {
-                            continue;
-                        }
-
-                        let counter = match level {
-                            0..=4 => &mut counts.no_tribute,
-                            5..=6 => &mut counts.one_tribute,
-                            7.. => &mut counts.two_tributes,
-                        };
-                        *counter += usize::from(entry.count(PartType::Playing));
-                    }
-                }
-            });
-
-            counts
-        });
+        let counts = Memo::new(move |_| deck.with(|deck| level_counts(deck, &cards)));
         let bars = [
             GraphBar::with_label(
                 move || counts.with(|counts| counts.no_tribute),
@@ -308,10 +495,205 @@ impl Tool for LevelGraph {
 
         view! {
             <div>
-                <h3>"Monster Levels"</h3>
+                <ToolHeading text=Text::MonsterLevels />
+                <Graph extent=30 bars=&bars />
+                <CsvExport file_name="monster_levels.csv" rows=move || counts.with(LevelCounts::rows) />
+            </div>
+        }
+        .into_view()
+    }
+}
+
+pub struct AtkGraph;
+
+#[derive(Default, PartialEq, Eq)]
+pub(crate) struct AtkCounts {
+    pub low: usize,
+    pub medium: usize,
+    pub high: usize,
+    /// Monsters with "?" ATK, which are not part of the other counts or the average
+    pub questionmark: usize,
+    /// Average ATK of the monsters with a known value
+    pub average: Option<u32>,
+}
+
+impl AtkCounts {
+    /// Counts by category, as shown in exports.
+    pub fn rows(&self) -> [(&'static str, usize); 4] {
+        [
+            ("0 - 1499", self.low),
+            ("1500 - 2399", self.medium),
+            ("2400+", self.high),
+            ("?", self.questionmark),
+        ]
+    }
+}
+
+/// ATK distribution of the Main deck monsters.
+pub(crate) fn atk_counts(deck: &common::deck::Deck, cards: &CardData) -> AtkCounts {
+    let mut counts = AtkCounts::default();
+    let mut sum = 0;
+    let mut known = 0;
+
+    for entry in deck.monsters(cards) {
+        let card = &cards[entry.id()];
+        let CardType::Monster {
+            stats: MonsterStats::Normal { atk, .. } | MonsterStats::Link { atk, .. },
+            ..
+        } = &card.card_type
+        else {
+            continue;
+        };
+
+        let count = entry.count(PartType::Playing);
+        let Some(value) = atk.value() else {
+            counts.questionmark += usize::from(count);
+            continue;
+        };
+
+        let counter = match value {
+            0..=1499 => &mut counts.low,
+            1500..=2399 => &mut counts.medium,
+            2400.. => &mut counts.high,
+        };
+        *counter += usize::from(count);
+        sum += u32::from(value) * u32::from(count);
+        known += u32::from(count);
+    }
+
+    counts.average = (known > 0).then(|| sum / known);
+    counts
+}
+
+impl Tool for AtkGraph {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "atk_graph"
+    }
+
+    fn name(&self) -> Text {
+        Text::MonsterAtk
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+
+        let counts = Memo::new(move |_| deck.with(|deck| atk_counts(deck, &cards)));
+        let bars = [
+            GraphBar::with_label(
+                move || counts.with(|counts| counts.low),
+                "monster",
+                "0 - 1499",
+            ),
+            GraphBar::with_label(
+                move || counts.with(|counts| counts.medium),
+                "monster",
+                "1500 - 2399",
+            ),
+            GraphBar::with_label(
+                move || counts.with(|counts| counts.high),
+                "monster",
+                "2400+",
+            ),
+            GraphBar::with_label(
+                move || counts.with(|counts| counts.questionmark),
+                "monster",
+                "?",
+            ),
+        ];
+        let average = move || {
+            counts.with(|counts| match counts.average {
+                Some(average) => format!("Average: {average}"),
+                None => "Average: -".to_owned(),
+            })
+        };
+
+        view! {
+            <div>
+                <ToolHeading text=Text::MonsterAtk />
                 <Graph extent=30 bars=&bars />
+                <p>{average}</p>
+                <CsvExport file_name="monster_atk.csv" rows=move || counts.with(AtkCounts::rows) />
             </div>
         }
         .into_view()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use common::{
+        card::test_util::make_monster,
+        card_data::{CardDataStorage, Id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn level_buckets() {
+        let cards = vec![
+            make_monster(1, 0, None),
+            make_monster(2, 4, None),
+            make_monster(3, 5, None),
+            make_monster(4, 6, None),
+            make_monster(5, 7, None),
+            make_monster(6, 12, None),
+            make_monster(7, 8, Some(MonsterType::Ritual)),
+            make_monster(8, 4, Some(MonsterType::Xyz)),
+        ];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = common::deck::Deck::default();
+        for id in 0..8 {
+            deck.increment(Id::new(id), PartType::Playing, 1);
+        }
+        // Rank 4 Xyz kept in the Main deck must not count as a level 4 monster
+        deck.set_main_override(Id::new(7), true);
+
+        let counts = level_counts(&deck, &cards);
+        assert_eq!(counts.no_tribute, 2);
+        assert_eq!(counts.one_tribute, 2);
+        assert_eq!(counts.two_tributes, 2);
+    }
+
+    #[test]
+    fn csv() {
+        let counts = LevelCounts {
+            no_tribute: 12,
+            one_tribute: 3,
+            two_tributes: 0,
+        };
+        assert_eq!(
+            counts_csv(&counts.rows()),
+            "category,count\n0 - 4,12\n5 - 6,3\n7+,0\n"
+        );
+    }
+
+    #[test]
+    fn type_targets() {
+        let counts = |monster, spell, trap| TypeCounts {
+            monster,
+            spell,
+            trap,
+        };
+        let targets = TypeTargets::default();
+
+        assert_eq!(
+            type_advice(&counts(30, 5, 5), targets),
+            [
+                "10 more monsters than the suggested 20",
+                "8 fewer spells than the suggested 13"
+            ]
+        );
+        // Targets are scaled to the deck size
+        assert!(type_advice(&counts(10, 7, 3), targets).is_empty());
+        assert!(type_advice(&counts(0, 0, 0), targets).is_empty());
+
+        assert_eq!(TypeTargets::decode(&targets.encode_string()), Some(targets));
+        assert!(TypeTargets::decode("20,13").is_none());
+        assert!(TypeTargets::decode("20,13,7,0").is_none());
+    }
+}
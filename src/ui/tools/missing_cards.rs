@@ -0,0 +1,81 @@
+use common::card_data::CardData;
+use leptos::{
+    expect_context, view, For, IntoView, Memo, RwSignal, Show, Signal, SignalGet, SignalWith, View,
+};
+
+use crate::{
+    collection::{write_missing_csv, Collection},
+    deck::Deck,
+    i18n::Text,
+    print_error,
+    ui::deck::download,
+};
+
+use super::{Tool, ToolHeading};
+
+/// Lists cards the deck uses beyond the owned copies, and exports them as a shopping list.
+pub struct MissingCards;
+
+impl Tool for MissingCards {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "missing_cards"
+    }
+
+    fn name(&self) -> Text {
+        Text::MissingCards
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+        let collection = expect_context::<RwSignal<Collection>>();
+
+        let missing = Memo::new(move |_| {
+            collection.with(|collection| {
+                deck.with(|deck| collection.missing(deck, &cards).collect::<Vec<_>>())
+            })
+        });
+
+        let export = move |_| {
+            let mut buffer = Vec::new();
+            let result = collection.with(|collection| {
+                deck.with(|deck| write_missing_csv(collection, deck, &cards, &mut buffer))
+            });
+            if let Err(err) = result
+                .map_err(Into::into)
+                .and_then(|()| download(&buffer, "text/csv", "missing_cards.csv"))
+            {
+                print_error!("Error while exporting:\n\n{err}");
+            }
+        };
+
+        view! {
+            <Show when=move || collection.with(|collection| collection.enabled)>
+                <div>
+                    <ToolHeading text=Text::MissingCards />
+                    <Show when=move || !missing.with(Vec::is_empty)>
+                        <button on:click=export>"Export CSV..."</button>
+                    </Show>
+                    <Show
+                        when=move || !missing.with(Vec::is_empty)
+                        fallback=|| view! { <p>"All cards are owned"</p> }
+                    >
+                        <ul class="errors">
+                            <For
+                                each=move || missing.get()
+                                key=|entry| *entry
+                                children=move |(id, count)| {
+                                    view! { <li>{format!("{count}x {}", cards[id].name)}</li> }
+                                }
+                            />
+                        </ul>
+                    </Show>
+                </div>
+            </Show>
+        }
+        .into_view()
+    }
+}
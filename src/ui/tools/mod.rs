@@ -1,47 +1,365 @@
+mod data_info;
+pub mod deck_image;
 mod error_list;
+mod extra_deck_check;
+mod extra_deck_rules;
 mod graphs;
+mod missing_cards;
+pub mod report;
+mod snapshots;
+mod suggestions;
+mod synchro_check;
+mod tag_summary;
 
-use leptos::{component, expect_context, view, IntoView, RwSignal, Signal, View};
+use std::{fmt, time::Duration};
 
-use crate::deck::Deck;
+use leptos::{
+    component, create_effect, create_node_ref, create_rw_signal, expect_context, html,
+    leptos_dom::helpers::TimeoutHandle, request_animation_frame, set_timeout_with_handle,
+    store_value, view, CollectView, For, IntoView, RwSignal, Show, Signal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, View,
+};
+
+use crate::{
+    deck::Deck,
+    i18n::{Locale, Text},
+    text_encoding::TextEncoding,
+    ui::settings,
+};
 
 trait Tool {
     fn init() -> Self
     where
         Self: Sized;
 
+    /// Stable identifier used for storing the configuration.
+    fn key(&self) -> &'static str;
+
+    /// Name shown in the tool settings.
+    fn name(&self) -> Text;
+
     fn view(&self, deck: Signal<Deck>) -> View;
 }
 
-struct ToolManager(Vec<Box<dyn Tool>>);
+/// Persisted configuration of the tools panel.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct ToolsConfig {
+    /// Tool keys in display order, together with their visibility
+    tools: Vec<(String, bool)>,
+    scroll: i32,
+}
+
+impl ToolsConfig {
+    const STORAGE_KEY: &'static str = "tools";
+
+    fn load() -> Self {
+        settings::load_encoded(Self::STORAGE_KEY)
+    }
+
+    fn save(&self) {
+        settings::save(Self::STORAGE_KEY, &self.encode_string());
+    }
+}
+
+impl TextEncoding for ToolsConfig {
+    fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        write!(writer, "{}", self.scroll)?;
+        for (key, visible) in &self.tools {
+            write!(writer, ";{key}:{}", u8::from(*visible))?;
+        }
+        Ok(())
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        let mut parts = text.split(';');
+        let scroll = parts.next()?.parse().ok()?;
+
+        let tools = parts
+            .map(|part| {
+                let (key, visible) = part.split_once(':')?;
+                let visible = match visible {
+                    "0" => false,
+                    "1" => true,
+                    _ => return None,
+                };
+                Some((key.to_owned(), visible))
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { tools, scroll })
+    }
+}
+
+/// Deck for the tools, following the main deck at most once per animation frame.
+///
+/// Several deck changes in quick succession, like a series of drops or held down shortcuts, only
+/// lead to one recomputation of the tools.
+fn throttled_deck(deck: RwSignal<Deck>) -> Signal<Deck> {
+    let throttled = create_rw_signal(deck.get_untracked());
+    let pending = store_value(false);
+
+    create_effect(move |previous: Option<()>| {
+        deck.with(|_| {});
+        // Nothing changed yet on the first run
+        if previous.is_none() || pending.get_value() {
+            return;
+        }
+
+        pending.set_value(true);
+        request_animation_frame(move || {
+            pending.set_value(false);
+            throttled.set(deck.get_untracked());
+        });
+    });
+
+    throttled.into()
+}
+
+struct ToolEntry {
+    tool: Box<dyn Tool>,
+    visible: RwSignal<bool>,
+}
+
+struct ToolManager {
+    entries: Vec<ToolEntry>,
+    /// Indices into the entries in display order
+    order: RwSignal<Vec<usize>>,
+}
 
 impl ToolManager {
     fn new() -> Self {
-        Self(vec![])
+        Self {
+            entries: vec![],
+            order: create_rw_signal(vec![]),
+        }
     }
 
     fn add<T: Tool + 'static>(&mut self) {
-        self.0.push(Box::new(T::init()));
+        self.order.update(|order| order.push(self.entries.len()));
+        self.entries.push(ToolEntry {
+            tool: Box::new(T::init()),
+            visible: create_rw_signal(true),
+        });
+    }
+
+    /// Restore order and visibility of the tools.
+    ///
+    /// Tools missing from the configuration keep their visibility and are placed after the
+    /// configured ones, in the order they were added.
+    fn apply_config(&self, config: &ToolsConfig) {
+        let position = |entry: &ToolEntry| {
+            config
+                .tools
+                .iter()
+                .position(|(key, _)| key == entry.tool.key())
+                .unwrap_or(usize::MAX)
+        };
+        self.order
+            .update(|order| order.sort_by_key(|&idx| position(&self.entries[idx])));
+
+        for entry in &self.entries {
+            if let Some((_, visible)) = config.tools.iter().find(|(key, _)| key == entry.tool.key())
+            {
+                entry.visible.set(*visible);
+            }
+        }
+    }
+
+    /// Current configuration, tracking the order and the visibility signals.
+    fn config(&self, scroll: i32) -> ToolsConfig {
+        ToolsConfig {
+            tools: self
+                .order
+                .get()
+                .into_iter()
+                .map(|idx| {
+                    let entry = &self.entries[idx];
+                    (entry.tool.key().to_owned(), entry.visible.get())
+                })
+                .collect(),
+            scroll,
+        }
+    }
+
+    /// Controls to show, hide and reorder the tools.
+    fn settings(&self) -> impl IntoView {
+        let locale = expect_context::<RwSignal<Locale>>();
+        let order = self.order;
+        let rows = self
+            .entries
+            .iter()
+            .map(|entry| (entry.tool.name(), entry.visible))
+            .collect::<Vec<_>>();
+
+        // Move the tool at the given display position by one step
+        let shift = move |position: usize, up: bool| {
+            order.update(|order| {
+                let other = if up {
+                    position.checked_sub(1)
+                } else {
+                    Some(position + 1).filter(|&other| other < order.len())
+                };
+                if let Some(other) = other {
+                    order.swap(position, other);
+                }
+            });
+        };
+
+        view! {
+            <details class="tool-settings">
+                <summary>{move || Text::Tools.localize(locale.get())}</summary>
+                <ul>
+                    {move || {
+                        let count = order.with(Vec::len);
+                        order
+                            .get()
+                            .into_iter()
+                            .enumerate()
+                            .map(|(position, idx)| {
+                                let (name, visible) = rows[idx];
+                                view! {
+                                    <li>
+                                        <label>
+                                            <input
+                                                type="checkbox"
+                                                prop:checked=move || visible.get()
+                                                on:change=move |_| visible.update(|visible| *visible = !*visible)
+                                            />
+                                            {move || name.localize(locale.get())}
+                                        </label>
+                                        <button
+                                            title=move || Text::MoveUp.localize(locale.get())
+                                            disabled=position == 0
+                                            on:click=move |_| shift(position, true)
+                                        >
+                                            "↑"
+                                        </button>
+                                        <button
+                                            title=move || Text::MoveDown.localize(locale.get())
+                                            disabled=position + 1 == count
+                                            on:click=move |_| shift(position, false)
+                                        >
+                                            "↓"
+                                        </button>
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </ul>
+            </details>
+        }
     }
 
     fn view(&self) -> impl IntoView {
-        let deck = expect_context::<RwSignal<Deck>>();
-        self.0
+        let deck = throttled_deck(expect_context());
+        let views = self
+            .entries
             .iter()
-            .map(|tool| tool.view(deck.into()))
-            .collect::<Vec<_>>()
+            .map(|entry| {
+                let visible = entry.visible;
+                let view = entry.tool.view(deck);
+                view! { <Show when=move || visible.get()>{view.clone()}</Show> }.into_view()
+            })
+            .collect::<Vec<_>>();
+
+        let order = self.order;
+        view! {
+            <For
+                each=move || order.get()
+                key=|&idx| idx
+                children=move |idx| views[idx].clone()
+            />
+        }
     }
 }
 
+/// Heading of a tool, in the language of the user interface.
+#[component]
+#[must_use]
+fn ToolHeading(text: Text) -> impl IntoView {
+    let locale = expect_context::<RwSignal<Locale>>();
+    view! { <h3>{move || text.localize(locale.get())}</h3> }
+}
+
 #[component]
 #[must_use]
 pub fn Tools() -> impl IntoView {
+    // Delay before storing the scroll position, to avoid saving on every scroll event
+    const DEBOUNCE: Duration = Duration::from_millis(250);
+
     let mut tools = ToolManager::new();
 
     tools.add::<error_list::ErrorList>();
+    tools.add::<synchro_check::SynchroCheck>();
+    tools.add::<extra_deck_check::ExtraDeckCheck>();
     tools.add::<graphs::TypeGraph>();
     tools.add::<graphs::ExtraTypeGraph>();
+    tools.add::<extra_deck_rules::ExtraDeckRuleCheck>();
     tools.add::<graphs::LevelGraph>();
+    tools.add::<graphs::AtkGraph>();
+    tools.add::<tag_summary::TagSummary>();
+    tools.add::<missing_cards::MissingCards>();
+    tools.add::<suggestions::StapleSuggestions>();
+    tools.add::<snapshots::SnapshotList>();
+    tools.add::<data_info::DataInfo>();
+
+    let config = ToolsConfig::load();
+    tools.apply_config(&config);
+
+    let scroll = create_rw_signal(config.scroll);
+    let pending = store_value(None::<TimeoutHandle>);
+    let node_ref = create_node_ref::<html::Div>();
+    node_ref.on_load(move |node| node.set_scroll_top(config.scroll));
+
+    let settings = tools.settings();
+    let view = tools.view();
+    create_effect(move |_| tools.config(scroll.get()).save());
 
-    view! { <div class="tools">{tools.view()}</div> }
+    view! {
+        <div
+            class="tools"
+            ref=node_ref
+            on:scroll=move |_| {
+                if let Some(handle) = pending.get_value() {
+                    handle.clear();
+                }
+                let update = move || {
+                    pending.set_value(None);
+                    scroll.set(node_ref.get_untracked().unwrap().scroll_top());
+                };
+                pending.set_value(set_timeout_with_handle(update, DEBOUNCE).ok());
+            }
+        >
+            {settings}
+            {view}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config_encoding() {
+        let config = ToolsConfig {
+            tools: vec![
+                ("errors".to_owned(), true),
+                ("level_graph".to_owned(), false),
+            ],
+            scroll: 120,
+        };
+
+        assert_eq!(config.encode_string(), "120;errors:1;level_graph:0");
+        assert_eq!(ToolsConfig::decode(&config.encode_string()), Some(config));
+        assert_eq!(
+            ToolsConfig::decode("0"),
+            Some(ToolsConfig {
+                tools: vec![],
+                scroll: 0
+            })
+        );
+        assert!(ToolsConfig::decode("0;errors:x").is_none());
+    }
 }
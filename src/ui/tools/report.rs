@@ -0,0 +1,230 @@
+//! Markdown summary of the deck, containing the data shown by the tools
+
+use std::fmt::{self, Display, Write};
+
+use common::{
+    card::{Attribute, CardType, EffectTag},
+    card_data::CardData,
+    deck::{Deck, PartType},
+    deck_part::DeckPart,
+};
+
+use crate::{
+    deck_meta::DeckMeta,
+    deck_order::deck_order,
+    i18n::{Locale, Text},
+};
+
+use super::graphs::{atk_counts, extra_type_counts, level_counts, type_counts};
+
+fn write_counts<L: Display>(
+    writer: &mut impl Write,
+    title: &str,
+    counts: impl IntoIterator<Item = (L, usize)>,
+) -> fmt::Result {
+    writeln!(writer, "## {title}\n")?;
+    for (label, count) in counts {
+        writeln!(writer, "- {label}: {count}")?;
+    }
+    writeln!(writer)
+}
+
+fn write_meta(meta: &DeckMeta, cards: &CardData, writer: &mut impl Write) -> fmt::Result {
+    let name = meta.name.trim();
+    if name.is_empty() {
+        writeln!(writer, "# Deck Report\n")?;
+    } else {
+        writeln!(writer, "# {name}\n")?;
+    }
+
+    let cover = meta.cover.map(|id| cards[id].name);
+    let fields = [
+        ("Author", Some(meta.author.trim())),
+        ("Format", Some(meta.format.trim())),
+        ("Cover Card", cover),
+    ];
+    let mut has_fields = false;
+    for (label, value) in fields {
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            writeln!(writer, "- {label}: {value}")?;
+            has_fields = true;
+        }
+    }
+    if has_fields {
+        writeln!(writer)?;
+    }
+
+    let notes = meta.notes.trim();
+    if !notes.is_empty() {
+        writeln!(writer, "{notes}\n")?;
+    }
+    Ok(())
+}
+
+fn write_report(
+    deck: &Deck,
+    meta: &DeckMeta,
+    cards: &CardData,
+    writer: &mut impl Write,
+) -> fmt::Result {
+    write_meta(meta, cards, writer)?;
+
+    let sizes = deck.part_sizes(cards);
+    let parts = DeckPart::iter().map(|part| (part, sizes[part as usize]));
+    write_counts(writer, "Deck Parts", parts)?;
+
+    let types = type_counts(deck, cards);
+    write_counts(writer, "Card Types", types.rows())?;
+
+    let extra = extra_type_counts(deck, cards);
+    write_counts(writer, "Extra Deck Card Types", extra.rows())?;
+
+    let levels = level_counts(deck, cards);
+    write_counts(writer, "Monster Levels", levels.rows())?;
+
+    let atk = atk_counts(deck, cards);
+    write_counts(writer, "Monster ATK", atk.rows())?;
+    if let Some(average) = atk.average {
+        writeln!(writer, "Average ATK: {average}\n")?;
+    }
+
+    let attributes = Attribute::iter().map(|attribute| {
+        let count = deck
+            .monsters(cards)
+            .filter(|entry| {
+                matches!(
+                    cards[entry.id()].card_type,
+                    CardType::Monster { attribute: card_attribute, .. } if card_attribute == attribute
+                )
+            })
+            .map(|entry| usize::from(entry.count(PartType::Playing)))
+            .sum();
+        (attribute, count)
+    });
+    write_counts(writer, "Monster Attributes", attributes)?;
+
+    let tags = EffectTag::iter().map(|tag| {
+        let count = deck
+            .entries_for_part(DeckPart::Main, cards)
+            .filter(|(id, _)| cards[*id].effect_tags.has(tag))
+            .map(|(_, count)| usize::from(count))
+            .sum();
+        // Like the rest of the report, the tag names are in English
+        (Text::EffectTag(tag).localize(Locale::English), count)
+    });
+    write_counts(writer, "Effect Tags", tags)?;
+
+    for part in DeckPart::iter() {
+        let mut entries = deck.entries_for_part(part, cards).collect::<Vec<_>>();
+        if entries.is_empty() {
+            continue;
+        }
+        entries.sort_unstable_by(|(lhs, _), (rhs, _)| deck_order(&cards[*lhs], &cards[*rhs]));
+
+        writeln!(writer, "## {part} Deck\n")?;
+        for (id, count) in entries {
+            let card = &cards[id];
+            writeln!(writer, "- {count}x {} ({})", card.name, card.summary())?;
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+/// Generate a Markdown report of the deck, for sharing deck analysis outside of the app.
+///
+/// The report is titled with the deck name and starts with the other information of `meta`.
+#[must_use]
+pub fn report(deck: &Deck, meta: &DeckMeta, cards: &CardData) -> String {
+    let mut result = String::new();
+    write_report(deck, meta, cards, &mut result).unwrap(/* Write for String should never fail */);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        card::{
+            test_util::{make_card, make_extra_deck_card, make_pendulum_card},
+            CombatStat, MonsterStats,
+        },
+        card_data::{CardDataStorage, Id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn report_sections() {
+        let mut spell = make_card(1234);
+        spell.name = "Pot".to_owned();
+        let mut fusion = make_extra_deck_card(9876);
+        fusion.name = "Fusion".to_owned();
+        let cards = CardData::from(CardDataStorage::new(vec![spell, fusion], vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 3);
+        deck.increment(Id::new(1), PartType::Side, 1);
+
+        let report = report(&deck, &DeckMeta::default(), &cards);
+        assert!(report.starts_with("# Deck Report\n\n## Deck Parts\n\n- Main: 3\n"));
+        assert!(report.contains("- Spells: 3\n"));
+        assert!(report.contains("## Main Deck\n\n- 3x Pot (Spell)\n"));
+        assert!(report.contains(&format!(
+            "## Side Deck\n\n- 1x Fusion ({})\n",
+            cards[Id::new(1)].summary()
+        )));
+        assert!(!report.contains("## Extra Deck\n"));
+    }
+
+    #[test]
+    fn report_atk() {
+        let with_atk = |password, value: CombatStat| {
+            let mut card = make_pendulum_card(password, None);
+            if let CardType::Monster {
+                stats: MonsterStats::Normal { atk, .. },
+                ..
+            } = &mut card.card_type
+            {
+                *atk = value;
+            }
+            card
+        };
+        let cards = vec![
+            with_atk(1, CombatStat::new(1800)),
+            with_atk(2, CombatStat::new(2500)),
+            with_atk(3, CombatStat::questionmark()),
+        ];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 2);
+        deck.increment(Id::new(1), PartType::Playing, 1);
+        deck.increment(Id::new(2), PartType::Playing, 3);
+
+        // Questionmark ATK is counted separately and does not lower the average
+        let report = report(&deck, &DeckMeta::default(), &cards);
+        assert!(report.contains(
+            "## Monster ATK\n\n- 0 - 1499: 0\n- 1500 - 2399: 2\n- 2400+: 1\n- ?: 3\n\nAverage ATK: 2033\n"
+        ));
+    }
+
+    #[test]
+    fn report_meta() {
+        let mut spell = make_card(1234);
+        spell.name = "Pot".to_owned();
+        let cards = CardData::from(CardDataStorage::new(vec![spell], vec![]));
+
+        let meta = DeckMeta {
+            name: "Greed".to_owned(),
+            author: "Someone".to_owned(),
+            notes: "Draw a lot.".to_owned(),
+            cover: Some(Id::new(0)),
+            ..DeckMeta::default()
+        };
+        let report = report(&Deck::default(), &meta, &cards);
+        assert!(report.starts_with(
+            "# Greed\n\n- Author: Someone\n- Cover Card: Pot\n\nDraw a lot.\n\n## Deck Parts\n"
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+use common::{
+    card_data::CardData,
+    deck::{CountChange, PartType},
+};
+use leptos::{
+    create_effect, create_node_ref, create_rw_signal, ev, expect_context, html, view, CollectView,
+    IntoView, RwSignal, Show, Signal, SignalGet, SignalSet, SignalUpdate, SignalWith,
+    SignalWithUntracked, View,
+};
+
+use crate::{
+    collection::Collection,
+    deck::Deck,
+    i18n::Text,
+    snapshots::{Snapshot, Snapshots},
+    text_encoding::TextEncoding,
+    ui::{card_view::image_url, settings},
+};
+
+use super::{Tool, ToolHeading};
+
+const STORAGE_KEY: &str = "deck_snapshots";
+
+fn load() -> Snapshots {
+    settings::load_encoded(STORAGE_KEY)
+}
+
+fn save(snapshots: &Snapshots) {
+    settings::save(STORAGE_KEY, &snapshots.encode_string());
+}
+
+/// Images of the cards representing the snapshot, see [`Snapshot::thumbnail`].
+fn thumbnail(snapshot: &Snapshot, cards: &CardData) -> View {
+    let thumbnail = snapshot.thumbnail(cards);
+    let single = thumbnail.len() == 1;
+    let images = thumbnail
+        .into_iter()
+        .map(|id| view! { <img src=image_url(cards.image_password(id)) alt=cards[id].name /> })
+        .collect_view();
+
+    view! {
+        <div class="thumbnail" class:single=single>
+            {images}
+        </div>
+    }
+    .into_view()
+}
+
+/// Changes of the deck since a snapshot.
+fn change_list(changes: Vec<CountChange>, cards: &CardData) -> View {
+    if changes.is_empty() {
+        return view! { <p>"No changes since this snapshot"</p> }.into_view();
+    }
+
+    let changes = changes
+        .into_iter()
+        .map(|change| {
+            let part = match change.part_type {
+                PartType::Playing => "",
+                PartType::Side => " (Side)",
+            };
+            let text = format!(
+                "{}{part}: {} → {}",
+                cards[change.id].name, change.from, change.to
+            );
+            view! { <li>{text}</li> }
+        })
+        .collect::<Vec<_>>();
+    view! { <ul class="changes">{changes}</ul> }.into_view()
+}
+
+/// Cards missing to build all snapshots at the same time, see [`Collection::budget`].
+fn budget_list(snapshots: &Snapshots, collection: &Collection, cards: &CardData) -> View {
+    let decks = snapshots.0.iter().map(|snapshot| &*snapshot.deck);
+    let missing = collection
+        .budget(decks, cards)
+        .into_iter()
+        .filter(|&(_, _, missing)| missing > 0)
+        .map(|(id, needed, missing)| {
+            let text = format!("{missing}x {} ({needed} needed)", cards[id].name);
+            view! { <li>{text}</li> }
+        })
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        return view! { <p>"All snapshots can be built at the same time"</p> }.into_view();
+    }
+    view! {
+        <h4>"Missing for all snapshots"</h4>
+        <ul class="changes">{missing}</ul>
+    }
+    .into_view()
+}
+
+/// Save named copies of the deck, to restore them or compare the deck against them later.
+pub struct SnapshotList;
+
+impl Tool for SnapshotList {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "snapshots"
+    }
+
+    fn name(&self) -> Text {
+        Text::Snapshots
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+        let deck_rw = expect_context::<RwSignal<Deck>>();
+        let collection = expect_context::<RwSignal<Collection>>();
+
+        let snapshots = create_rw_signal(load());
+        create_effect(move |_| snapshots.with(save));
+
+        // Index of the snapshot the deck is compared against
+        let compared = create_rw_signal(None::<usize>);
+
+        let name_ref = create_node_ref::<html::Input>();
+        let add = move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+            let input = name_ref.get().unwrap();
+            let name = input.value();
+            if name.trim().is_empty() {
+                return;
+            }
+            deck.with_untracked(|deck| snapshots.update(|snapshots| snapshots.add(&name, deck)));
+            input.set_value("");
+        };
+
+        let restore = move |idx: usize| {
+            snapshots.with_untracked(|snapshots| {
+                deck_rw.update(|deck| deck.set_entries(&snapshots.0[idx].deck));
+            });
+        };
+        let delete = move |idx: usize| {
+            snapshots.update(|snapshots| {
+                snapshots.0.remove(idx);
+            });
+            compared.set(None);
+        };
+        let toggle_compare = move |idx: usize| {
+            compared.update(|compared| {
+                *compared = (*compared != Some(idx)).then_some(idx);
+            });
+        };
+
+        let list = move || {
+            snapshots.with(|snapshots| {
+                snapshots
+                    .0
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, snapshot)| {
+                        view! {
+                            <li>
+                                {thumbnail(snapshot, &cards)}
+                                <span class="name">{snapshot.name.clone()}</span>
+                                <button on:click=move |_| restore(idx)>"Restore"</button>
+                                <button
+                                    class:active=move || compared.get() == Some(idx)
+                                    on:click=move |_| toggle_compare(idx)
+                                >
+                                    "Compare"
+                                </button>
+                                <button on:click=move |_| delete(idx)>"Delete"</button>
+                            </li>
+                        }
+                    })
+                    .collect_view()
+            })
+        };
+
+        // Changes of the deck since the compared snapshot
+        let changes = move || {
+            let idx = compared.get()?;
+            let changes = snapshots.with(|snapshots| {
+                let snapshot = snapshots.0.get(idx)?;
+                Some(deck.with(|deck| snapshot.deck.diff(deck)))
+            })?;
+            Some(change_list(changes, &cards))
+        };
+
+        // Copies needed to build every snapshot at once, as copies can not be shared between decks
+        let budget = move || {
+            collection.with(|collection| {
+                if !collection.enabled {
+                    return None;
+                }
+                snapshots.with(|snapshots| {
+                    (!snapshots.0.is_empty()).then(|| budget_list(snapshots, collection, &cards))
+                })
+            })
+        };
+
+        view! {
+            <div class="snapshots">
+                <ToolHeading text=Text::Snapshots />
+                <form on:submit=add>
+                    <input type="text" placeholder="Name" ref=name_ref />
+                    <button type="submit">"Save"</button>
+                </form>
+                <Show when=move || !snapshots.with(|snapshots| snapshots.0.is_empty())>
+                    <ul class="list">{list}</ul>
+                </Show>
+                {changes}
+                {budget}
+            </div>
+        }
+        .into_view()
+    }
+}
@@ -0,0 +1,145 @@
+use common::{
+    card::CardType,
+    card_data::{CardData, Id},
+    deck::PartType,
+};
+use leptos::{
+    expect_context, view, For, IntoView, Memo, Show, Signal, SignalGet, SignalWith, View,
+};
+
+use crate::{deck::Deck, i18n::Text, ui::card_view::CardView};
+
+use super::{Tool, ToolHeading};
+
+/// Number of suggested cards shown.
+const LIMIT: usize = 10;
+
+/// How well a staple fits the playing cards of the deck.
+///
+/// Each playing copy of the same kind (monster, Extra deck monster, spell or trap) counts once,
+/// monsters additionally count once each for a shared race and attribute.
+fn relevance(deck: &common::deck::Deck, cards: &CardData, id: Id) -> usize {
+    let card_type = &cards[id].card_type;
+
+    deck.entries()
+        .map(|entry| {
+            let other = &cards[entry.id()].card_type;
+            let score = match (card_type, other) {
+                (
+                    CardType::Monster {
+                        race, attribute, ..
+                    },
+                    CardType::Monster {
+                        race: other_race,
+                        attribute: other_attribute,
+                        ..
+                    },
+                ) => {
+                    usize::from(card_type.is_extra_deck_monster() == other.is_extra_deck_monster())
+                        + usize::from(race == other_race)
+                        + usize::from(attribute == other_attribute)
+                }
+                (CardType::Spell(_), CardType::Spell(_))
+                | (CardType::Trap(_), CardType::Trap(_)) => 1,
+                _ => 0,
+            };
+            score * usize::from(entry.count(PartType::Playing))
+        })
+        .sum()
+}
+
+/// Staples which are not part of the deck yet, most relevant first.
+///
+/// Staples with equal relevance keep their order from the card data.
+fn suggestions(deck: &common::deck::Deck, cards: &CardData, limit: usize) -> Vec<Id> {
+    let mut result = cards
+        .staples()
+        .filter(|id| deck.total_count(*id) == 0)
+        .map(|id| (relevance(deck, cards, id), id))
+        .collect::<Vec<_>>();
+    result.sort_by(|(lhs, _), (rhs, _)| rhs.cmp(lhs));
+    result.into_iter().take(limit).map(|(_, id)| id).collect()
+}
+
+/// Suggests staples which are missing from the deck, ranked by how well their card type fits the
+/// cards already played.
+pub struct StapleSuggestions;
+
+impl Tool for StapleSuggestions {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "staple_suggestions"
+    }
+
+    fn name(&self) -> Text {
+        Text::StapleSuggestions
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+
+        let suggested = Memo::new(move |_| deck.with(|deck| suggestions(deck, &cards, LIMIT)));
+
+        view! {
+            <div>
+                <ToolHeading text=Text::StapleSuggestions />
+                <Show
+                    when=move || !suggested.with(Vec::is_empty)
+                    fallback=|| view! { <p>"All staples are part of the deck"</p> }
+                >
+                    <div class="card-list">
+                        <For
+                            each=move || suggested.get()
+                            key=|id| *id
+                            children=move |id| view! { <CardView id=id show_target=true /> }
+                        />
+                    </div>
+                </Show>
+            </div>
+        }
+        .into_view()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        card::{
+            test_util::{make_card, make_extra_deck_card},
+            TrapType,
+        },
+        card_data::CardDataStorage,
+    };
+
+    use super::*;
+
+    #[test]
+    fn ranking() {
+        let mut trap = make_card(3);
+        trap.card_type = CardType::Trap(TrapType::Normal);
+        let cards = vec![
+            make_card(1),
+            make_extra_deck_card(2),
+            trap,
+            make_card(4),
+            make_extra_deck_card(5),
+        ];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![1, 2, 3, 4, 5]));
+
+        // No cards to compare against, the staple order is kept
+        let deck = common::deck::Deck::default();
+        assert_eq!(
+            suggestions(&deck, &cards, LIMIT),
+            [0, 1, 2, 3, 4].map(Id::new)
+        );
+
+        let mut deck = common::deck::Deck::default();
+        deck.increment(Id::new(3), PartType::Playing, 1);
+        deck.increment(Id::new(4), PartType::Playing, 1);
+        assert_eq!(suggestions(&deck, &cards, LIMIT), [1, 0, 2].map(Id::new));
+        assert_eq!(suggestions(&deck, &cards, 1), [Id::new(1)]);
+    }
+}
@@ -0,0 +1,162 @@
+use common::{
+    card::{CardType, MonsterStats, MonsterType},
+    card_data::CardData,
+    deck::PartType,
+};
+use leptos::{
+    expect_context, html, view, For, IntoView, Memo, Show, Signal, SignalGet, SignalWith, View,
+};
+
+use crate::{deck::Deck, i18n::Text};
+
+use super::{Tool, ToolHeading};
+
+/// Highest level a monster can have.
+const MAX_LEVEL: usize = 12;
+
+/// Checks whether the main deck contains Tuners and non-Tuners with suitable levels for the
+/// Synchro monsters in the extra deck.
+///
+/// Only the generic "Tuner + non-Tuner" requirement is checked, specific material requirements
+/// from the card text are ignored. The results are advisory.
+pub struct SynchroCheck;
+
+/// Advisory notes for the Synchro monsters which can not be summoned with the levels in the deck.
+fn synchro_notes(deck: &common::deck::Deck, cards: &CardData) -> Vec<String> {
+    let mut tuners = [false; MAX_LEVEL + 1];
+    // Level sums which can be reached by combining non-Tuners
+    let mut non_tuner_sums = [false; MAX_LEVEL + 1];
+    non_tuner_sums[0] = true;
+    let mut synchros = vec![];
+
+    for entry in deck.entries() {
+        let copies = entry.count(PartType::Playing);
+        if copies == 0 {
+            continue;
+        }
+
+        let card = &cards[entry.id()];
+        let CardType::Monster {
+            stats: MonsterStats::Normal { level, .. },
+            ..
+        } = &card.card_type
+        else {
+            continue;
+        };
+        let level = usize::from(*level);
+
+        if card.card_type.monster_type() == Some(MonsterType::Synchro) {
+            synchros.push((card.name, level));
+        } else if !card.card_type.is_extra_deck_monster() && level <= MAX_LEVEL {
+            if card.card_type.is_tuner() {
+                tuners[level] = true;
+            } else {
+                // Each copy is used at most once, so the sums are updated from the highest one
+                // down, which does not build on sums including the same copy
+                for _ in 0..copies {
+                    for sum in (level..=MAX_LEVEL).rev() {
+                        if non_tuner_sums[sum - level] {
+                            non_tuner_sums[sum] = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut notes = vec![];
+    if synchros.is_empty() {
+        return notes;
+    }
+
+    if !tuners.contains(&true) {
+        notes.push("Main deck contains no Tuners".to_owned());
+        return notes;
+    }
+
+    synchros.sort_unstable();
+    for (name, level) in synchros {
+        let possible = (1..level.min(MAX_LEVEL + 1))
+            .any(|tuner_level| tuners[tuner_level] && non_tuner_sums[level - tuner_level]);
+
+        if !possible {
+            notes.push(format!(
+                "No matching Tuner and non-Tuner levels for {name} (Level {level})"
+            ));
+        }
+    }
+
+    notes
+}
+
+impl Tool for SynchroCheck {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "synchro_check"
+    }
+
+    fn name(&self) -> Text {
+        Text::SynchroMaterials
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+
+        let notes = Memo::new(move |_| deck.with(|deck| synchro_notes(deck, &cards)));
+
+        view! {
+            <Show when=move || !notes.with(Vec::is_empty)>
+                <div>
+                    <ToolHeading text=Text::SynchroMaterials />
+                    <ul class="advice">
+                        <For
+                            each=move || notes.get()
+                            key=Clone::clone
+                            children=move |note| { html::li().child(note) }
+                        />
+                    </ul>
+                </div>
+            </Show>
+        }
+        .into_view()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        card::{test_util::make_monster, CardType},
+        card_data::{CardDataStorage, Id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn non_tuner_copies() {
+        let mut tuner = make_monster(1, 1, None);
+        if let CardType::Monster { is_tuner, .. } = &mut tuner.card_type {
+            *is_tuner = true;
+        }
+        let mut synchro = make_monster(3, 4, Some(MonsterType::Synchro));
+        synchro.name = "Synchro".to_owned();
+        let cards = vec![tuner, make_monster(2, 1, None), synchro];
+        let cards = CardData::from(CardDataStorage::new(cards, vec![]));
+
+        let mut deck = common::deck::Deck::default();
+        deck.increment(Id::new(0), PartType::Playing, 1);
+        deck.increment(Id::new(1), PartType::Playing, 2);
+        deck.increment(Id::new(2), PartType::Playing, 1);
+
+        // A single Level 1 non-Tuner does not make Level 3
+        assert_eq!(
+            synchro_notes(&deck, &cards),
+            ["No matching Tuner and non-Tuner levels for Synchro (Level 4)"]
+        );
+
+        deck.increment(Id::new(1), PartType::Playing, 1);
+        assert!(synchro_notes(&deck, &cards).is_empty());
+    }
+}
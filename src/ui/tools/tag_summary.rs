@@ -0,0 +1,72 @@
+use common::{card::EffectTag, card_data::CardData, deck::PartType, deck_part::DeckPart};
+use leptos::{expect_context, view, IntoView, Memo, RwSignal, Signal, SignalGet, SignalWith, View};
+
+use crate::{
+    deck::Deck,
+    i18n::{Locale, Text},
+};
+
+use super::{Tool, ToolHeading};
+
+/// Counts the main deck cards for each effect tag.
+pub struct TagSummary;
+
+impl Tool for TagSummary {
+    fn init() -> Self {
+        Self
+    }
+
+    fn key(&self) -> &'static str {
+        "tag_summary"
+    }
+
+    fn name(&self) -> Text {
+        Text::EffectTags
+    }
+
+    fn view(&self, deck: Signal<Deck>) -> View {
+        let cards = expect_context::<CardData>();
+        let locale = expect_context::<RwSignal<Locale>>();
+
+        let counts = Memo::new(move |_| {
+            let mut counts = vec![0; EffectTag::iter().count()];
+
+            deck.with(|deck| {
+                for entry in deck.entries() {
+                    if deck.playing_part(entry.id(), &cards) != DeckPart::Main {
+                        continue;
+                    }
+
+                    let card = &cards[entry.id()];
+                    for (idx, tag) in EffectTag::iter().enumerate() {
+                        if card.effect_tags.has(tag) {
+                            counts[idx] += usize::from(entry.count(PartType::Playing));
+                        }
+                    }
+                }
+            });
+
+            counts
+        });
+
+        let rows = EffectTag::iter()
+            .enumerate()
+            .map(|(idx, tag)| {
+                view! {
+                    <tr>
+                        <th>{move || Text::EffectTag(tag).localize(locale.get())}</th>
+                        <td>{move || counts.with(|counts| counts[idx])}</td>
+                    </tr>
+                }
+            })
+            .collect::<Vec<_>>();
+
+        view! {
+            <div>
+                <ToolHeading text=Text::EffectTags />
+                <table class="tag-summary">{rows}</table>
+            </div>
+        }
+        .into_view()
+    }
+}
@@ -7,9 +7,13 @@ pub trait UndoRedoMessage: Copy {
     fn invert(self) -> Self;
 }
 
+/// Separator between entries of the same group in the text encoding.
+const GROUP_SEPARATOR: char = '&';
+
 #[derive(Debug, Clone)]
 pub struct UndoRedo<T> {
-    entries: Vec<T>,
+    /// Actions, with a flag whether they are part of the same group as the previous action
+    entries: Vec<(T, bool)>,
     offset: usize,
 }
 
@@ -24,44 +28,81 @@ impl<T> Default for UndoRedo<T> {
 
 impl<T: UndoRedoMessage> UndoRedo<T> {
     pub fn push_action(&mut self, action: T) {
+        self.push_group([action]);
+    }
+
+    /// Push multiple actions, which are undone and redone together by [`Self::undo_group`] and
+    /// [`Self::redo_group`].
+    pub fn push_group(&mut self, actions: impl IntoIterator<Item = T>) {
         if self.offset > 0 {
             self.entries.truncate(self.entries.len() - self.offset);
             self.offset = 0;
         }
 
-        self.entries.push(action);
+        let actions = actions.into_iter().enumerate();
+        self.entries
+            .extend(actions.map(|(idx, action)| (action, idx > 0)));
     }
 
     #[must_use]
     pub fn undo(&mut self) -> Option<T> {
-        let message = self.entries.iter().copied().rev().nth(self.offset);
+        let message = self.entries.iter().rev().nth(self.offset);
         if message.is_some() {
             self.offset += 1;
         }
-        message.map(UndoRedoMessage::invert)
+        message.map(|(message, _)| message.invert())
     }
 
     #[must_use]
     pub fn redo(&mut self) -> Option<T> {
         if self.offset > 0 {
             self.offset -= 1;
-            self.entries.iter().copied().rev().nth(self.offset)
+            self.entries
+                .iter()
+                .rev()
+                .nth(self.offset)
+                .map(|(message, _)| *message)
         } else {
             None
         }
     }
+
+    /// Undo the last group of actions, returning the messages in the order they should be applied.
+    #[must_use]
+    pub fn undo_group(&mut self) -> Vec<T> {
+        let mut result = Vec::new();
+        while let Some((_, continued)) = self.entries.iter().rev().nth(self.offset) {
+            let continued = *continued;
+            result.extend(self.undo());
+            if !continued {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Redo the next group of actions, returning the messages in the order they should be applied.
+    #[must_use]
+    pub fn redo_group(&mut self) -> Vec<T> {
+        let mut result = Vec::new();
+        result.extend(self.redo());
+        while self.offset > 0 && self.entries[self.entries.len() - self.offset].1 {
+            result.extend(self.redo());
+        }
+        result
+    }
 }
 
 impl<T: TextEncoding> TextEncoding for UndoRedo<T> {
     fn encode(&self, writer: &mut impl fmt::Write) -> fmt::Result {
         write!(writer, "{};", self.offset)?;
 
-        let mut entries = self.entries.iter();
-        if let Some(item) = entries.next() {
-            item.encode(writer)?;
-        }
-        for item in entries {
-            writer.write_char(',')?;
+        for (idx, (item, continued)) in self.entries.iter().enumerate() {
+            if *continued {
+                writer.write_char(GROUP_SEPARATOR)?;
+            } else if idx > 0 {
+                writer.write_char(',')?;
+            }
             item.encode(writer)?;
         }
 
@@ -72,11 +113,19 @@ impl<T: TextEncoding> TextEncoding for UndoRedo<T> {
         let (offset, text) = text.split_once(';')?;
 
         let offset = offset.parse().ok()?;
-        let entries = if text.is_empty() {
-            Vec::new()
-        } else {
-            text.split(',').map(T::decode).collect::<Option<_>>()?
-        };
+        let mut entries = Vec::new();
+        if !text.is_empty() {
+            for group in text.split(',') {
+                for (idx, item) in group.split(GROUP_SEPARATOR).enumerate() {
+                    entries.push((T::decode(item)?, idx > 0));
+                }
+            }
+        }
+
+        // Can not undo more actions than there are
+        if offset > entries.len() {
+            return None;
+        }
 
         Some(Self { entries, offset })
     }
@@ -84,6 +133,8 @@ impl<T: TextEncoding> TextEncoding for UndoRedo<T> {
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[derive(Debug, Clone, Copy, PartialEq)]
@@ -208,5 +259,120 @@ mod test {
 
         let mut ur = UR::decode(&ur.encode_string()).unwrap();
         assert_eq!(ur.redo(), Some(TestMessage::Apply(1)));
+
+        assert!(UR::decode("3;a0,a1").is_none());
+    }
+
+    #[test]
+    fn groups() {
+        let mut ur = UR::default();
+        ur.push_action(TestMessage::Apply(0));
+        ur.push_group([TestMessage::Apply(1), TestMessage::Apply(2)]);
+
+        let mut ur = UR::decode(&ur.encode_string()).unwrap();
+        assert_eq!(
+            ur.undo_group(),
+            &[TestMessage::Revert(2), TestMessage::Revert(1)]
+        );
+        assert_eq!(ur.undo_group(), &[TestMessage::Revert(0)]);
+        assert!(ur.undo_group().is_empty());
+
+        assert_eq!(ur.redo_group(), &[TestMessage::Apply(0)]);
+        assert_eq!(
+            ur.redo_group(),
+            &[TestMessage::Apply(1), TestMessage::Apply(2)]
+        );
+        assert!(ur.redo_group().is_empty());
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Operation {
+        /// Push a group with the given number of actions
+        Push(usize),
+        Undo,
+        Redo,
+        UndoGroup,
+        RedoGroup,
+    }
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        prop_oneof![
+            (1..4_usize).prop_map(Operation::Push),
+            Just(Operation::Undo),
+            Just(Operation::Redo),
+            Just(Operation::UndoGroup),
+            Just(Operation::RedoGroup),
+        ]
+    }
+
+    /// Applied actions, which have to be reverted in reverse order.
+    fn apply(state: &mut Vec<usize>, messages: impl IntoIterator<Item = TestMessage>) {
+        for message in messages {
+            match message {
+                TestMessage::Apply(value) => state.push(value),
+                TestMessage::Revert(value) => assert_eq!(state.pop(), Some(value)),
+            }
+        }
+    }
+
+    fn values(entries: &[(TestMessage, bool)]) -> Vec<usize> {
+        entries
+            .iter()
+            .map(|(message, _)| match message {
+                TestMessage::Apply(value) | TestMessage::Revert(value) => *value,
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn random_operations(operations in prop::collection::vec(operation(), 0..48)) {
+            let mut ur = UR::default();
+            let mut state = Vec::new();
+            let mut next_value = 0;
+
+            for operation in operations {
+                match operation {
+                    Operation::Push(len) => {
+                        let values = next_value..next_value + len;
+                        next_value += len;
+                        apply(&mut state, values.clone().map(TestMessage::Apply));
+                        ur.push_group(values.map(TestMessage::Apply));
+                    }
+                    Operation::Undo => apply(&mut state, ur.undo()),
+                    Operation::Redo => apply(&mut state, ur.redo()),
+                    Operation::UndoGroup => apply(&mut state, ur.undo_group()),
+                    Operation::RedoGroup => apply(&mut state, ur.redo_group()),
+                }
+
+                // The state matches the actions which are not undone
+                let applied = values(&ur.entries[..ur.entries.len() - ur.offset]);
+                prop_assert_eq!(&state, &applied);
+
+                let decoded = UR::decode(&ur.encode_string()).unwrap();
+                prop_assert_eq!(&decoded.entries, &ur.entries);
+                prop_assert_eq!(decoded.offset, ur.offset);
+            }
+
+            // Undoing everything and redoing it again applies all actions
+            let all = values(&ur.entries);
+            loop {
+                let messages = ur.undo_group();
+                if messages.is_empty() {
+                    break;
+                }
+                apply(&mut state, messages);
+            }
+            prop_assert!(state.is_empty());
+
+            loop {
+                let messages = ur.redo_group();
+                if messages.is_empty() {
+                    break;
+                }
+                apply(&mut state, messages);
+            }
+            prop_assert_eq!(state, all);
+        }
     }
 }